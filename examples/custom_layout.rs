@@ -0,0 +1,226 @@
+use std::error::Error;
+use wgpu::CompositeAlphaMode;
+use wgpu_glyph::ab_glyph::{point, Font, Rect, ScaleFont};
+use wgpu_glyph::{
+    ab_glyph, GlyphBrushBuilder, GlyphPositioner, Section, SectionGeometry,
+    SectionGlyph, SectionText, Text, ToSectionText,
+};
+
+/// A layout placing every character in the cell of a grid, revealing only
+/// the first `visible` characters, like a typewriter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Typewriter {
+    columns: usize,
+    visible: usize,
+}
+
+impl GlyphPositioner for Typewriter {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let (left, top) = geometry.screen_position;
+        let mut glyphs = Vec::new();
+        let mut index = 0;
+
+        for (section_index, section) in sections.iter().enumerate() {
+            let SectionText {
+                text,
+                scale,
+                font_id,
+            } = section.to_section_text();
+
+            let font = fonts[font_id.0].as_scaled(scale);
+            let width = font.h_advance(font.glyph_id('M'));
+            let height = font.height() + font.line_gap();
+
+            for (byte_index, character) in text.char_indices() {
+                if index == self.visible {
+                    return glyphs;
+                }
+
+                let column = (index % self.columns) as f32;
+                let row = (index / self.columns) as f32;
+
+                glyphs.push(SectionGlyph {
+                    section_index,
+                    byte_index,
+                    glyph: font.glyph_id(character).with_scale_and_position(
+                        scale,
+                        point(
+                            left + column * width,
+                            top + font.ascent() + row * height,
+                        ),
+                    ),
+                    font_id,
+                });
+
+                index += 1;
+            }
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        let (left, top) = geometry.screen_position;
+        let (width, height) = geometry.bounds;
+
+        Rect {
+            min: point(left, top),
+            max: point(left + width, top + height),
+        }
+    }
+}
+
+const TEXT: &str = "Custom layouts position the glyphs of the brush, \
+                    one character at a time.";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    // Open window and create a surface
+    let event_loop = winit::event_loop::EventLoop::new()?;
+
+    let window = winit::window::WindowBuilder::new()
+        .with_resizable(false)
+        .build(&event_loop)
+        .unwrap();
+
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let surface = instance.create_surface(&window)?;
+
+    // Initialize GPU
+    let (device, queue) = futures::executor::block_on(async {
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .expect("Request adapter");
+
+        adapter
+            .request_device(&wgpu::DeviceDescriptor::default())
+            .await
+            .expect("Request device")
+    });
+
+    // Create staging belt
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+
+    // Prepare swap chain
+    let render_format = wgpu::TextureFormat::Bgra8UnormSrgb;
+    let size = window.inner_size();
+
+    surface.configure(
+        &device,
+        &wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: render_format,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::AutoVsync,
+            alpha_mode: CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        },
+    );
+
+    // Prepare glyph_brush
+    let inconsolata = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "Inconsolata-Regular.ttf"
+    ))?;
+
+    let mut glyph_brush = GlyphBrushBuilder::using_font(inconsolata)
+        .build(&device, render_format);
+
+    let mut typewriter = Typewriter {
+        columns: 24,
+        visible: 0,
+    };
+
+    // Render loop
+    let window = &window;
+    window.request_redraw();
+
+    event_loop
+        .run(move |event, elwt| match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => elwt.exit(),
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::RedrawRequested,
+                ..
+            } => {
+                // Get a command encoder for the current frame
+                let mut encoder = device.create_command_encoder(
+                    &wgpu::CommandEncoderDescriptor {
+                        label: Some("Redraw"),
+                    },
+                );
+
+                // Get the next frame
+                let frame =
+                    surface.get_current_texture().expect("Get next frame");
+                let view = &frame
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+
+                // Type one more character every frame
+                typewriter.visible = (typewriter.visible + 1).min(TEXT.len());
+
+                glyph_brush.queue_custom_layout(
+                    Section {
+                        screen_position: (30.0, 30.0),
+                        bounds: (size.width as f32, size.height as f32),
+                        text: vec![Text::new(TEXT)
+                            .with_color([1.0, 1.0, 1.0, 1.0])
+                            .with_scale(30.0)],
+                        ..Section::default()
+                    },
+                    &typewriter,
+                );
+
+                // Clear the frame and draw the text in the same pass
+                glyph_brush.clear_next_draw(wgpu::Color {
+                    r: 0.1,
+                    g: 0.1,
+                    b: 0.1,
+                    a: 1.0,
+                });
+
+                glyph_brush
+                    .draw_queued(
+                        &device,
+                        &mut staging_belt,
+                        &mut encoder,
+                        view,
+                        size.width,
+                        size.height,
+                    )
+                    .expect("Draw queued");
+
+                // Submit the work!
+                staging_belt.finish();
+                queue.submit(Some(encoder.finish()));
+                frame.present();
+                // Recall unused staging buffers
+                staging_belt.recall();
+
+                if typewriter.visible < TEXT.len() {
+                    window.request_redraw();
+                }
+            }
+            _ => {}
+        })
+        .map_err(Into::into)
+}
@@ -0,0 +1,127 @@
+use crate::ab_glyph::{Font, Rect, ScaleFont};
+use crate::{Extra, GlyphStyle, SectionGlyph};
+
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+/// An animation of a section, evaluated when it is drawn.
+///
+/// Queue animated sections with
+/// [`GlyphBrush::queue_animated`](struct.GlyphBrush.html#method.queue_animated)
+/// and set the time they are evaluated at with
+/// [`GlyphBrush::set_animation_time`](struct.GlyphBrush.html#method.set_animation_time).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Animation {
+    /// Shows the section for `visible` seconds, then hides it for `hidden`
+    /// seconds, from a time of zero.
+    Blink { visible: f32, hidden: f32 },
+    /// Scrolls the section horizontally within its bounds, at `speed` pixels
+    /// per second to the left, or to the right if negative.
+    ///
+    /// The section is laid out on lines as wide as needed and drawn again
+    /// `gap` pixels after its end, so it wraps around.
+    Marquee { speed: f32, gap: f32 },
+}
+
+impl Hash for Animation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Animation::Blink { visible, hidden } => {
+                (0, visible.to_bits(), hidden.to_bits()).hash(state);
+            }
+            Animation::Marquee { speed, gap } => {
+                (1, speed.to_bits(), gap.to_bits()).hash(state);
+            }
+        }
+    }
+}
+
+/// Returns the width of laid out glyphs, from the left of the leftmost one to
+/// the advance of the rightmost one.
+pub(crate) fn width<F: Font>(fonts: &[F], glyphs: &[SectionGlyph]) -> f32 {
+    let (left, right) = glyphs.iter().fold(
+        (f32::INFINITY, f32::NEG_INFINITY),
+        |(left, right), glyph| {
+            let advance = fonts[glyph.font_id.0]
+                .as_scaled(glyph.glyph.scale)
+                .h_advance(glyph.glyph.id);
+
+            (
+                left.min(glyph.glyph.position.x),
+                right.max(glyph.glyph.position.x + advance),
+            )
+        },
+    );
+
+    (right - left).max(0.0)
+}
+
+/// A section queued with an animation, laid out once and drawn with its
+/// animation evaluated at the time of the draw.
+pub(crate) struct Animated {
+    pub(crate) glyphs: Vec<SectionGlyph>,
+    pub(crate) extra: Vec<Extra>,
+    pub(crate) bounds: Rect,
+    /// The width of the laid out text, scrolled by marquees.
+    pub(crate) width: f32,
+    pub(crate) animation: Animation,
+    pub(crate) style: GlyphStyle,
+}
+
+impl Animated {
+    /// Returns the horizontal offsets the glyphs are drawn at, once per
+    /// offset, at the given time. Hidden sections have no offsets.
+    pub(crate) fn offsets(&self, time: Duration) -> Vec<f32> {
+        let time = time.as_secs_f64();
+
+        match self.animation {
+            Animation::Blink { visible, hidden } => {
+                let period = f64::from(visible) + f64::from(hidden);
+
+                if period <= 0.0 || time.rem_euclid(period) < f64::from(visible)
+                {
+                    vec![0.0]
+                } else {
+                    Vec::new()
+                }
+            }
+            Animation::Marquee { speed, gap } => {
+                let period = self.width + gap;
+
+                if period <= 0.0 {
+                    return vec![0.0];
+                }
+
+                let shift = (-time * f64::from(speed))
+                    .rem_euclid(f64::from(period))
+                    as f32
+                    - period;
+
+                // A copy is drawn for every period within the bounds, plus
+                // the one scrolling in
+                let copies = if self.bounds.width().is_finite() {
+                    (self.bounds.width() / period).ceil() as usize + 2
+                } else {
+                    2
+                };
+
+                (0..copies)
+                    .map(|copy| shift + copy as f32 * period)
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the glyphs of the section drawn at the given offsets.
+    pub(crate) fn glyphs(&self, offsets: &[f32]) -> Vec<SectionGlyph> {
+        offsets
+            .iter()
+            .flat_map(|offset| {
+                self.glyphs.iter().cloned().map(move |mut glyph| {
+                    glyph.glyph.position.x += offset;
+                    glyph
+                })
+            })
+            .collect()
+    }
+}
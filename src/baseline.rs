@@ -0,0 +1,131 @@
+use glyph_brush::ab_glyph::{Font, Rect};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that shifts the
+/// baseline of some texts of a section, e.g. for superscripts, subscripts or
+/// aligning inline icons.
+///
+/// The section is laid out by the inner layout as if no text was shifted, so
+/// line breaks and line heights stay the same. The glyphs of the shifted texts
+/// are then moved vertically.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineShift<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    shifts: Vec<(usize, f32)>,
+}
+
+impl<L> BaselineShift<L> {
+    /// Wraps the given layout, without shifting any text.
+    pub fn new(layout: L) -> Self {
+        BaselineShift {
+            layout,
+            shifts: Vec::new(),
+        }
+    }
+
+    /// Shifts the baseline of the text with the given index in the section
+    /// by the given amount of pixels. Positive values raise the text, like a
+    /// superscript, and negative values lower it, like a subscript.
+    pub fn text_shift(mut self, index: usize, shift: f32) -> Self {
+        self.shifts.retain(|(i, _)| *i != index);
+        self.shifts.push((index, shift));
+        self
+    }
+
+    /// Returns the baseline shift of the text with the given index.
+    pub fn shift_of(&self, index: usize) -> f32 {
+        self.shifts
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map_or(0.0, |(_, shift)| *shift)
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for BaselineShift<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs =
+            self.layout.calculate_glyphs(fonts, geometry, sections);
+
+        if self.shifts.is_empty() {
+            return glyphs;
+        }
+
+        for glyph in &mut glyphs {
+            glyph.glyph.position.y -= self.shift_of(glyph.section_index);
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for BaselineShift<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+
+        for (index, shift) in &self.shifts {
+            (index, shift.to_bits()).hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::{FontRef, PxScale};
+    use glyph_brush::{FontId, SectionText};
+
+    #[test]
+    fn shifts_the_glyphs_of_shifted_texts() {
+        let font = FontRef::try_from_slice(include_bytes!(
+            "../examples/Inconsolata-Regular.ttf"
+        ))
+        .unwrap();
+
+        let geometry = SectionGeometry::default();
+        let texts = ["Ab", "c", "d"].map(|text| SectionText {
+            text,
+            scale: PxScale::from(20.0),
+            font_id: FontId(0),
+        });
+
+        let layout = Layout::default();
+        let shifted = BaselineShift::new(layout)
+            .text_shift(1, 5.0)
+            .text_shift(2, -3.0);
+
+        let expected = layout.calculate_glyphs(&[&font], &geometry, &texts);
+        let glyphs = shifted.calculate_glyphs(&[&font], &geometry, &texts);
+
+        assert_eq!(glyphs.len(), expected.len());
+
+        for (glyph, expected) in glyphs.iter().zip(&expected) {
+            let shift = [0.0, 5.0, -3.0][glyph.section_index];
+
+            assert_eq!(glyph.glyph.position.x, expected.glyph.position.x);
+            assert_eq!(
+                glyph.glyph.position.y,
+                expected.glyph.position.y - shift
+            );
+        }
+    }
+}
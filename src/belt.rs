@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The staging belts owned by a `GlyphBrush`.
+///
+/// Every draw uses a belt that is not in use by the GPU, finishing it at the
+/// end of the draw. On the next draw, the command encoder of the previous one
+/// has been submitted, so its belt is recalled once the submitted work is
+/// done and becomes available again.
+#[derive(Debug)]
+pub(crate) struct Belts {
+    chunk_size: wgpu::BufferAddress,
+    available: Arc<Mutex<Vec<wgpu::util::StagingBelt>>>,
+    submitted: Option<wgpu::util::StagingBelt>,
+}
+
+impl Belts {
+    pub(crate) fn new(chunk_size: wgpu::BufferAddress) -> Self {
+        Belts {
+            chunk_size,
+            available: Arc::new(Mutex::new(Vec::new())),
+            submitted: None,
+        }
+    }
+
+    /// Returns a staging belt that is not in use by the GPU.
+    pub(crate) fn acquire(
+        &mut self,
+        queue: &wgpu::Queue,
+    ) -> wgpu::util::StagingBelt {
+        if let Some(mut belt) = self.submitted.take() {
+            let available = Arc::clone(&self.available);
+
+            queue.on_submitted_work_done(move || {
+                belt.recall();
+
+                available
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(belt);
+            });
+        }
+
+        self.available
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_else(|| wgpu::util::StagingBelt::new(self.chunk_size))
+    }
+
+    /// Finishes a staging belt used by a draw, recalling it on the next one.
+    pub(crate) fn release(&mut self, mut belt: wgpu::util::StagingBelt) {
+        belt.finish();
+
+        self.submitted = Some(belt);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::ab_glyph::{point, Rect};
+use crate::Instance;
+
+use std::collections::HashMap;
+
+/// A pre-baked bitmap font in the AngelCode BMFont text format (`.fnt`).
+///
+/// The glyphs of a bitmap font are copied from its page images instead of
+/// being rasterized. The pages are loaded by the application and set as
+/// atlases of the brush with
+/// [`GlyphBrush::set_atlas`](struct.GlyphBrush.html#method.set_atlas), the
+/// first page at the [`atlas`](#method.atlas) index of the font and the
+/// following ones at the next indices.
+///
+/// Queue its text with
+/// [`GlyphBrush::queue_bitmap_text`](struct.GlyphBrush.html#method.queue_bitmap_text).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitmapFont {
+    line_height: f32,
+    base: f32,
+    page_size: (f32, f32),
+    pages: Vec<String>,
+    glyphs: HashMap<char, BitmapGlyph>,
+    kernings: HashMap<(char, char), f32>,
+    atlas: u32,
+}
+
+/// A glyph of a [`BitmapFont`], in the pixels of its page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BitmapGlyph {
+    rect: Rect,
+    offset: (f32, f32),
+    advance: f32,
+    page: u32,
+}
+
+/// A text drawn with a [`BitmapFont`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitmapText<'a> {
+    /// The text to draw. Line breaks start new lines.
+    pub text: &'a str,
+    /// The position of the top-left corner of the text, in pixels.
+    pub screen_position: (f32, f32),
+    /// The scale of the text, relative to the size the font was baked at.
+    pub scale: f32,
+    /// The color multiplied by the pages of the font.
+    pub color: [f32; 4],
+    /// The depth of the text.
+    pub z: f32,
+}
+
+impl<'a> BitmapText<'a> {
+    /// Creates a [`BitmapText`] at the origin, at the size the font was baked
+    /// at and without tinting its pages.
+    pub fn new(text: &'a str) -> Self {
+        BitmapText {
+            text,
+            screen_position: (0.0, 0.0),
+            scale: 1.0,
+            color: [1.0; 4],
+            z: 0.0,
+        }
+    }
+
+    /// Sets the position of the top-left corner of the text.
+    pub fn with_screen_position<P: Into<(f32, f32)>>(
+        mut self,
+        position: P,
+    ) -> Self {
+        self.screen_position = position.into();
+        self
+    }
+
+    /// Sets the scale of the text.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the color of the text.
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the depth of the text.
+    pub fn with_z(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+}
+
+impl BitmapFont {
+    /// Parses a bitmap font in the BMFont text format, with its first page
+    /// at the atlas `1`.
+    pub fn parse(source: &str) -> Result<BitmapFont, String> {
+        let mut font = BitmapFont {
+            line_height: 0.0,
+            base: 0.0,
+            page_size: (0.0, 0.0),
+            pages: Vec::new(),
+            glyphs: HashMap::new(),
+            kernings: HashMap::new(),
+            atlas: 1,
+        };
+
+        for (number, line) in source.lines().enumerate() {
+            let mut tokens = tokenize(line);
+
+            let Some(tag) = tokens.next() else {
+                continue;
+            };
+
+            let attributes: HashMap<&str, &str> =
+                tokens.filter_map(|token| token.split_once('=')).collect();
+
+            let attribute = |name: &str| -> Result<f32, String> {
+                attributes
+                    .get(name)
+                    .and_then(|value| value.parse().ok())
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid `{name}` of `{tag}` on line {}",
+                            number + 1
+                        )
+                    })
+            };
+
+            match tag {
+                "common" => {
+                    font.line_height = attribute("lineHeight")?;
+                    font.base = attribute("base")?;
+                    font.page_size =
+                        (attribute("scaleW")?, attribute("scaleH")?);
+                }
+                "page" => {
+                    let id = attribute("id")? as usize;
+                    let file = attributes
+                        .get("file")
+                        .map(|file| file.trim_matches('"').to_owned())
+                        .unwrap_or_default();
+
+                    if font.pages.len() <= id {
+                        font.pages.resize(id + 1, String::new());
+                    }
+
+                    font.pages[id] = file;
+                }
+                "char" => {
+                    let Some(character) =
+                        char::from_u32(attribute("id")? as u32)
+                    else {
+                        continue;
+                    };
+
+                    let (x, y) = (attribute("x")?, attribute("y")?);
+
+                    let _ = font.glyphs.insert(
+                        character,
+                        BitmapGlyph {
+                            rect: Rect {
+                                min: point(x, y),
+                                max: point(
+                                    x + attribute("width")?,
+                                    y + attribute("height")?,
+                                ),
+                            },
+                            offset: (
+                                attribute("xoffset")?,
+                                attribute("yoffset")?,
+                            ),
+                            advance: attribute("xadvance")?,
+                            page: attribute("page")? as u32,
+                        },
+                    );
+                }
+                "kerning" => {
+                    let first = char::from_u32(attribute("first")? as u32);
+                    let second = char::from_u32(attribute("second")? as u32);
+
+                    if let (Some(first), Some(second)) = (first, second) {
+                        let _ = font
+                            .kernings
+                            .insert((first, second), attribute("amount")?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if font.page_size.0 <= 0.0 || font.page_size.1 <= 0.0 {
+            return Err(String::from("Missing `common` line"));
+        }
+
+        Ok(font)
+    }
+
+    /// Sets the index of the atlas of the first page of the font.
+    pub fn with_atlas(mut self, atlas: u32) -> Self {
+        self.atlas = atlas;
+        self
+    }
+
+    /// Returns the index of the atlas of the first page of the font.
+    pub fn atlas(&self) -> u32 {
+        self.atlas
+    }
+
+    /// Returns the file names of the page images of the font, in order.
+    pub fn pages(&self) -> &[String] {
+        &self.pages
+    }
+
+    /// Returns the distance between two lines of text, in pixels at the size
+    /// the font was baked at.
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    /// Returns the distance from the top of a line to its baseline, in
+    /// pixels at the size the font was baked at.
+    pub fn base(&self) -> f32 {
+        self.base
+    }
+
+    /// Returns the instances of the glyphs of the given text.
+    ///
+    /// Characters missing from the font are skipped.
+    pub(crate) fn instances(&self, text: &BitmapText<'_>) -> Vec<Instance> {
+        let (left, top) = text.screen_position;
+        let scale = text.scale;
+
+        let mut instances = Vec::new();
+        let mut caret = point(left, top);
+        let mut previous = None;
+
+        for character in text.text.chars() {
+            if character == '\n' {
+                caret = point(left, caret.y + self.line_height * scale);
+                previous = None;
+                continue;
+            }
+
+            let Some(glyph) = self.glyphs.get(&character) else {
+                continue;
+            };
+
+            if let Some(kerning) = previous
+                .and_then(|previous| self.kernings.get(&(previous, character)))
+            {
+                caret.x += kerning * scale;
+            }
+
+            let x = caret.x + glyph.offset.0 * scale;
+            let y = caret.y + glyph.offset.1 * scale;
+
+            if glyph.rect.width() > 0.0 && glyph.rect.height() > 0.0 {
+                instances.push(Instance::new(
+                    Rect {
+                        min: point(x, y),
+                        max: point(
+                            x + glyph.rect.width() * scale,
+                            y + glyph.rect.height() * scale,
+                        ),
+                    },
+                    Rect {
+                        min: point(
+                            glyph.rect.min.x / self.page_size.0,
+                            glyph.rect.min.y / self.page_size.1,
+                        ),
+                        max: point(
+                            glyph.rect.max.x / self.page_size.0,
+                            glyph.rect.max.y / self.page_size.1,
+                        ),
+                    },
+                    text.color,
+                    text.z,
+                    self.atlas + glyph.page,
+                ));
+            }
+
+            caret.x += glyph.advance * scale;
+            previous = Some(character);
+        }
+
+        instances
+    }
+}
+
+/// Splits a line of a BMFont file by whitespace outside of quotes.
+fn tokenize(line: &str) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+
+    line.split(move |c: char| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+
+        c.is_whitespace() && !quoted
+    })
+    .filter(|token| !token.is_empty())
+}
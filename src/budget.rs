@@ -0,0 +1,109 @@
+use crate::Instance;
+
+use std::collections::HashSet;
+
+/// The memory used by a `GlyphBrush`, in bytes.
+///
+/// Obtained with
+/// [`GlyphBrush::memory_usage`](struct.GlyphBrush.html#method.memory_usage).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// The GPU memory used by the glyph cache, the textures of oversized and
+    /// color glyphs, and their upload buffers.
+    ///
+    /// A shared cache is counted by every brush using it.
+    pub atlas: u64,
+    /// The GPU memory used by the instance buffers of every frame in flight.
+    pub instances: u64,
+    /// The GPU memory used by the uniform buffers, like the transforms and
+    /// the palette.
+    pub uniforms: u64,
+    /// The CPU memory used by the caches of the brush, like the copies of
+    /// its textures and the glyphs and layouts kept between frames.
+    ///
+    /// This is an estimate, as it does not account for the allocator
+    /// overhead nor the sections cached by the positioning cache.
+    pub caches: u64,
+}
+
+impl MemoryUsage {
+    /// Returns the GPU memory used by the brush.
+    pub fn gpu(&self) -> u64 {
+        self.atlas + self.instances + self.uniforms
+    }
+
+    /// Returns the GPU and CPU memory used by the brush.
+    pub fn total(&self) -> u64 {
+        self.gpu() + self.caches
+    }
+}
+
+/// The memory used by a `GlyphBrush` when it exceeds its budget.
+///
+/// Reported to the callback set with
+/// [`GlyphBrush::on_memory_pressure`](struct.GlyphBrush.html#method.on_memory_pressure).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryPressure {
+    /// The amount of bytes of GPU memory used by the brush. See
+    /// [`MemoryUsage::gpu`].
+    pub usage: u64,
+    /// The memory budget of the brush, in bytes.
+    pub budget: u64,
+}
+
+/// The smallest size the glyph cache is shrunk to.
+const MIN_CACHE_SIZE: u32 = 64;
+
+/// Returns the size to shrink a glyph cache of the given dimensions to, so
+/// that it fits the budget along with the given bytes of other GPU memory
+/// while still holding the glyphs of the last frame, if it is smaller.
+pub(crate) fn shrunk_cache_size(
+    glyphs: &[Instance],
+    (width, height): (u32, u32),
+    other_bytes: u64,
+    budget: u64,
+) -> Option<(u32, u32)> {
+    let regions: HashSet<[u32; 4]> = glyphs
+        .iter()
+        .map(|glyph| {
+            let rect = glyph.tex_rect();
+
+            [
+                (rect.min.x * width as f32).round() as u32,
+                (rect.min.y * height as f32).round() as u32,
+                (rect.max.x * width as f32).round() as u32,
+                (rect.max.y * height as f32).round() as u32,
+            ]
+        })
+        .collect();
+
+    let used: u64 = regions
+        .iter()
+        .map(|[left, top, right, bottom]| {
+            u64::from(left.abs_diff(*right)) * u64::from(top.abs_diff(*bottom))
+        })
+        .sum();
+
+    let (mut new_width, mut new_height) = (width, height);
+
+    while u64::from(new_width) * u64::from(new_height) + other_bytes > budget {
+        let (halved_width, halved_height) = if new_width >= new_height {
+            (new_width / 2, new_height)
+        } else {
+            (new_width, new_height / 2)
+        };
+
+        // Keep room for the glyphs in use, as the draw cache does not pack
+        // them perfectly
+        if halved_width.min(halved_height) < MIN_CACHE_SIZE
+            || u64::from(halved_width) * u64::from(halved_height) < used * 2
+        {
+            break;
+        }
+
+        (new_width, new_height) = (halved_width, halved_height);
+    }
+
+    ((new_width, new_height) != (width, height))
+        .then_some((new_width, new_height))
+}
@@ -5,12 +5,26 @@ use glyph_brush::delegate_glyph_brush_builder_fns;
 use glyph_brush::DefaultSectionHasher;
 
 use super::GlyphBrush;
+use crate::pipeline::{InstanceMode, Settings};
+use crate::{DepthOrder, OnCacheFull, SharedCache};
+use std::borrow::Cow;
 
 /// Builder for a [`GlyphBrush`](struct.GlyphBrush.html).
+///
+/// # Memory usage
+///
+/// By default, the brush keeps the layout of the sections queued in the
+/// last frame and the glyphs of its last draw, so unchanged sections are not
+/// laid out again and unchanged frames are not uploaded again. Memory
+/// constrained targets can trade CPU time for memory with
+/// [`cache_glyph_positioning`](#method.cache_glyph_positioning), which lays
+/// out every section on every draw, and
+/// [`cache_redraws`](#method.cache_redraws), which uploads the glyphs on
+/// every draw instead of keeping a copy of them, unless a memory budget is
+/// set.
 pub struct GlyphBrushBuilder<D, F, H = DefaultSectionHasher> {
     inner: glyph_brush::GlyphBrushBuilder<F, H>,
-    texture_filter_method: wgpu::FilterMode,
-    multisample_state: wgpu::MultisampleState,
+    settings: Settings,
     depth: D,
 }
 
@@ -20,8 +34,7 @@ impl<F, H> From<glyph_brush::GlyphBrushBuilder<F, H>>
     fn from(inner: glyph_brush::GlyphBrushBuilder<F, H>) -> Self {
         GlyphBrushBuilder {
             inner,
-            texture_filter_method: wgpu::FilterMode::Linear,
-            multisample_state: wgpu::MultisampleState::default(),
+            settings: Settings::default(),
             depth: (),
         }
     }
@@ -38,8 +51,7 @@ impl GlyphBrushBuilder<(), ()> {
     pub fn using_fonts<F: Font>(fonts: Vec<F>) -> GlyphBrushBuilder<(), F> {
         GlyphBrushBuilder {
             inner: glyph_brush::GlyphBrushBuilder::using_fonts(fonts),
-            texture_filter_method: wgpu::FilterMode::Linear,
-            multisample_state: wgpu::MultisampleState::default(),
+            settings: Settings::default(),
             depth: (),
         }
     }
@@ -70,7 +82,7 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
         mut self,
         filter_method: wgpu::FilterMode,
     ) -> Self {
-        self.texture_filter_method = filter_method;
+        self.settings.filter_mode = filter_method;
         self
     }
 
@@ -79,7 +91,496 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
         mut self,
         multisample_state: wgpu::MultisampleState,
     ) -> Self {
-        self.multisample_state = multisample_state;
+        self.settings.multisample = multisample_state;
+        self
+    }
+
+    /// Sets the color channels of the render target the text is written to.
+    ///
+    /// E.g. [`wgpu::ColorWrites::ALPHA`] renders text only into the alpha of
+    /// a mask texture, like the ones used to cut out or blur a UI, without a
+    /// custom pipeline.
+    ///
+    /// Defaults to [`wgpu::ColorWrites::ALL`].
+    pub fn write_mask(mut self, write_mask: wgpu::ColorWrites) -> Self {
+        self.settings.write_mask = write_mask;
+        self
+    }
+
+    /// Sets the winding order of the front faces of the glyph quads.
+    ///
+    /// Glyph quads are wound counter-clockwise on screen, so they are back
+    /// faces with the default front face. Set it to
+    /// [`wgpu::FrontFace::Ccw`] before culling back faces to hide the text
+    /// mirrored by its transform, e.g. with a negative scale.
+    ///
+    /// Defaults to [`wgpu::FrontFace::Cw`].
+    pub fn front_face(mut self, front_face: wgpu::FrontFace) -> Self {
+        self.settings.primitive.front_face = front_face;
+        self
+    }
+
+    /// Sets the faces of the glyph quads that are culled, e.g. to hide text
+    /// seen from behind in 3D.
+    ///
+    /// Defaults to `None`, so no glyph is culled.
+    pub fn cull_mode(mut self, cull_mode: Option<wgpu::Face>) -> Self {
+        self.settings.primitive.cull_mode = cull_mode;
+        self
+    }
+
+    /// Uses alpha-to-coverage instead of blending to antialias text drawn
+    /// onto multi-sampled targets.
+    ///
+    /// The alpha of the text masks the samples it covers, so it cooperates
+    /// with depth testing regardless of the drawing order, instead of
+    /// showing sorting artifacts where blended text overlaps. Text drawn
+    /// onto single-sampled targets keeps blending.
+    ///
+    /// Defaults to `false`. Also enabled by the `alpha_to_coverage_enabled`
+    /// field of the [`multisample_state`](#method.multisample_state).
+    pub fn alpha_to_coverage(mut self, enabled: bool) -> Self {
+        self.settings.multisample.alpha_to_coverage_enabled = enabled;
+        self
+    }
+
+    /// Sets the way glyph instances are fed to the vertex shader.
+    ///
+    /// By default, instances are bound as a vertex buffer. See
+    /// [`InstanceMode`](enum.InstanceMode.html).
+    pub fn instance_mode(mut self, instance_mode: InstanceMode) -> Self {
+        self.settings.instance_mode = instance_mode;
+        self
+    }
+
+    /// Sets the WGSL source of the shader of the render pipeline.
+    ///
+    /// The shader must provide `vs_main` and `fs_main` entry points
+    /// compatible with the default [`SHADER`](constant.SHADER.html) (or
+    /// [`STORAGE_SHADER`](constant.STORAGE_SHADER.html) and
+    /// [`COMPACT_SHADER`](constant.COMPACT_SHADER.html), depending on the
+    /// [`instance_mode`](#method.instance_mode)), which can be used as a
+    /// starting point. Drawing onto single-channel targets requires their
+    /// `fs_mask` entry point too.
+    ///
+    /// The pipeline constants of the options of the builder, like
+    /// [`srgb_colors`](#method.srgb_colors), are only set if the shader
+    /// declares them, while the ones set with
+    /// [`pipeline_constant`](#method.pipeline_constant) must be declared.
+    pub fn shader(mut self, source: impl Into<Cow<'static, str>>) -> Self {
+        self.settings.shader = Some(source.into());
+        self
+    }
+
+    /// Loads the WGSL source of the shader of the render pipeline from the
+    /// given path, reloading it whenever the file changes.
+    ///
+    /// The file is checked before every draw, and the
+    /// [`shader`](#method.shader) is used until it is loaded. Requires the
+    /// `hot-reload` feature, meant for development.
+    #[cfg(feature = "hot-reload")]
+    pub fn shader_path(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.settings.shader_path = Some(path.into());
+        self
+    }
+
+    /// Sets whether a transparent pixel is left around every glyph packed
+    /// in the atlas.
+    ///
+    /// Padding keeps the neighbors of a glyph from bleeding into it when it
+    /// is sampled with linear filtering, e.g. when the text is scaled or
+    /// drawn in perspective, while no padding packs more glyphs in the same
+    /// atlas. It applies to the glyph cache and to the atlas of the
+    /// [`svg_glyphs`](#method.svg_glyphs). A
+    /// [`SharedCache`](struct.SharedCache.html) keeps its own padding.
+    ///
+    /// By default, glyphs are __padded__.
+    pub fn pad_glyphs(mut self, pad_glyphs: bool) -> Self {
+        self.inner.draw_cache_builder =
+            self.inner.draw_cache_builder.pad_glyphs(pad_glyphs);
+
+        #[cfg(feature = "svg")]
+        {
+            self.settings.pad_glyphs = pad_glyphs;
+        }
+
+        self
+    }
+
+    /// Sets the size above which glyphs are oversized, in pixels.
+    ///
+    /// Oversized glyphs, like the ones of huge display text, are not packed
+    /// in the glyph cache, which would otherwise grow to fit them or fail to.
+    /// Instead, every one is rasterized into a dedicated texture, kept while
+    /// it is drawn every frame.
+    ///
+    /// Defaults to `1024`.
+    pub fn oversized_glyph_size(mut self, size: u32) -> Self {
+        self.settings.oversized_glyph_size = size;
+        self
+    }
+
+    /// Sets what the brush does when the glyphs of a draw do not fit in the
+    /// glyph cache, which cannot grow beyond the largest texture supported
+    /// by the device.
+    ///
+    /// Defaults to [`OnCacheFull::Error`], failing the draw.
+    pub fn on_cache_full(mut self, policy: OnCacheFull) -> Self {
+        self.settings.on_cache_full = policy;
+        self
+    }
+
+    /// Normalizes the text of queued sections to Unicode Normalization Form
+    /// C (NFC) when it is not already normalized.
+    ///
+    /// Visually identical text composed differently, e.g. accents decomposed
+    /// by an input method, is then cached as the same section and drawn with
+    /// the precomposed glyphs of the font. The byte indices of the laid out
+    /// glyphs refer to the normalized text. Requires the `normalization`
+    /// feature.
+    ///
+    /// Disabled by default.
+    #[cfg(feature = "normalization")]
+    pub fn normalize_text(mut self, normalize_text: bool) -> Self {
+        self.settings.normalize_text = normalize_text;
+        self
+    }
+
+    /// Rasterizes the OT-SVG glyphs of the fonts, like the ones of some icon
+    /// and emoji fonts, into an RGBA atlas page bound as the atlas with the
+    /// given index.
+    ///
+    /// The PNG bitmaps of fonts with embedded bitmaps, like most color emoji
+    /// fonts, are decoded into the same page from the strike chosen with
+    /// [`bitmap_strikes`](#method.bitmap_strikes).
+    ///
+    /// The glyphs are drawn with their own colors and the alpha of their
+    /// text, after the glyphs of the glyph cache. The atlas is owned by the
+    /// brush, so it must not be set with
+    /// [`GlyphBrush::set_atlas`](struct.GlyphBrush.html#method.set_atlas).
+    /// Requires the `svg` feature.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Panics
+    /// Panics if `index` is `0` or not lower than
+    /// [`MAX_ATLASES`](constant.MAX_ATLASES.html).
+    #[cfg(feature = "svg")]
+    pub fn svg_glyphs(mut self, atlas: usize) -> Self {
+        assert!(
+            (1..crate::MAX_ATLASES).contains(&atlas),
+            "Invalid atlas index: {atlas}"
+        );
+
+        self.settings.svg_atlas = Some(atlas);
+        self
+    }
+
+    /// Sets how the strike of a bitmap glyph is chosen relative to its size,
+    /// for fonts with bitmaps of multiple sizes. Bitmap glyphs are drawn with
+    /// [`svg_glyphs`](#method.svg_glyphs).
+    ///
+    /// Defaults to [`StrikePolicy::NearestLarger`].
+    ///
+    /// [`StrikePolicy::NearestLarger`]: enum.StrikePolicy.html#variant.NearestLarger
+    pub fn bitmap_strikes(mut self, policy: crate::StrikePolicy) -> Self {
+        self.settings.strike_policy = policy;
+        self
+    }
+
+    /// Sets the value of a pipeline-overridable constant of the shader, i.e.
+    /// an `override` declaration in WGSL.
+    ///
+    /// Constants tune the effects of the shader without recompiling it,
+    /// e.g. the `alpha_gamma` exponent applied to the coverage of glyphs by
+    /// the default shaders. A custom [`shader`](#method.shader) must declare
+    /// every constant that is set.
+    ///
+    /// They can be changed later with
+    /// [`GlyphBrush::set_pipeline_constant`](struct.GlyphBrush.html#method.set_pipeline_constant).
+    pub fn pipeline_constant(
+        mut self,
+        name: impl Into<String>,
+        value: f64,
+    ) -> Self {
+        let name = name.into();
+
+        self.settings
+            .constants
+            .retain(|(current, _)| *current != name);
+        self.settings.constants.push((name, value));
+        self
+    }
+
+    /// Appends the given bind group layouts to the render pipeline layout,
+    /// after the bind groups of the brush. The first layout corresponds to
+    /// the bind group `2`.
+    ///
+    /// The bind groups can be provided with
+    /// [`GlyphBrush::set_bind_group`](struct.GlyphBrush.html#method.set_bind_group).
+    /// Use it together with a custom [`shader`](#method.shader) to access
+    /// additional resources.
+    pub fn bind_group_layouts(
+        mut self,
+        layouts: Vec<wgpu::BindGroupLayout>,
+    ) -> Self {
+        self.settings.bind_group_layouts = layouts;
+        self
+    }
+
+    /// Shares the given glyph cache with the other brushes using it, instead
+    /// of building a new one.
+    ///
+    /// The [`initial_cache_size`](#method.initial_cache_size) is ignored,
+    /// the dimensions of the [`SharedCache`](struct.SharedCache.html) are
+    /// used instead.
+    pub fn shared_cache(mut self, cache: &SharedCache) -> Self {
+        self.settings.shared_cache = Some(cache.clone());
+        self
+    }
+
+    /// Sets the chunk size, in bytes, of the staging belts owned by the brush.
+    ///
+    /// They are only used by
+    /// [`GlyphBrush::draw_queued_owned`](struct.GlyphBrush.html#method.draw_queued_owned).
+    /// Defaults to 64 KiB.
+    pub fn staging_belt_chunk_size(
+        mut self,
+        chunk_size: wgpu::BufferAddress,
+    ) -> Self {
+        self.settings.staging_belt_chunk_size = chunk_size;
+        self
+    }
+
+    /// Sets the amount of frames the GPU may still be drawing when the next
+    /// one is drawn, usually `2` or `3`.
+    ///
+    /// The brush keeps an instance buffer and a transform buffer for every
+    /// frame in flight, and writes each draw to the ones of the next frame
+    /// in turn, so its uploads usually do not have to wait for the GPU to
+    /// finish reading the buffers of the previous draws, at the expense of
+    /// the memory of the extra buffers.
+    ///
+    /// It is only a hint: the brush does not track when the submitted
+    /// frames are done, and wgpu orders the uploads after the work already
+    /// submitted regardless, so exceeding the frames in flight is never
+    /// incorrect, only possibly slower. The palette and the transform slots
+    /// are shared by every frame, as they are only uploaded when changed.
+    ///
+    /// Defaults to `1`, writing every draw to the same buffers.
+    pub fn frames_in_flight(mut self, frames: usize) -> Self {
+        self.settings.frames_in_flight = frames.max(1);
+        self
+    }
+
+    /// Rasterizes glyphs at the given multiple of their size, drawing them
+    /// downsampled.
+    ///
+    /// Glyphs are rasterized axis-aligned, so text drawn rotated or scaled
+    /// down by the transform shows stair-stepping. Supersampling smooths it
+    /// out at the expense of a larger glyph cache. It relies on the
+    /// [`texture_filter_method`](#method.texture_filter_method) being
+    /// `Linear`, which is the default.
+    ///
+    /// Defaults to `1`, no supersampling.
+    pub fn supersampling(mut self, factor: u32) -> Self {
+        self.settings.supersampling = factor.max(1);
+        self
+    }
+
+    /// Sets whether the colors of the text are sRGB-encoded, like the colors
+    /// of CSS or design tools, instead of linear.
+    ///
+    /// Colors are converted to linear in the shader, so they match their
+    /// sRGB values when drawn onto sRGB targets. See
+    /// [`srgb_color`](fn.srgb_color.html) to convert them on the CPU instead.
+    /// A custom [`shader`](#method.shader) only converts them if it declares
+    /// the `srgb_colors` pipeline constant of the default shaders.
+    ///
+    /// By default, colors are linear.
+    pub fn srgb_colors(mut self, srgb: bool) -> Self {
+        self.settings
+            .constants
+            .retain(|(name, _)| name != "srgb_colors");
+
+        if srgb {
+            self.settings
+                .constants
+                .push(("srgb_colors".to_owned(), 1.0));
+        }
+
+        self
+    }
+
+    /// Draws the text upside down, for targets read bottom-up like the
+    /// frames of video pipelines or of some OpenGL interop.
+    ///
+    /// The y axis is flipped after the transform of every draw, and the
+    /// winding of the glyph quads is kept, so the text is neither culled nor
+    /// mirrored once the target is read back. Scissor regions are flipped
+    /// with the text, across the viewport of the draw, while damage
+    /// rectangles stay in the coordinates of the sections. A custom
+    /// [`shader`](#method.shader) only flips the text if it declares the
+    /// `flip_y` pipeline constant of the default shaders.
+    ///
+    /// By default, the text is drawn upright.
+    pub fn flip_y(mut self, flip_y: bool) -> Self {
+        self.settings.constants.retain(|(name, _)| name != "flip_y");
+
+        if flip_y {
+            self.settings.constants.push(("flip_y".to_owned(), 1.0));
+        }
+
+        self
+    }
+
+    /// Sharpens the edges of the glyphs in the shader, fading them over
+    /// `1.0 / sharpness` pixels on screen whatever the scale or rotation of
+    /// the text, e.g. to keep small text crisp when a transform magnifies or
+    /// rotates it.
+    ///
+    /// The change of the coverage between neighboring pixels, i.e. its
+    /// `fwidth`, is used to find the distance to the edge of the glyph, so
+    /// `1.0` is a good default. Higher values make the edges harder and lower
+    /// ones blurrier. A custom [`shader`](#method.shader) only sharpens the
+    /// edges if it declares the `edge_sharpness` pipeline constant of the
+    /// default shaders.
+    ///
+    /// By default, or with `0.0`, the coverage is drawn as rasterized.
+    pub fn edge_sharpness(mut self, sharpness: f32) -> Self {
+        self.settings
+            .constants
+            .retain(|(name, _)| name != "edge_sharpness");
+
+        if sharpness > 0.0 {
+            self.settings
+                .constants
+                .push(("edge_sharpness".to_owned(), f64::from(sharpness)));
+        }
+
+        self
+    }
+
+    /// Sorts the glyphs by their depth after the transform of every draw,
+    /// drawing them in the given order. Custom instances are sorted too, but
+    /// they are still drawn after the glyphs.
+    ///
+    /// The depth of a glyph is the depth of its center, so it works best with
+    /// glyphs that do not intersect each other. Sorting happens on the CPU,
+    /// whenever the glyphs or the transform change.
+    ///
+    /// By default, glyphs are drawn in the order they were queued in.
+    pub fn depth_order(mut self, order: DepthOrder) -> Self {
+        self.settings.depth_order = Some(order);
+        self
+    }
+
+    /// Sorts the glyphs by the region of the glyph cache they are drawn
+    /// from, so the fragments of consecutive glyphs sample nearby texels,
+    /// e.g. to draw hundreds of thousands of glyphs.
+    ///
+    /// Overlapping glyphs are then drawn in the order of the cache instead
+    /// of the order they were queued in, so it is disabled by default. With
+    /// a [`depth_order`](#method.depth_order), glyphs at the same depth are
+    /// drawn in the order of the cache. Custom instances are not sorted.
+    pub fn sort_by_atlas(mut self, sort_by_atlas: bool) -> Self {
+        self.settings.sort_by_atlas = sort_by_atlas;
+        self
+    }
+
+    /// Scissors the render pass of every draw to the pixels covered by the
+    /// glyphs, backgrounds and custom instances drawn, after the transform
+    /// of the draw, so text updating a part of the screen does not touch
+    /// the rest of the render target.
+    ///
+    /// The scissoring region of a draw, if any, still clips the text. The
+    /// whole target is drawn to if some text is drawn with a
+    /// [transform slot](struct.GlyphStyle.html#structfield.transform_slot),
+    /// or behind the camera of a perspective transform. A custom [`shader`](#method.shader) must
+    /// position the instances like the default shaders.
+    ///
+    /// The bounds are computed on the CPU whenever the instances are
+    /// uploaded, so it is disabled by default.
+    pub fn auto_scissor(mut self, auto_scissor: bool) -> Self {
+        self.settings.auto_scissor = auto_scissor;
+        self
+    }
+
+    /// Draws the queued sections, backgrounds and custom instances in layers
+    /// by the z of their text, regardless of the order they were queued in.
+    ///
+    /// Everything with a larger z is drawn first and covered by everything
+    /// with a smaller z, matching the depth test of a depth buffer. For
+    /// instance, a tooltip queued with [`Text::with_z`] set to `0.0` is drawn
+    /// over the text and panels at `0.5`, in the same draw. Instances with the
+    /// same z keep the order they are drawn in without layers.
+    ///
+    /// The instances are sorted on the CPU whenever they are uploaded, so it
+    /// is disabled by default.
+    ///
+    /// [`Text::with_z`]: struct.Text.html#method.with_z
+    pub fn layered(mut self, layered: bool) -> Self {
+        self.settings.layered = layered;
+        self
+    }
+
+    /// Tracks the areas whose text changes between draws, reported by
+    /// [`GlyphBrush::damage`](struct.GlyphBrush.html#method.damage).
+    ///
+    /// Every instance drawn is compared to the ones of the previous draw
+    /// whenever they change, so it is disabled by default.
+    pub fn track_damage(mut self, track_damage: bool) -> Self {
+        self.settings.track_damage = track_damage;
+        self
+    }
+
+    /// Keeps a copy of the instances drawn, returned by
+    /// [`GlyphBrush::recorded_instances`](struct.GlyphBrush.html#method.recorded_instances),
+    /// e.g. to test the placement of glyphs without reading the target back.
+    ///
+    /// The instances are copied whenever they are uploaded, so it is
+    /// disabled by default.
+    pub fn record_instances(mut self, record_instances: bool) -> Self {
+        self.settings.record_instances = record_instances;
+        self
+    }
+
+    /// Hashes the content queued for every draw, returned by
+    /// [`GlyphBrush::content_hash`](struct.GlyphBrush.html#method.content_hash)
+    /// and compared by
+    /// [`GlyphBrush::is_dirty`](struct.GlyphBrush.html#method.is_dirty).
+    ///
+    /// Every section queued or retained and every instance drawn is hashed
+    /// on every draw, so it is disabled by default.
+    pub fn track_content(mut self, track_content: bool) -> Self {
+        self.settings.track_content = track_content;
+        self
+    }
+
+    /// Shares the layout of the sections with the same texts, fonts, scales,
+    /// bounds and layout, wherever they are queued, e.g. the labels of the
+    /// many buttons of a widget-heavy UI.
+    ///
+    /// Sections are laid out again whenever they move, since the glyph
+    /// positioning cache is keyed by their screen position. With this cache,
+    /// their glyphs are translated from the layout of a section with the
+    /// same content instead. Layouts not used in a draw are dropped, and
+    /// custom layouts must position glyphs relative to the screen position
+    /// of their section, like the built-in ones.
+    ///
+    /// Disabled by default.
+    pub fn layout_cache(mut self, layout_cache: bool) -> Self {
+        self.settings.layout_cache = layout_cache;
+        self
+    }
+
+    /// Sets the prefix of the labels of the GPU resources of the brush, to
+    /// tell the resources of multiple brushes apart in GPU captures.
+    ///
+    /// Defaults to `"wgpu_glyph"`, which results in labels like
+    /// `"wgpu_glyph::Pipeline instances"`.
+    pub fn label(mut self, label: impl Into<Cow<'static, str>>) -> Self {
+        self.settings.label = label.into();
         self
     }
 
@@ -87,7 +588,10 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
     /// hash collisions so use a good hash algorithm.
     ///
     /// This hasher is used to distinguish sections, rather than for hashmap
-    /// internal use.
+    /// internal use, e.g. a faster hasher like FxHash or a deterministic one
+    /// for reproducible builds. Sections drawn with a
+    /// [`shared_cache`](#method.shared_cache) are distinguished with the
+    /// [`DefaultSectionHasher`](type.DefaultSectionHasher.html).
     ///
     /// Defaults to [xxHash](https://docs.rs/twox-hash).
     pub fn section_hasher<T: BuildHasher>(
@@ -96,8 +600,7 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
     ) -> GlyphBrushBuilder<D, F, T> {
         GlyphBrushBuilder {
             inner: self.inner.section_hasher(section_hasher),
-            texture_filter_method: self.texture_filter_method,
-            multisample_state: self.multisample_state,
+            settings: self.settings,
             depth: self.depth,
         }
     }
@@ -109,8 +612,7 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
     ) -> GlyphBrushBuilder<wgpu::DepthStencilState, F, H> {
         GlyphBrushBuilder {
             inner: self.inner,
-            texture_filter_method: self.texture_filter_method,
-            multisample_state: self.multisample_state,
+            settings: self.settings,
             depth: depth_stencil_state,
         }
     }
@@ -119,6 +621,11 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
 impl<F: Font + Sync, H: BuildHasher> GlyphBrushBuilder<(), F, H> {
     /// Builds a `GlyphBrush` using the given `wgpu::Device` that can render
     /// text for texture views with the given `render_format`.
+    ///
+    /// The brush can draw onto targets of any other format or sample count
+    /// too. Their render pipelines are created and cached on first use.
+    /// Targets with a single channel, like `R8Unorm` textures, get the
+    /// coverage of the text, e.g. to generate masks for post-processing.
     pub fn build(
         self,
         device: &wgpu::Device,
@@ -126,19 +633,45 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrushBuilder<(), F, H> {
     ) -> GlyphBrush<(), F, H> {
         GlyphBrush::<(), F, H>::new(
             device,
-            self.texture_filter_method,
-            self.multisample_state,
-            render_format,
+            &self.settings,
+            Some(render_format),
             self.inner,
         )
     }
+
+    /// Builds a `GlyphBrush` using the given `wgpu::Device` without
+    /// compiling any render pipeline.
+    ///
+    /// Every render pipeline, including the one of the debug overlay, is
+    /// created on the first draw onto a target of its format and sample
+    /// count, e.g. when the format of the surface is not known yet.
+    pub fn build_lazy(self, device: &wgpu::Device) -> GlyphBrush<(), F, H> {
+        GlyphBrush::<(), F, H>::new(device, &self.settings, None, self.inner)
+    }
 }
 
 impl<F: Font + Sync, H: BuildHasher>
     GlyphBrushBuilder<wgpu::DepthStencilState, F, H>
 {
+    /// Sets the depth bias of the render pipeline, overriding the bias of
+    /// the depth stencil state.
+    ///
+    /// Text drawn onto 3D surfaces, like signs or floor markings, lies at the
+    /// same depth as the geometry below it. A bias keeps the glyph quads from
+    /// z-fighting with it, e.g. a negative `constant` when using a `Less`
+    /// depth comparison.
+    pub fn depth_bias(mut self, bias: wgpu::DepthBiasState) -> Self {
+        self.depth.bias = bias;
+        self
+    }
+
     /// Builds a `GlyphBrush` using the given `wgpu::Device` that can render
     /// text for texture views with the given `render_format`.
+    ///
+    /// The brush can draw onto targets of any other format or sample count
+    /// too. Their render pipelines are created and cached on first use.
+    /// Targets with a single channel, like `R8Unorm` textures, get the
+    /// coverage of the text, e.g. to generate masks for post-processing.
     pub fn build(
         self,
         device: &wgpu::Device,
@@ -146,9 +679,27 @@ impl<F: Font + Sync, H: BuildHasher>
     ) -> GlyphBrush<wgpu::DepthStencilState, F, H> {
         GlyphBrush::<wgpu::DepthStencilState, F, H>::new(
             device,
-            self.texture_filter_method,
-            self.multisample_state,
-            render_format,
+            &self.settings,
+            Some(render_format),
+            self.depth,
+            self.inner,
+        )
+    }
+
+    /// Builds a `GlyphBrush` using the given `wgpu::Device` without
+    /// compiling any render pipeline.
+    ///
+    /// Every render pipeline, including the one of the debug overlay, is
+    /// created on the first draw onto a target of its format and sample
+    /// count, e.g. when the format of the surface is not known yet.
+    pub fn build_lazy(
+        self,
+        device: &wgpu::Device,
+    ) -> GlyphBrush<wgpu::DepthStencilState, F, H> {
+        GlyphBrush::<wgpu::DepthStencilState, F, H>::new(
+            device,
+            &self.settings,
+            None,
             self.depth,
             self.inner,
         )
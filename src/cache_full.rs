@@ -0,0 +1,224 @@
+use crate::ab_glyph::{point, Rect};
+use crate::Instance;
+
+use glyph_brush::Rectangle;
+
+/// What a `GlyphBrush` does when the glyphs of a draw do not fit in its
+/// glyph cache, even at the largest size supported by the device.
+///
+/// Set with
+/// [`GlyphBrushBuilder::on_cache_full`](struct.GlyphBrushBuilder.html#method.on_cache_full).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OnCacheFull {
+    /// The draw returns an error and draws nothing.
+    #[default]
+    Error,
+    /// The glyph cache is cleared to pack as many glyphs as it can hold, and
+    /// the glyphs that still do not fit are drawn from temporary textures
+    /// created for the draw.
+    ///
+    /// Every glyph is drawn, at the cost of an upload and a draw call for
+    /// every glyph out of the cache.
+    ClearAndRetry,
+    /// The glyphs that do not fit in the glyph cache are not drawn, while the
+    /// rest of the text is.
+    SkipNewGlyphs,
+}
+
+/// The largest dimensions of the virtual cache of a [`VirtualCache`].
+const MAX_VIRTUAL_DIMENSION: u32 = 1 << 15;
+
+/// The glyphs of a draw packed into a virtual cache larger than the glyph
+/// cache of a `GlyphBrush`, which cannot grow to fit them.
+///
+/// The regions of the virtual cache within the glyph cache are uploaded to
+/// it, so the glyphs packed there are drawn as usual.
+pub(crate) struct VirtualCache {
+    policy: OnCacheFull,
+    dimensions: (u32, u32),
+    virtual_dimensions: (u32, u32),
+    /// The regions of the virtual cache out of the glyph cache, with their
+    /// pixels, kept to draw their glyphs from temporary textures.
+    uploads: Vec<(Rectangle<u32>, Vec<u8>)>,
+}
+
+/// A glyph out of the glyph cache, drawn from a temporary texture.
+pub(crate) struct Temporary {
+    pub size: [u16; 2],
+    pub coverage: Vec<u8>,
+    /// The instance of the glyph, covering the whole texture.
+    pub instance: Instance,
+}
+
+impl VirtualCache {
+    pub(crate) fn new(policy: OnCacheFull, dimensions: (u32, u32)) -> Self {
+        VirtualCache {
+            policy,
+            dimensions,
+            virtual_dimensions: dimensions,
+            uploads: Vec::new(),
+        }
+    }
+
+    /// Returns the dimensions of the glyph cache.
+    pub(crate) fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    /// Doubles the virtual cache, returning its new dimensions, or `None` if
+    /// it is too large already.
+    pub(crate) fn grow(&mut self) -> Option<(u32, u32)> {
+        let (width, height) = self.virtual_dimensions;
+
+        if width.max(height) >= MAX_VIRTUAL_DIMENSION {
+            return None;
+        }
+
+        self.virtual_dimensions = (width * 2, height * 2);
+        self.uploads.clear();
+
+        Some(self.virtual_dimensions)
+    }
+
+    /// Uploads a region of the virtual cache, passing the part within the
+    /// glyph cache to `update`.
+    pub(crate) fn upload(
+        &mut self,
+        rect: Rectangle<u32>,
+        data: &[u8],
+        mut update: impl FnMut(Rectangle<u32>, &[u8]),
+    ) {
+        let (width, height) = self.dimensions;
+        let inside = Rectangle {
+            min: rect.min,
+            max: [rect.max[0].min(width), rect.max[1].min(height)],
+        };
+
+        if inside.min[0] < inside.max[0] && inside.min[1] < inside.max[1] {
+            if inside == rect {
+                update(rect, data);
+            } else {
+                let pixels: Vec<u8> = data
+                    .chunks_exact(rect.width() as usize)
+                    .take(inside.height() as usize)
+                    .flat_map(|row| &row[..inside.width() as usize])
+                    .copied()
+                    .collect();
+
+                update(inside, &pixels);
+            }
+        }
+
+        if inside != rect && self.policy == OnCacheFull::ClearAndRetry {
+            self.uploads.push((rect, data.to_vec()));
+        }
+    }
+
+    /// Returns the instances of the glyphs packed within the glyph cache,
+    /// with their texture coordinates in the glyph cache, and the glyphs
+    /// packed out of it, if they are drawn from temporary textures.
+    pub(crate) fn finish(
+        self,
+        instances: Vec<Instance>,
+    ) -> (Vec<Instance>, Vec<Temporary>) {
+        let (width, height) = self.dimensions;
+        let (virtual_width, virtual_height) = self.virtual_dimensions;
+
+        let scale = (
+            virtual_width as f32 / width as f32,
+            virtual_height as f32 / height as f32,
+        );
+
+        let mut cached = Vec::with_capacity(instances.len());
+        let mut temporaries = Vec::new();
+
+        for instance in instances {
+            let tex_rect = instance.tex_rect();
+
+            if tex_rect.max.x * scale.0 <= 1.0
+                && tex_rect.max.y * scale.1 <= 1.0
+            {
+                cached.push(instance.with_tex_rect(Rect {
+                    min: point(
+                        tex_rect.min.x * scale.0,
+                        tex_rect.min.y * scale.1,
+                    ),
+                    max: point(
+                        tex_rect.max.x * scale.0,
+                        tex_rect.max.y * scale.1,
+                    ),
+                }));
+            } else if let Some(temporary) = self.temporary(instance) {
+                temporaries.push(temporary);
+            }
+        }
+
+        (cached, temporaries)
+    }
+
+    /// Copies the pixels of a glyph packed out of the glyph cache into a
+    /// temporary texture, if they were kept.
+    fn temporary(&self, instance: Instance) -> Option<Temporary> {
+        let (width, height) = self.virtual_dimensions;
+        let tex_rect = instance.tex_rect();
+
+        let left = tex_rect.min.x * width as f32;
+        let top = tex_rect.min.y * height as f32;
+        let right = tex_rect.max.x * width as f32;
+        let bottom = tex_rect.max.y * height as f32;
+
+        let region = Rectangle {
+            min: [left.floor() as u32, top.floor() as u32],
+            max: [right.ceil() as u32, bottom.ceil() as u32],
+        };
+
+        // Glyphs out of the bounds of their section cover nothing
+        if region.max[0] <= region.min[0] || region.max[1] <= region.min[1] {
+            return None;
+        }
+
+        let size = [
+            u16::try_from(region.width()).ok()?,
+            u16::try_from(region.height()).ok()?,
+        ];
+
+        let (upload, data) = self.uploads.iter().find(|(upload, _)| {
+            upload.min[0] <= region.min[0]
+                && upload.min[1] <= region.min[1]
+                && region.max[0] <= upload.max[0]
+                && region.max[1] <= upload.max[1]
+        })?;
+
+        let coverage = data
+            .chunks_exact(upload.width() as usize)
+            .skip((region.min[1] - upload.min[1]) as usize)
+            .take(region.height() as usize)
+            .flat_map(|row| {
+                let start = (region.min[0] - upload.min[0]) as usize;
+
+                &row[start..start + region.width() as usize]
+            })
+            .copied()
+            .collect();
+
+        // Glyphs clipped by the bounds of their section only cover a part of
+        // their region
+        let region_width = region.width() as f32;
+        let region_height = region.height() as f32;
+
+        Some(Temporary {
+            size,
+            coverage,
+            instance: instance.with_tex_rect(Rect {
+                min: point(
+                    (left - region.min[0] as f32) / region_width,
+                    (top - region.min[1] as f32) / region_height,
+                ),
+                max: point(
+                    (right - region.min[0] as f32) / region_width,
+                    (bottom - region.min[1] as f32) / region_height,
+                ),
+            }),
+        })
+    }
+}
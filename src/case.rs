@@ -0,0 +1,284 @@
+use glyph_brush::ab_glyph::{Font, Rect};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    SectionText, ToSectionText,
+};
+
+use crate::Language;
+
+/// A case transform applied to texts at layout time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// All the letters are uppercase.
+    Upper,
+    /// All the letters are lowercase.
+    Lower,
+    /// The first letter of every word is uppercase, the others are left
+    /// unchanged.
+    Title,
+}
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that transforms the
+/// case of the texts of a section, like the CSS `text-transform` property.
+///
+/// The texts of the section are left untouched, so they keep hashing the
+/// same, and the glyphs keep the byte indices of the characters they come
+/// from. Case mappings follow the language of the section when it has
+/// specific ones, e.g. `i` becomes `İ` in Turkish.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct CaseTransform<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    case: Option<Case>,
+    text_cases: Vec<(usize, Case)>,
+    language: Option<Language>,
+}
+
+impl<L> CaseTransform<L> {
+    /// Wraps the given layout, without transforming any text.
+    pub fn new(layout: L) -> Self {
+        CaseTransform {
+            layout,
+            case: None,
+            text_cases: Vec::new(),
+            language: None,
+        }
+    }
+
+    /// Sets the case of all the texts of the section.
+    pub fn case(mut self, case: Case) -> Self {
+        self.case = Some(case);
+        self
+    }
+
+    /// Sets the case of the text with the given index in the section,
+    /// overriding the case of the section.
+    pub fn text_case(mut self, index: usize, case: Case) -> Self {
+        self.text_cases.retain(|(i, _)| *i != index);
+        self.text_cases.push((index, case));
+        self
+    }
+
+    /// Sets the language whose case mappings are used.
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Returns the case of the text with the given index, if any.
+    pub fn case_of(&self, index: usize) -> Option<Case> {
+        self.text_cases
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, case)| *case)
+            .or(self.case)
+    }
+
+    fn is_turkic(&self) -> bool {
+        self.language
+            .as_ref()
+            .is_some_and(|language| matches!(language.language(), "tr" | "az"))
+    }
+
+    fn push_upper(&self, text: &mut String, character: char) {
+        match character {
+            'i' if self.is_turkic() => text.push('İ'),
+            _ => text.extend(character.to_uppercase()),
+        }
+    }
+
+    fn push_lower(&self, text: &mut String, character: char) {
+        match character {
+            'I' if self.is_turkic() => text.push('ı'),
+            'İ' if self.is_turkic() => text.push('i'),
+            _ => text.extend(character.to_lowercase()),
+        }
+    }
+
+    /// Transforms the case of a text, tracking whether the next character
+    /// starts a word across the texts of the section.
+    fn transform(
+        &self,
+        text: &str,
+        case: Case,
+        word_start: &mut bool,
+    ) -> Transformed {
+        let mut transformed = Transformed {
+            text: String::with_capacity(text.len()),
+            bytes: Vec::new(),
+        };
+
+        for (byte_index, character) in text.char_indices() {
+            transformed.bytes.push((transformed.text.len(), byte_index));
+
+            match case {
+                Case::Upper => {
+                    self.push_upper(&mut transformed.text, character)
+                }
+                Case::Lower => {
+                    self.push_lower(&mut transformed.text, character)
+                }
+                Case::Title if *word_start => {
+                    self.push_upper(&mut transformed.text, character)
+                }
+                Case::Title => transformed.text.push(character),
+            }
+
+            *word_start = precedes_word(character);
+        }
+
+        transformed
+    }
+}
+
+/// Returns whether the character after the given one starts a word.
+fn precedes_word(character: char) -> bool {
+    !character.is_alphanumeric() && character != '\'' && character != '’'
+}
+
+/// A text with its case transformed.
+struct Transformed {
+    text: String,
+    /// The byte index of every character, in the transformed and in the
+    /// original text.
+    bytes: Vec<(usize, usize)>,
+}
+
+impl Transformed {
+    fn original_byte_index(&self, byte_index: usize) -> usize {
+        match self
+            .bytes
+            .binary_search_by_key(&byte_index, |(new, _)| *new)
+        {
+            Ok(i) => self.bytes[i].1,
+            // Characters expanded into several ones, like `ß` into `SS`,
+            // map back to the character they come from.
+            Err(i) => self.bytes[i.saturating_sub(1)].1,
+        }
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for CaseTransform<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        if self.case.is_none() && self.text_cases.is_empty() {
+            return self.layout.calculate_glyphs(fonts, geometry, sections);
+        }
+
+        let mut word_start = true;
+
+        let transformed: Vec<Option<Transformed>> = sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                let text = section.to_section_text().text;
+
+                match self.case_of(index) {
+                    Some(case) => {
+                        Some(self.transform(text, case, &mut word_start))
+                    }
+                    None => {
+                        word_start = text
+                            .chars()
+                            .next_back()
+                            .map_or(word_start, precedes_word);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let texts: Vec<SectionText<'_>> = sections
+            .iter()
+            .zip(&transformed)
+            .map(|(section, transformed)| {
+                let text = section.to_section_text();
+
+                match transformed {
+                    Some(transformed) => SectionText {
+                        text: &transformed.text,
+                        ..text
+                    },
+                    None => text,
+                }
+            })
+            .collect();
+
+        let mut glyphs = self.layout.calculate_glyphs(fonts, geometry, &texts);
+
+        for glyph in &mut glyphs {
+            if let Some(transformed) = &transformed[glyph.section_index] {
+                glyph.byte_index =
+                    transformed.original_byte_index(glyph.byte_index);
+            }
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(
+        transform: &CaseTransform<Layout<BuiltInLineBreaker>>,
+        text: &str,
+        case: Case,
+    ) -> String {
+        transform.transform(text, case, &mut true).text
+    }
+
+    #[test]
+    fn turkish_case_mappings() {
+        let turkish = CaseTransform::new(Layout::default())
+            .language(Language::parse("tr-TR").unwrap());
+
+        assert_eq!(transform(&turkish, "istanbul", Case::Upper), "İSTANBUL");
+        assert_eq!(transform(&turkish, "IĞDIR", Case::Lower), "ığdır");
+        assert_eq!(transform(&turkish, "İZMİR", Case::Lower), "izmir");
+        assert_eq!(transform(&turkish, "iki ırmak", Case::Title), "İki Irmak");
+    }
+
+    #[test]
+    fn default_case_mappings() {
+        let default = CaseTransform::new(Layout::default());
+
+        assert_eq!(transform(&default, "istanbul", Case::Upper), "ISTANBUL");
+        assert_eq!(transform(&default, "IĞDIR", Case::Lower), "iğdir");
+        assert_eq!(
+            transform(&default, "it's an owl", Case::Title),
+            "It's An Owl"
+        );
+    }
+
+    #[test]
+    fn original_byte_indices() {
+        let default = CaseTransform::new(Layout::default());
+        let transformed = default.transform("aßb", Case::Upper, &mut true);
+
+        assert_eq!(transformed.text, "ASSB");
+
+        // Both letters of `SS` map back to `ß`
+        assert_eq!(
+            (0..4)
+                .map(|index| transformed.original_byte_index(index))
+                .collect::<Vec<_>>(),
+            [0, 1, 1, 3]
+        );
+    }
+}
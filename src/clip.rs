@@ -0,0 +1,111 @@
+use crate::Region;
+
+use glyph_brush::ab_glyph::{point, Font, Rect};
+use glyph_brush::{
+    GlyphPositioner, SectionGeometry, SectionGlyph, ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+
+/// The stack of clip regions of a `GlyphBrush`, each one intersected with
+/// the ones below it.
+#[derive(Debug, Default)]
+pub(crate) struct ClipStack {
+    clips: Vec<Rect>,
+}
+
+impl ClipStack {
+    pub(crate) fn push(&mut self, region: Region) {
+        let rect = Rect {
+            min: point(region.x as f32, region.y as f32),
+            max: point(
+                region.x as f32 + region.width as f32,
+                region.y as f32 + region.height as f32,
+            ),
+        };
+
+        let clip = match self.clips.last() {
+            Some(top) => intersect(*top, rect),
+            None => rect,
+        };
+
+        self.clips.push(clip);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Rect> {
+        self.clips.pop()
+    }
+
+    /// Returns the intersection of the pushed regions, scaled to pixels on
+    /// screen with the given density, if any region is pushed.
+    pub(crate) fn current(&self, (x, y): (f32, f32)) -> Option<Rect> {
+        self.clips.last().map(|clip| Rect {
+            min: point(clip.min.x * x, clip.min.y * y),
+            max: point(clip.max.x * x, clip.max.y * y),
+        })
+    }
+}
+
+/// Returns the intersection of two rectangles, which is empty at the corner
+/// of one of them if they do not overlap.
+pub(crate) fn intersect(a: Rect, b: Rect) -> Rect {
+    let min = point(a.min.x.max(b.min.x), a.min.y.max(b.min.y));
+
+    Rect {
+        min,
+        max: point(
+            a.max.x.min(b.max.x).max(min.x),
+            a.max.y.min(b.max.y).max(min.y),
+        ),
+    }
+}
+
+/// A [`GlyphPositioner`] that clips the glyphs of a section to a clip
+/// region, besides its own bounds.
+#[derive(Debug)]
+pub(crate) struct Layout<L> {
+    layout: L,
+    clip: Option<Rect>,
+}
+
+impl<L> Layout<L> {
+    pub(crate) fn new(layout: L, clip: Option<Rect>) -> Self {
+        Layout { layout, clip }
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Layout<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        self.layout.calculate_glyphs(fonts, geometry, sections)
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        let bounds = self.layout.bounds_rect(geometry);
+
+        match self.clip {
+            Some(clip) => intersect(bounds, clip),
+            None => bounds,
+        }
+    }
+}
+
+impl<L: Hash> Hash for Layout<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+
+        self.clip
+            .map(|clip| {
+                [clip.min.x, clip.min.y, clip.max.x, clip.max.y]
+                    .map(f32::to_bits)
+            })
+            .hash(state);
+    }
+}
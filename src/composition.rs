@@ -0,0 +1,115 @@
+use crate::ab_glyph::{point, Rect};
+use crate::Instance;
+
+use std::ops::Range;
+
+/// The decorations of the text being composed by an input method (IME) in a
+/// section, i.e. its preedit text.
+///
+/// Queue it with
+/// [`GlyphBrush::queue_with_composition`](struct.GlyphBrush.html#method.queue_with_composition).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Composition {
+    /// The byte range of the composed text, in the texts of the section
+    /// joined together.
+    pub range: Range<usize>,
+    /// The style of the underline of the composed text.
+    pub underline: Underline,
+    /// The color of the underline.
+    pub underline_color: [f32; 4],
+    /// The color of the highlight behind the composed text, if any.
+    pub highlight: Option<[f32; 4]>,
+}
+
+/// The style of the underline of a [`Composition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Underline {
+    /// A continuous line.
+    #[default]
+    Solid,
+    /// A line broken into dashes.
+    Dashed,
+    /// A continuous line twice as thick, e.g. for the segment being
+    /// converted.
+    Thick,
+}
+
+impl Composition {
+    /// Creates a [`Composition`] of the given byte range, with a solid
+    /// underline of the given color and no highlight.
+    pub fn new(range: Range<usize>, underline_color: [f32; 4]) -> Self {
+        Composition {
+            range,
+            underline: Underline::Solid,
+            underline_color,
+            highlight: None,
+        }
+    }
+
+    /// Sets the style of the underline.
+    pub fn with_underline(mut self, underline: Underline) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    /// Sets the color of the highlight behind the composed text.
+    pub fn with_highlight(mut self, highlight: [f32; 4]) -> Self {
+        self.highlight = Some(highlight);
+        self
+    }
+
+    /// Returns the instances of the highlights of the given lines of the
+    /// composed text.
+    pub(crate) fn highlights<'a>(
+        &'a self,
+        lines: &'a [Rect],
+        z: f32,
+    ) -> impl Iterator<Item = Instance> + 'a {
+        self.highlight.into_iter().flat_map(move |color| {
+            lines
+                .iter()
+                .map(move |line| Instance::solid(*line, color, z))
+        })
+    }
+
+    /// Returns the instances of the underlines of the given lines of the
+    /// composed text, along their bottom edges.
+    pub(crate) fn underlines<'a>(
+        &'a self,
+        lines: &'a [Rect],
+        z: f32,
+    ) -> impl Iterator<Item = Instance> + 'a {
+        lines.iter().flat_map(move |line| {
+            let thickness = match self.underline {
+                Underline::Solid | Underline::Dashed => {
+                    (line.height() / 16.0).round().max(1.0)
+                }
+                Underline::Thick => (line.height() / 8.0).round().max(2.0),
+            };
+
+            let dash = match self.underline {
+                Underline::Solid | Underline::Thick => line.width(),
+                Underline::Dashed => thickness * 3.0,
+            };
+
+            let top = line.max.y - thickness;
+
+            (0..)
+                .map(move |i| line.min.x + i as f32 * dash * 2.0)
+                .take_while(move |left| *left < line.max.x)
+                .map(move |left| {
+                    Instance::solid(
+                        Rect {
+                            min: point(left, top),
+                            max: point(
+                                (left + dash).min(line.max.x),
+                                line.max.y,
+                            ),
+                        },
+                        self.underline_color,
+                        z,
+                    )
+                })
+        })
+    }
+}
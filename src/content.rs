@@ -0,0 +1,108 @@
+use crate::ab_glyph::Rect;
+use crate::{Extra, GlyphStyle, Instance, SectionGlyph};
+
+use glyph_brush::DefaultSectionHasher;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// Hashes the content queued to a `GlyphBrush`, to tell whether it differs
+/// from the content of the last draw.
+#[derive(Default)]
+pub(crate) struct Content {
+    hasher: DefaultSectionHasher,
+    /// The hash of the content queued since the last draw.
+    queued: u64,
+    /// The hash of the content of the last draw, if any.
+    drawn: Option<u64>,
+}
+
+impl Content {
+    /// Adds some queued content, like a section and its layout.
+    pub(crate) fn queue(&mut self, content: impl Hash) {
+        self.queued = self.combine(content);
+    }
+
+    /// Adds queued pre-positioned glyphs.
+    pub(crate) fn queue_pre_positioned(
+        &mut self,
+        glyphs: &[SectionGlyph],
+        extra: &[Extra],
+        bounds: Rect,
+        style: GlyphStyle,
+    ) {
+        let glyphs: Vec<_> = glyphs
+            .iter()
+            .map(|glyph| {
+                (
+                    glyph.section_index,
+                    glyph.byte_index,
+                    glyph.font_id,
+                    glyph.glyph.id,
+                    [
+                        glyph.glyph.position.x,
+                        glyph.glyph.position.y,
+                        glyph.glyph.scale.x,
+                        glyph.glyph.scale.y,
+                    ]
+                    .map(f32::to_bits),
+                )
+            })
+            .collect();
+
+        let bounds = [bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y]
+            .map(f32::to_bits);
+
+        self.queue((glyphs, extra, bounds, style));
+    }
+
+    /// Returns the hash of the queued content with the given instances and
+    /// other content that is not queued yet.
+    pub(crate) fn hash<'a>(
+        &self,
+        instances: impl IntoIterator<Item = &'a Instance>,
+        pending: impl Hash,
+    ) -> u64 {
+        let instances: Vec<_> = instances
+            .into_iter()
+            .map(|instance| {
+                let tex_rect = instance.tex_rect();
+
+                (
+                    instance.appearance(),
+                    [
+                        tex_rect.min.x,
+                        tex_rect.min.y,
+                        tex_rect.max.x,
+                        tex_rect.max.y,
+                    ]
+                    .map(f32::to_bits),
+                )
+            })
+            .collect();
+
+        self.combine((instances, pending))
+    }
+
+    /// Returns whether the given hash differs from the one of the last draw.
+    pub(crate) fn differs(&self, hash: u64) -> bool {
+        self.drawn != Some(hash)
+    }
+
+    /// Records the hash of the content of a draw.
+    pub(crate) fn draw(&mut self, hash: u64) {
+        self.drawn = Some(hash);
+    }
+
+    /// Clears the queued content once it is drawn.
+    pub(crate) fn clear(&mut self) {
+        self.queued = 0;
+    }
+
+    fn combine(&self, content: impl Hash) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+
+        self.queued.hash(&mut hasher);
+        content.hash(&mut hasher);
+
+        hasher.finish()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::ab_glyph::{point, Rect};
+use crate::Instance;
+
+use std::ops::Range;
+
+/// The caret and the selection of a section being edited, like the ones of
+/// a text input.
+///
+/// Queue it with
+/// [`GlyphBrush::queue_with_cursor`](struct.GlyphBrush.html#method.queue_with_cursor).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextCursor {
+    /// The byte offset of the caret, in the texts of the section joined
+    /// together.
+    pub caret: usize,
+    /// The color of the caret, e.g. transparent while it blinks off.
+    pub caret_color: [f32; 4],
+    /// The width of the caret, in pixels.
+    pub caret_width: f32,
+    /// The byte range of the selected text, in the texts of the section
+    /// joined together.
+    pub selection: Range<usize>,
+    /// The color of the highlight behind the selected text.
+    pub selection_color: [f32; 4],
+}
+
+impl TextCursor {
+    /// Creates a [`TextCursor`] with a caret of the given color one pixel
+    /// wide, and no selection.
+    pub fn new(caret: usize, caret_color: [f32; 4]) -> Self {
+        TextCursor {
+            caret,
+            caret_color,
+            caret_width: 1.0,
+            selection: caret..caret,
+            selection_color: [0.0; 4],
+        }
+    }
+
+    /// Sets the width of the caret.
+    pub fn with_caret_width(mut self, caret_width: f32) -> Self {
+        self.caret_width = caret_width;
+        self
+    }
+
+    /// Sets the selected text and the color of its highlight.
+    pub fn with_selection(
+        mut self,
+        selection: Range<usize>,
+        selection_color: [f32; 4],
+    ) -> Self {
+        self.selection = selection;
+        self.selection_color = selection_color;
+        self
+    }
+
+    /// Returns the instance of the caret, starting at the left edge of the
+    /// given caret rectangle.
+    pub(crate) fn caret_instance(&self, caret: Rect, z: f32) -> Instance {
+        let left = caret.min.x.round();
+
+        Instance::solid(
+            Rect {
+                min: point(left, caret.min.y),
+                max: point(left + self.caret_width, caret.max.y),
+            },
+            self.caret_color,
+            z,
+        )
+    }
+
+    /// Returns the instances of the highlights of the given lines of the
+    /// selected text.
+    pub(crate) fn selection_instances<'a>(
+        &'a self,
+        lines: &'a [Rect],
+        z: f32,
+    ) -> impl Iterator<Item = Instance> + 'a {
+        lines
+            .iter()
+            .map(move |line| Instance::solid(*line, self.selection_color, z))
+    }
+}
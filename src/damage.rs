@@ -0,0 +1,104 @@
+use crate::ab_glyph::{point, Rect};
+use crate::Instance;
+
+use std::collections::HashMap;
+
+/// Tracks the instances drawn by a `GlyphBrush` to find the areas whose text
+/// changed between frames.
+#[derive(Debug, Default)]
+pub(crate) struct Damage {
+    drawn: HashMap<[u32; 10], usize>,
+    rects: Vec<Rect>,
+}
+
+impl Damage {
+    /// The amount of damaged rectangles above which they are reported as a
+    /// single bounding rectangle.
+    const MAX_RECTS: usize = 32;
+
+    /// Records the instances of a frame, computing the areas covered by the
+    /// instances that appeared or disappeared since the last one.
+    pub(crate) fn draw<'a>(
+        &mut self,
+        instances: impl IntoIterator<Item = &'a Instance>,
+    ) {
+        let mut drawn: HashMap<[u32; 10], usize> = HashMap::new();
+        let mut rects = Vec::new();
+
+        for instance in instances {
+            let key = instance.appearance();
+            *drawn.entry(key).or_default() += 1;
+
+            match self.drawn.get_mut(&key) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => rects.push(pixel_rect(&key)),
+            }
+        }
+
+        for (key, count) in &self.drawn {
+            rects.extend(std::iter::repeat_n(pixel_rect(key), *count));
+        }
+
+        self.drawn = drawn;
+        self.rects = merge(rects);
+    }
+
+    /// Reports no damage, e.g. for a frame where nothing changed.
+    pub(crate) fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    pub(crate) fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+}
+
+/// Returns the rectangle covered by an instance with the given appearance.
+fn pixel_rect(key: &[u32; 10]) -> Rect {
+    let [left, top, right, bottom, ..] = key.map(f32::from_bits);
+
+    Rect {
+        min: point(left.min(right), top.min(bottom)),
+        max: point(left.max(right), top.max(bottom)),
+    }
+}
+
+/// Merges rectangles that overlap vertically and are at most their height
+/// apart horizontally, like the glyphs of a line, collapsing them into their
+/// bounding rectangle if there are too many.
+fn merge(mut rects: Vec<Rect>) -> Vec<Rect> {
+    rects.sort_by(|a, b| a.min.x.total_cmp(&b.min.x));
+
+    let mut merged: Vec<Rect> = Vec::new();
+
+    for rect in &rects {
+        let gap = rect.height();
+
+        let nearby = merged.iter().position(|merged| {
+            rect.min.y <= merged.max.y
+                && merged.min.y <= rect.max.y
+                && rect.min.x <= merged.max.x + gap
+        });
+
+        match nearby {
+            Some(index) => merged[index] = union(&merged[index], rect),
+            None if merged.len() < Damage::MAX_RECTS => merged.push(*rect),
+            None => {
+                let bounds = rects
+                    .iter()
+                    .fold(*rect, |bounds, rect| union(&bounds, rect));
+
+                return vec![bounds];
+            }
+        }
+    }
+
+    merged
+}
+
+fn union(a: &Rect, b: &Rect) -> Rect {
+    Rect {
+        min: point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+        max: point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+    }
+}
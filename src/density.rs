@@ -0,0 +1,59 @@
+use crate::{Instance, Section, SectionGlyph};
+
+use glyph_brush::ab_glyph::Rect;
+
+/// Scales a section from layout pixels to pixels on screen with the given
+/// density.
+pub(crate) fn scale_section(section: &mut Section<'_>, (x, y): (f32, f32)) {
+    section.screen_position.0 *= x;
+    section.screen_position.1 *= y;
+    section.bounds.0 *= x;
+    section.bounds.1 *= y;
+
+    for text in &mut section.text {
+        text.scale.x *= x;
+        text.scale.y *= y;
+    }
+}
+
+/// Scales pre-positioned glyphs from layout pixels to pixels on screen with
+/// the given density.
+pub(crate) fn scale_glyphs(
+    glyphs: &mut [SectionGlyph],
+    bounds: &mut Rect,
+    (x, y): (f32, f32),
+) {
+    for glyph in glyphs {
+        glyph.glyph.position.x *= x;
+        glyph.glyph.position.y *= y;
+        glyph.glyph.scale.x *= x;
+        glyph.glyph.scale.y *= y;
+    }
+
+    bounds.min.x *= x;
+    bounds.min.y *= y;
+    bounds.max.x *= x;
+    bounds.max.y *= y;
+}
+
+/// Scales glyphs back from pixels on screen to layout pixels with the given
+/// density.
+pub(crate) fn unscale_glyphs(glyphs: &mut [SectionGlyph], (x, y): (f32, f32)) {
+    for glyph in glyphs {
+        glyph.glyph.position.x /= x;
+        glyph.glyph.position.y /= y;
+        glyph.glyph.scale.x /= x;
+        glyph.glyph.scale.y /= y;
+    }
+}
+
+/// Scales instances back from pixels on screen to layout pixels with the
+/// given density.
+pub(crate) fn unscale_instances(
+    instances: &mut [Instance],
+    density: (f32, f32),
+) {
+    for instance in instances {
+        instance.unscale(density);
+    }
+}
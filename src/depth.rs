@@ -0,0 +1,45 @@
+use crate::pipeline::Transform;
+use crate::Instance;
+
+/// The order in which the instances of a `GlyphBrush` are drawn, by their
+/// depth after the transform of the draw.
+///
+/// Translucent text in 3D blends correctly when drawn back to front, without
+/// a depth prepass. Drawing opaque text front to back lets the depth test
+/// reject hidden fragments early instead.
+///
+/// Use it with
+/// [`GlyphBrushBuilder::depth_order`](struct.GlyphBrushBuilder.html#method.depth_order).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthOrder {
+    /// Instances with larger depths are drawn first.
+    BackToFront,
+    /// Instances with smaller depths are drawn first.
+    FrontToBack,
+}
+
+/// Sorts instances by the depth of their center after the given transform.
+///
+/// The sort is stable, so instances at the same depth keep the order they
+/// were queued in.
+pub(crate) fn sort(
+    instances: &mut Vec<Instance>,
+    order: DepthOrder,
+    transform: &Transform,
+) {
+    let mut sorted: Vec<_> = instances
+        .drain(..)
+        .map(|instance| (transform.depth(instance.center()), instance))
+        .collect();
+
+    match order {
+        DepthOrder::BackToFront => {
+            sorted.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        }
+        DepthOrder::FrontToBack => {
+            sorted.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        }
+    }
+
+    instances.extend(sorted.into_iter().map(|(_, instance)| instance));
+}
@@ -0,0 +1,169 @@
+use glyph_brush::ab_glyph::{point, Font, Rect};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, HorizontalAlign, Layout, LineBreaker,
+    SectionGeometry, SectionGlyph, ToSectionText,
+};
+
+/// The base direction of the text of a section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    /// Resolved from the first character with a strong direction in the
+    /// text, defaulting to left-to-right.
+    #[default]
+    Auto,
+    /// Left-to-right, like Latin or Cyrillic text.
+    LeftToRight,
+    /// Right-to-left, like Arabic or Hebrew text.
+    RightToLeft,
+}
+
+/// A horizontal alignment relative to the base direction of the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Alignment {
+    /// Aligned to the left in left-to-right text, to the right in
+    /// right-to-left text.
+    #[default]
+    Start,
+    /// Centered.
+    Center,
+    /// Aligned to the right in left-to-right text, to the left in
+    /// right-to-left text.
+    End,
+}
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that aligns the text of
+/// a section to the start or the end of its lines, depending on the base
+/// direction of the text.
+///
+/// The horizontal alignment of the wrapped layout is replaced by the one the
+/// [`Alignment`] resolves to, so right-to-left interfaces can share the
+/// alignments of their left-to-right counterparts. The screen position of
+/// the section is the anchor of the resolved alignment, like with the
+/// alignments of [`Layout`](enum.Layout.html).
+///
+/// The glyphs are still laid out from left to right.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Directional<B: LineBreaker = BuiltInLineBreaker> {
+    layout: Layout<B>,
+    alignment: Alignment,
+    direction: Direction,
+}
+
+impl<B: LineBreaker> Directional<B> {
+    /// Wraps the given layout, aligning its text with the given alignment
+    /// and resolving the direction from the text.
+    pub fn new(layout: Layout<B>, alignment: Alignment) -> Self {
+        Directional {
+            layout,
+            alignment,
+            direction: Direction::Auto,
+        }
+    }
+
+    /// Sets the base direction of the text, instead of resolving it from the
+    /// text.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns the base direction of the given texts, resolving
+    /// [`Direction::Auto`].
+    pub fn resolve_direction<S: ToSectionText>(
+        &self,
+        sections: &[S],
+    ) -> Direction {
+        match self.direction {
+            Direction::Auto => sections
+                .iter()
+                .flat_map(|section| section.to_section_text().text.chars())
+                .find_map(strong_direction)
+                .unwrap_or(Direction::LeftToRight),
+            direction => direction,
+        }
+    }
+
+    fn layout_for(&self, direction: Direction) -> Layout<B> {
+        let h_align = match (self.alignment, direction) {
+            (Alignment::Center, _) => HorizontalAlign::Center,
+            (Alignment::Start, Direction::RightToLeft)
+            | (Alignment::End, Direction::LeftToRight | Direction::Auto) => {
+                HorizontalAlign::Right
+            }
+            (Alignment::Start, _) | (Alignment::End, _) => {
+                HorizontalAlign::Left
+            }
+        };
+
+        self.layout.h_align(h_align)
+    }
+}
+
+impl<B: LineBreaker> GlyphPositioner for Directional<B> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        self.layout_for(self.resolve_direction(sections))
+            .calculate_glyphs(fonts, geometry, sections)
+    }
+
+    /// Returns the bounds of the resolved alignment. When the direction is
+    /// resolved from the text, the bounds of both directions are joined.
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        match self.direction {
+            Direction::Auto => {
+                let ltr = self
+                    .layout_for(Direction::LeftToRight)
+                    .bounds_rect(geometry);
+                let rtl = self
+                    .layout_for(Direction::RightToLeft)
+                    .bounds_rect(geometry);
+
+                Rect {
+                    min: point(
+                        ltr.min.x.min(rtl.min.x),
+                        ltr.min.y.min(rtl.min.y),
+                    ),
+                    max: point(
+                        ltr.max.x.max(rtl.max.x),
+                        ltr.max.y.max(rtl.max.y),
+                    ),
+                }
+            }
+            direction => self.layout_for(direction).bounds_rect(geometry),
+        }
+    }
+}
+
+/// Returns the direction of a character with a strong direction, using the
+/// blocks of the right-to-left scripts.
+fn strong_direction(c: char) -> Option<Direction> {
+    if !c.is_alphabetic() {
+        return None;
+    }
+
+    let rtl = matches!(
+        c as u32,
+        0x0590..=0x08FF
+            | 0xFB1D..=0xFDFF
+            | 0xFE70..=0xFEFF
+            | 0x10800..=0x10FFF
+            | 0x1E800..=0x1EFFF
+    );
+
+    Some(if rtl {
+        Direction::RightToLeft
+    } else {
+        Direction::LeftToRight
+    })
+}
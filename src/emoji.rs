@@ -0,0 +1,376 @@
+use crate::fallback;
+
+use glyph_brush::ab_glyph::{Font, GlyphId};
+use glyph_brush::{FontId, Section, SectionText, ToSectionText};
+use std::ops::Range;
+use ttf_parser::gsub::{LigatureSubstitution, SubstitutionSubtable};
+use ttf_parser::{Face, Tag};
+
+/// The OpenType features emoji fonts compose sequences with.
+const FEATURES: [&[u8; 4]; 3] = [b"ccmp", b"liga", b"rlig"];
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+const KEYCAP: char = '\u{20E3}';
+
+/// An emoji sequence of a section, like an emoji with a skin tone modifier,
+/// a family joined with zero width joiners, a flag or a keycap.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EmojiSequence {
+    /// The index of the text of the section the sequence is in.
+    pub section_index: usize,
+    /// The byte range of the sequence in its text.
+    pub bytes: Range<usize>,
+    /// The font the sequence is drawn with, which is a fallback font if the
+    /// font of the text has no glyph for its first emoji.
+    pub font_id: FontId,
+    /// Whether the font composes the sequence into a single glyph.
+    /// Otherwise, its emoji are drawn one after the other, without the
+    /// invisible joiners, selectors and tags between them.
+    pub composed: bool,
+}
+
+/// An emoji sequence of a text, with the font it is drawn with and the
+/// glyph composing it in that font, if any.
+pub(crate) struct Sequence {
+    pub(crate) bytes: Range<usize>,
+    pub(crate) font_id: FontId,
+    pub(crate) glyph: Option<GlyphId>,
+}
+
+/// Returns the emoji sequences of a section, with the fonts they are drawn
+/// with given the fallback fonts of the brush.
+pub(crate) fn sequences<F: Font, X>(
+    fonts: &[F],
+    fallbacks: &[FontId],
+    section: &Section<'_, X>,
+) -> Vec<EmojiSequence> {
+    let texts = section.text.iter().map(ToSectionText::to_section_text);
+
+    resolve(fonts, fallbacks, texts)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(section_index, sequences)| {
+            sequences.into_iter().map(move |sequence| EmojiSequence {
+                section_index,
+                bytes: sequence.bytes,
+                font_id: sequence.font_id,
+                composed: sequence.glyph.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the emoji sequences of each text, with the fonts they are drawn
+/// with given the fallback fonts and the glyphs composing them.
+pub(crate) fn resolve<'a, F: Font>(
+    fonts: &[F],
+    fallbacks: &[FontId],
+    texts: impl IntoIterator<Item = SectionText<'a>>,
+) -> Vec<Vec<Sequence>> {
+    // Fonts are only parsed if they have sequences to compose
+    let mut faces: Vec<Option<Option<Face<'_>>>> = vec![None; fonts.len()];
+
+    texts
+        .into_iter()
+        .map(|text| {
+            ranges(text.text)
+                .into_iter()
+                .map(|bytes| {
+                    let sequence = &text.text[bytes.clone()];
+                    let first = sequence.chars().next().unwrap_or_default();
+
+                    let font_id = if fonts[text.font_id.0].glyph_id(first)
+                        != GlyphId(0)
+                    {
+                        text.font_id
+                    } else {
+                        fallback::find(fonts, fallbacks, first)
+                            .unwrap_or(text.font_id)
+                    };
+
+                    let face = faces[font_id.0].get_or_insert_with(|| {
+                        Face::parse(fonts[font_id.0].font_data(), 0).ok()
+                    });
+
+                    Sequence {
+                        glyph: ligature(
+                            &fonts[font_id.0],
+                            face.as_ref(),
+                            sequence,
+                        ),
+                        bytes,
+                        font_id,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the byte ranges of the emoji sequences of a text.
+///
+/// Sequences are recognized like in Unicode Technical Standard #51, with
+/// a rough approximation of the `Extended_Pictographic` property, and only
+/// if they join more than an emoji with its presentation selector.
+fn ranges(text: &str) -> Vec<Range<usize>> {
+    if !may_contain(text) {
+        return Vec::new();
+    }
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte = |index: usize| chars.get(index).map_or(text.len(), |c| c.0);
+    let character = |index: usize| chars.get(index).map(|c| c.1);
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    while let Some(first) = character(start) {
+        if is_regional_indicator(first)
+            && character(start + 1).is_some_and(is_regional_indicator)
+        {
+            ranges.push(byte(start)..byte(start + 2));
+            start += 2;
+            continue;
+        }
+
+        let (mut end, mut joined) = element(&chars, start);
+
+        if is_pictographic(first) {
+            while character(end) == Some(ZERO_WIDTH_JOINER)
+                && character(end + 1).is_some_and(is_pictographic)
+            {
+                end = element(&chars, end + 1).0;
+                joined = true;
+            }
+        }
+
+        if joined {
+            ranges.push(byte(start)..byte(end));
+        }
+
+        start = end;
+    }
+
+    ranges
+}
+
+/// Returns whether a text may contain emoji sequences, i.e. whether it has
+/// any of the characters that join emoji into a sequence, without
+/// allocating.
+///
+/// A presentation selector alone does not make a sequence, so it is not
+/// looked for.
+pub(crate) fn may_contain(text: &str) -> bool {
+    text.chars().any(|character| {
+        character == ZERO_WIDTH_JOINER
+            || character == KEYCAP
+            || is_modifier(character)
+            || is_tag(character)
+            || is_regional_indicator(character)
+    })
+}
+
+/// Returns the index of the character after the emoji element starting at
+/// the given index, and whether the element has a modifier, tags or a
+/// keycap.
+fn element(chars: &[(usize, char)], start: usize) -> (usize, bool) {
+    let character = |index: usize| chars.get(index).map(|c| c.1);
+
+    let mut end = start + 1;
+    let mut joined = false;
+
+    if character(end).is_some_and(is_variation_selector) {
+        end += 1;
+    }
+
+    if character(end).is_some_and(is_modifier) {
+        end += 1;
+        joined = true;
+    }
+
+    while character(end).is_some_and(is_tag) {
+        end += 1;
+        joined = true;
+    }
+
+    if character(end) == Some(KEYCAP) {
+        end += 1;
+        joined = true;
+    }
+
+    (end, joined)
+}
+
+/// Returns the glyph composing the characters of a sequence in the ligature
+/// substitutions of a font, if any.
+///
+/// The sequence is composed with and without its variation selectors, as
+/// fonts differ in whether their ligatures include them.
+fn ligature<F: Font>(
+    font: &F,
+    face: Option<&Face<'_>>,
+    sequence: &str,
+) -> Option<GlyphId> {
+    let gsub = face?.tables().gsub?;
+
+    let substitutions: Vec<LigatureSubstitution<'_>> = FEATURES
+        .iter()
+        .filter_map(|feature| gsub.features.find(Tag::from_bytes(feature)))
+        .flat_map(|feature| feature.lookup_indices)
+        .filter_map(|index| gsub.lookups.get(index))
+        .flat_map(|lookup| {
+            lookup.subtables.into_iter::<SubstitutionSubtable<'_>>()
+        })
+        .filter_map(|subtable| match subtable {
+            SubstitutionSubtable::Ligature(ligature) => Some(ligature),
+            _ => None,
+        })
+        .collect();
+
+    if substitutions.is_empty() {
+        return None;
+    }
+
+    let glyphs = |selectors: bool| {
+        sequence
+            .chars()
+            .filter(|character| selectors || !is_variation_selector(*character))
+            .map(|character| ttf_parser::GlyphId(font.glyph_id(character).0))
+            .collect()
+    };
+
+    [true, false]
+        .into_iter()
+        .find_map(|selectors| substitute(&substitutions, glyphs(selectors)))
+}
+
+/// Applies the ligature substitutions to the glyphs until they are composed
+/// into a single glyph, returning it if they are.
+fn substitute(
+    substitutions: &[LigatureSubstitution<'_>],
+    mut glyphs: Vec<ttf_parser::GlyphId>,
+) -> Option<GlyphId> {
+    let mut substituted = false;
+
+    // Fonts may compose sequences in several steps, e.g. a family from the
+    // couple of its parents
+    while glyphs.len() > 1 {
+        let ligature = (0..glyphs.len()).find_map(|start| {
+            substitutions.iter().find_map(|substitution| {
+                let set = substitution
+                    .ligature_sets
+                    .get(substitution.coverage.get(glyphs[start])?)?;
+
+                set.into_iter().find_map(|ligature| {
+                    let end =
+                        start + 1 + usize::from(ligature.components.len());
+
+                    let matches = end <= glyphs.len()
+                        && ligature
+                            .components
+                            .into_iter()
+                            .eq(glyphs[start + 1..end].iter().copied());
+
+                    matches.then_some((start..end, ligature.glyph))
+                })
+            })
+        });
+
+        let (range, glyph) = ligature?;
+
+        let _ = glyphs.splice(range, [glyph]);
+        substituted = true;
+    }
+
+    substituted.then(|| GlyphId(glyphs[0].0))
+}
+
+/// Returns whether a character of a sequence is not drawn if the sequence
+/// is not composed.
+pub(crate) fn is_invisible(character: char) -> bool {
+    character == ZERO_WIDTH_JOINER
+        || is_variation_selector(character)
+        || is_tag(character)
+}
+
+fn is_variation_selector(character: char) -> bool {
+    matches!(character, '\u{FE00}'..='\u{FE0F}')
+}
+
+fn is_modifier(character: char) -> bool {
+    matches!(character, '\u{1F3FB}'..='\u{1F3FF}')
+}
+
+fn is_tag(character: char) -> bool {
+    matches!(character, '\u{E0020}'..='\u{E007F}')
+}
+
+fn is_regional_indicator(character: char) -> bool {
+    matches!(character, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+fn is_pictographic(character: char) -> bool {
+    matches!(
+        character,
+        '\u{00A9}'
+            | '\u{00AE}'
+            | '\u{203C}'
+            | '\u{2049}'
+            | '\u{2122}'
+            | '\u{2139}'
+            | '\u{2194}'..='\u{2BFF}'
+            | '\u{3030}'
+            | '\u{303D}'
+            | '\u{3297}'
+            | '\u{3299}'
+            | '\u{1F000}'..='\u{1FAFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the start and end of the byte ranges of the sequences.
+    fn bytes(text: &str) -> Vec<(usize, usize)> {
+        ranges(text)
+            .into_iter()
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    #[test]
+    fn ranges_of_sequences() {
+        // A thumbs up with a skin tone modifier
+        assert_eq!(bytes("a\u{1F44D}\u{1F3FD}b"), [(1, 9)]);
+
+        // A family joined with zero width joiners
+        assert_eq!(
+            bytes("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"),
+            [(0, 18)]
+        );
+
+        // Two flags of regional indicators
+        assert_eq!(
+            bytes("\u{1F1EB}\u{1F1F7}\u{1F1E9}\u{1F1EA}"),
+            [(0, 8), (8, 16)]
+        );
+
+        // A keycap with its presentation selector
+        assert_eq!(bytes("1\u{FE0F}\u{20E3}"), [(0, 7)]);
+    }
+
+    #[test]
+    fn ranges_without_sequences() {
+        assert_eq!(bytes("Hello, world!"), []);
+
+        // A presentation selector alone does not make a sequence
+        assert_eq!(bytes("\u{2764}\u{FE0F}"), []);
+
+        // Joiners only join pictographs
+        assert_eq!(bytes("\u{1F468}\u{200D}a"), []);
+
+        // A lone regional indicator is not a flag
+        assert_eq!(bytes("\u{1F1EB}"), []);
+    }
+}
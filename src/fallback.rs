@@ -0,0 +1,381 @@
+use crate::emoji;
+
+use glyph_brush::ab_glyph::{Font, GlyphId, PxScale, Rect};
+use glyph_brush::{
+    FontId, GlyphPositioner, Section, SectionGeometry, SectionGlyph,
+    SectionText, ToSectionText,
+};
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// A character with no glyph in the font selected for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MissingGlyph {
+    /// The character that is missing.
+    pub character: char,
+    /// The font selected for the character.
+    pub font_id: FontId,
+    /// The fallback font used to draw the character instead, if any.
+    pub fallback: Option<FontId>,
+}
+
+/// What is drawn for characters that have no glyph in their font nor in any
+/// of the fallback fonts.
+#[derive(Clone, Default)]
+pub enum Notdef {
+    /// Nothing is drawn and the character takes no space.
+    Hidden,
+    /// The `.notdef` glyph of the font, usually a box, is drawn.
+    #[default]
+    Glyph,
+    /// The given replacement character is drawn, e.g. `'\u{FFFD}'`.
+    Replace(char),
+    /// The string returned by the callback for the missing character is
+    /// drawn.
+    Substitute(Arc<dyn Fn(char) -> String + Send + Sync>),
+}
+
+impl Notdef {
+    /// Creates a [`Notdef::Substitute`] policy with the given callback.
+    pub fn substitute(
+        substitute: impl Fn(char) -> String + Send + Sync + 'static,
+    ) -> Notdef {
+        Notdef::Substitute(Arc::new(substitute))
+    }
+}
+
+impl fmt::Debug for Notdef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Notdef::Hidden => write!(f, "Hidden"),
+            Notdef::Glyph => write!(f, "Glyph"),
+            Notdef::Replace(character) => write!(f, "Replace({character:?})"),
+            Notdef::Substitute(_) => write!(f, "Substitute(..)"),
+        }
+    }
+}
+
+impl Hash for Notdef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+
+        match self {
+            Notdef::Hidden | Notdef::Glyph => {}
+            Notdef::Replace(character) => character.hash(state),
+            Notdef::Substitute(substitute) => {
+                Arc::as_ptr(substitute).cast::<()>().hash(state)
+            }
+        }
+    }
+}
+
+/// The fallback state of a `GlyphBrush`.
+#[derive(Default)]
+pub(crate) struct Fallback {
+    pub(crate) fonts: Vec<FontId>,
+    pub(crate) notdef: Notdef,
+    pub(crate) on_missing: Option<Box<dyn FnMut(MissingGlyph) + Send + Sync>>,
+    reported: HashSet<(FontId, char)>,
+}
+
+impl Fallback {
+    /// Reports the characters of the section that are missing in their
+    /// fonts, once per font and character.
+    pub(crate) fn report<F: Font, X>(
+        &mut self,
+        fonts: &[F],
+        section: &Section<'_, X>,
+    ) {
+        let Some(on_missing) = &mut self.on_missing else {
+            return;
+        };
+
+        for text in &section.text {
+            for character in text.text.chars() {
+                if character.is_control()
+                    || fonts[text.font_id.0].glyph_id(character).0 != 0
+                    || !self.reported.insert((text.font_id, character))
+                {
+                    continue;
+                }
+
+                on_missing(MissingGlyph {
+                    character,
+                    font_id: text.font_id,
+                    fallback: find(fonts, &self.fonts, character),
+                });
+            }
+        }
+    }
+
+    /// Wraps the given layout so missing glyphs are drawn using the fallback
+    /// fonts or replaced according to the [`Notdef`] policy.
+    pub(crate) fn layout<'a, L>(&'a self, layout: &'a L) -> Layout<'a, L> {
+        Layout {
+            layout,
+            fonts: &self.fonts,
+            notdef: &self.notdef,
+        }
+    }
+
+    /// Forgets the characters reported so far, so they are reported again.
+    pub(crate) fn reset(&mut self) {
+        self.reported.clear();
+    }
+}
+
+/// A [`GlyphPositioner`] that draws missing characters using the first
+/// fallback font that contains them, replacing the rest according to a
+/// [`Notdef`] policy, and composes emoji sequences, before the inner layout
+/// breaks the texts into lines.
+///
+/// Texts are split into runs of a single font for the inner layout, so,
+/// like between the texts of a section, lines are not broken where a run
+/// ends.
+#[derive(Debug)]
+pub(crate) struct Layout<'a, L> {
+    layout: &'a L,
+    fonts: &'a [FontId],
+    notdef: &'a Notdef,
+}
+
+impl<L> Layout<'_, L> {
+    /// Splits the texts into runs of a single font, with their missing
+    /// characters and emoji sequences substituted.
+    fn runs<F: Font>(
+        &self,
+        fonts: &[F],
+        texts: &[SectionText<'_>],
+        sequences: &[Vec<emoji::Sequence>],
+    ) -> Vec<Run> {
+        let mut runs: Vec<Run> = Vec::new();
+
+        for (section_index, (text, sequences)) in
+            texts.iter().zip(sequences).enumerate()
+        {
+            let resolve = |character: char| {
+                if fonts[text.font_id.0].glyph_id(character) != GlyphId(0) {
+                    text.font_id
+                } else {
+                    find(fonts, self.fonts, character).unwrap_or(text.font_id)
+                }
+            };
+
+            let mut push = |character: char,
+                            font_id: FontId,
+                            byte_index: usize,
+                            glyph: Option<GlyphId>| {
+                if !matches!(
+                    runs.last(),
+                    Some(run) if run.section_index == section_index
+                        && run.font_id == font_id
+                ) {
+                    runs.push(Run {
+                        section_index,
+                        text: String::new(),
+                        scale: text.scale,
+                        font_id,
+                        characters: Vec::new(),
+                    });
+                }
+
+                let run = runs.last_mut().expect("Run of the character");
+
+                run.characters.push((run.text.len(), byte_index, glyph));
+                run.text.push(character);
+            };
+
+            let mut sequences = sequences.iter().peekable();
+
+            for (byte_index, character) in text.text.char_indices() {
+                while sequences
+                    .next_if(|sequence| sequence.bytes.end <= byte_index)
+                    .is_some()
+                {}
+
+                if let Some(sequence) = sequences
+                    .peek()
+                    .filter(|sequence| sequence.bytes.contains(&byte_index))
+                {
+                    match sequence.glyph {
+                        // Composed sequences are laid out as their first
+                        // emoji, which has the advance of the composed
+                        // glyph in emoji fonts
+                        Some(glyph) => {
+                            if byte_index == sequence.bytes.start {
+                                push(
+                                    character,
+                                    sequence.font_id,
+                                    byte_index,
+                                    Some(glyph),
+                                );
+                            }
+
+                            continue;
+                        }
+                        None if emoji::is_invisible(character) => continue,
+                        None => {}
+                    }
+                }
+
+                if !is_missing(fonts, text.font_id, character) {
+                    push(character, text.font_id, byte_index, None);
+                    continue;
+                }
+
+                if let Some(font_id) = find(fonts, self.fonts, character) {
+                    push(character, font_id, byte_index, None);
+                    continue;
+                }
+
+                match self.notdef {
+                    Notdef::Glyph => {
+                        push(character, text.font_id, byte_index, None)
+                    }
+                    Notdef::Hidden => {}
+                    Notdef::Replace(replacement) => push(
+                        *replacement,
+                        resolve(*replacement),
+                        byte_index,
+                        None,
+                    ),
+                    Notdef::Substitute(substitute) => {
+                        for replacement in substitute(character).chars() {
+                            push(
+                                replacement,
+                                resolve(replacement),
+                                byte_index,
+                                None,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        runs
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Layout<'_, L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        // Texts are scanned without allocating first, as most of them have
+        // neither missing characters nor emoji sequences
+        let substituted = (!self.fonts.is_empty()
+            || !matches!(self.notdef, Notdef::Glyph))
+            && sections.iter().any(|section| {
+                let text = section.to_section_text();
+
+                text.text
+                    .chars()
+                    .any(|character| is_missing(fonts, text.font_id, character))
+            });
+
+        let composed = sections
+            .iter()
+            .any(|section| emoji::may_contain(section.to_section_text().text));
+
+        if !substituted && !composed {
+            return self.layout.calculate_glyphs(fonts, geometry, sections);
+        }
+
+        let texts: Vec<SectionText<'_>> = sections
+            .iter()
+            .map(ToSectionText::to_section_text)
+            .collect();
+
+        let sequences =
+            emoji::resolve(fonts, self.fonts, texts.iter().copied());
+
+        let runs = self.runs(fonts, &texts, &sequences);
+        let texts: Vec<SectionText<'_>> = runs.iter().map(Run::text).collect();
+
+        let mut glyphs = self.layout.calculate_glyphs(fonts, geometry, &texts);
+
+        for glyph in &mut glyphs {
+            runs[glyph.section_index].restore(glyph);
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+/// A run of a text of a section drawn with a single font, with its missing
+/// characters and emoji sequences substituted.
+struct Run {
+    section_index: usize,
+    text: String,
+    scale: PxScale,
+    font_id: FontId,
+    /// The byte index of each character in the run and in the text of the
+    /// section, and the glyph composing the sequence it starts, if any.
+    characters: Vec<(usize, usize, Option<GlyphId>)>,
+}
+
+impl Run {
+    fn text(&self) -> SectionText<'_> {
+        SectionText {
+            text: &self.text,
+            scale: self.scale,
+            font_id: self.font_id,
+        }
+    }
+
+    /// Points a glyph laid out from the run back to the text of its section.
+    fn restore(&self, glyph: &mut SectionGlyph) {
+        if let Ok(index) = self
+            .characters
+            .binary_search_by_key(&glyph.byte_index, |character| character.0)
+        {
+            let (_, byte_index, composed) = self.characters[index];
+
+            glyph.byte_index = byte_index;
+
+            if let Some(id) = composed {
+                glyph.glyph.id = id;
+            }
+        }
+
+        glyph.section_index = self.section_index;
+    }
+}
+
+impl<L: Hash> Hash for Layout<'_, L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.fonts.hash(state);
+        self.notdef.hash(state);
+    }
+}
+
+/// Returns whether a character has no glyph in its font.
+fn is_missing<F: Font>(fonts: &[F], font_id: FontId, character: char) -> bool {
+    !character.is_control()
+        && fonts[font_id.0].glyph_id(character) == GlyphId(0)
+}
+
+pub(crate) fn find<F: Font>(
+    fonts: &[F],
+    fallbacks: &[FontId],
+    character: char,
+) -> Option<FontId> {
+    fallbacks
+        .iter()
+        .copied()
+        .find(|id| fonts[id.0].glyph_id(character).0 != 0)
+}
@@ -0,0 +1,233 @@
+use glyph_brush::ab_glyph::{Font, GlyphId, Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    SectionText, ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+use ttf_parser::gsub::{SingleSubstitution, SubstitutionSubtable};
+use ttf_parser::{Face, Tag};
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that draws the digits
+/// of some texts of a section with tabular figures or a slashed zero, e.g.
+/// to align the numbers of a data table.
+///
+/// Glyphs are not shaped, so only the single substitutions of the `tnum` and
+/// `zero` OpenType features of a font are applied to its digits. Tabular
+/// figures are synthesized for fonts without a `tnum` feature, centering
+/// every digit in the advance of the widest one.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+///
+/// Line breaking is performed by the inner layout before widening the
+/// digits, so wrapped lines may slightly exceed their bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Figures<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    tabular: Texts,
+    slashed_zero: Texts,
+}
+
+/// The texts of a section a style of figures applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Texts {
+    all: bool,
+    indices: Vec<usize>,
+}
+
+impl Texts {
+    fn push(&mut self, index: usize) {
+        if !self.indices.contains(&index) {
+            self.indices.push(index);
+        }
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.all || self.indices.contains(&index)
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.all && self.indices.is_empty()
+    }
+}
+
+impl<L> Figures<L> {
+    /// Wraps the given layout, keeping the figures of every text as is.
+    pub fn new(layout: L) -> Self {
+        Figures {
+            layout,
+            tabular: Texts::default(),
+            slashed_zero: Texts::default(),
+        }
+    }
+
+    /// Uses tabular figures for all the texts of the section.
+    pub fn tabular(mut self) -> Self {
+        self.tabular.all = true;
+        self
+    }
+
+    /// Uses tabular figures for the text with the given index in the
+    /// section.
+    pub fn tabular_text(mut self, index: usize) -> Self {
+        self.tabular.push(index);
+        self
+    }
+
+    /// Uses a slashed zero for all the texts of the section.
+    pub fn slashed_zero(mut self) -> Self {
+        self.slashed_zero.all = true;
+        self
+    }
+
+    /// Uses a slashed zero for the text with the given index in the section.
+    pub fn slashed_zero_text(mut self, index: usize) -> Self {
+        self.slashed_zero.push(index);
+        self
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Figures<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs =
+            self.layout.calculate_glyphs(fonts, geometry, sections);
+
+        if self.tabular.is_empty() && self.slashed_zero.is_empty() {
+            return glyphs;
+        }
+
+        let texts: Vec<SectionText<'_>> = sections
+            .iter()
+            .map(ToSectionText::to_section_text)
+            .collect();
+
+        // Fonts are only parsed if they have digits to substitute
+        let mut faces: Vec<Option<Option<Face<'_>>>> = vec![None; fonts.len()];
+
+        let mut line_y = f32::NAN;
+        let mut shift = 0.0;
+
+        for glyph in &mut glyphs {
+            if glyph.glyph.position.y != line_y {
+                line_y = glyph.glyph.position.y;
+                shift = 0.0;
+            }
+
+            glyph.glyph.position.x += shift;
+
+            let index = glyph.section_index;
+            let tabular = self.tabular.contains(index);
+            let slashed_zero = self.slashed_zero.contains(index);
+
+            let character =
+                texts[index].text[glyph.byte_index..].chars().next();
+
+            let Some(character) = character.filter(char::is_ascii_digit) else {
+                continue;
+            };
+
+            let zero = slashed_zero && character == '0';
+
+            if !tabular && !zero {
+                continue;
+            }
+
+            let font_id = glyph.font_id.0;
+            let font = fonts[font_id].as_scaled(glyph.glyph.scale);
+            let face = faces[font_id].get_or_insert_with(|| {
+                Face::parse(fonts[font_id].font_data(), 0).ok()
+            });
+
+            let advance = font.h_advance(glyph.glyph.id);
+            let mut id = glyph.glyph.id;
+            let mut width = None;
+
+            if tabular {
+                match substitute(face.as_ref(), b"tnum", id) {
+                    Some(substitute) => id = substitute,
+                    None => width = Some(widest_digit(&font)),
+                }
+            }
+
+            if zero {
+                id = substitute(face.as_ref(), b"zero", id).unwrap_or(id);
+            }
+
+            let substitute_advance = font.h_advance(id);
+            let width = width.unwrap_or(substitute_advance);
+
+            glyph.glyph.id = id;
+            glyph.glyph.position.x += (width - substitute_advance) / 2.0;
+
+            shift += width - advance;
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for Figures<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.tabular.hash(state);
+        self.slashed_zero.hash(state);
+    }
+}
+
+/// Returns the glyph substituting the given one in the single substitutions
+/// of an OpenType feature of a font, if any.
+fn substitute(
+    face: Option<&Face<'_>>,
+    feature: &[u8; 4],
+    glyph: GlyphId,
+) -> Option<GlyphId> {
+    let gsub = face?.tables().gsub?;
+    let feature = gsub.features.find(Tag::from_bytes(feature))?;
+    let glyph = ttf_parser::GlyphId(glyph.0);
+
+    feature
+        .lookup_indices
+        .into_iter()
+        .filter_map(|index| gsub.lookups.get(index))
+        .flat_map(|lookup| {
+            lookup.subtables.into_iter::<SubstitutionSubtable<'_>>()
+        })
+        .find_map(|subtable| {
+            let SubstitutionSubtable::Single(single) = subtable else {
+                return None;
+            };
+
+            let index = single.coverage().get(glyph)?;
+
+            let substitute = match single {
+                SingleSubstitution::Format1 { delta, .. } => {
+                    ttf_parser::GlyphId(glyph.0.wrapping_add(delta as u16))
+                }
+                SingleSubstitution::Format2 { substitutes, .. } => {
+                    substitutes.get(index)?
+                }
+            };
+
+            Some(GlyphId(substitute.0))
+        })
+}
+
+/// Returns the advance of the widest digit of a font.
+fn widest_digit<F: Font>(font: &impl ScaleFont<F>) -> f32 {
+    ('0'..='9')
+        .map(|digit| font.h_advance(font.glyph_id(digit)))
+        .fold(0.0, f32::max)
+}
@@ -0,0 +1,254 @@
+use crate::ab_glyph::{point, Font, PxScale, Rect, ScaleFont};
+use crate::{Extra, FontId, Instance, SectionGlyph};
+
+/// A grid of monospaced characters with fixed cell sizes, like the screen of
+/// a terminal emulator or a debug console.
+///
+/// Cells are addressed by row and column, and every cell has its own
+/// foreground and background colors. Characters are placed in their cells
+/// directly instead of laying out text, so large grids are cheap to queue.
+///
+/// Queue it with
+/// [`GlyphBrush::queue_grid`](struct.GlyphBrush.html#method.queue_grid).
+#[derive(Debug, Clone)]
+pub struct TextGrid {
+    screen_position: (f32, f32),
+    cell_size: (f32, f32),
+    columns: usize,
+    rows: usize,
+    font_id: FontId,
+    scale: PxScale,
+    cells: Vec<Cell>,
+}
+
+/// A cell of a [`TextGrid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    /// The character of the cell.
+    pub character: char,
+    /// The color of the character.
+    pub foreground: [f32; 4],
+    /// The color of the cell, if any.
+    pub background: Option<[f32; 4]>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            character: ' ',
+            foreground: [0.0, 0.0, 0.0, 1.0],
+            background: None,
+        }
+    }
+}
+
+impl TextGrid {
+    /// Creates a [`TextGrid`] of empty cells with the given dimensions and
+    /// cell size, in pixels.
+    ///
+    /// The scale of the characters defaults to the height of the cells.
+    pub fn new(columns: usize, rows: usize, cell_size: (f32, f32)) -> Self {
+        TextGrid {
+            screen_position: (0.0, 0.0),
+            cell_size,
+            columns,
+            rows,
+            font_id: FontId::default(),
+            scale: PxScale::from(cell_size.1),
+            cells: vec![Cell::default(); columns * rows],
+        }
+    }
+
+    /// Sets the position on screen of the grid, in pixels from top-left.
+    pub fn with_screen_position<P: Into<(f32, f32)>>(
+        mut self,
+        position: P,
+    ) -> Self {
+        self.screen_position = position.into();
+        self
+    }
+
+    /// Sets the font of the characters, which should be monospaced.
+    pub fn with_font_id(mut self, font_id: FontId) -> Self {
+        self.font_id = font_id;
+        self
+    }
+
+    /// Sets the scale of the characters.
+    pub fn with_scale<S: Into<PxScale>>(mut self, scale: S) -> Self {
+        self.scale = scale.into();
+        self
+    }
+
+    /// Returns the amount of columns of the grid.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the amount of rows of the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Resizes the grid, keeping the cells that still fit in it.
+    pub fn resize(&mut self, columns: usize, rows: usize) {
+        let mut cells = vec![Cell::default(); columns * rows];
+
+        for row in 0..rows.min(self.rows) {
+            for column in 0..columns.min(self.columns) {
+                cells[row * columns + column] =
+                    self.cells[row * self.columns + column];
+            }
+        }
+
+        self.columns = columns;
+        self.rows = rows;
+        self.cells = cells;
+    }
+
+    /// Returns the cell at the given row and column, if it is in the grid.
+    pub fn cell(&self, row: usize, column: usize) -> Option<&Cell> {
+        self.index(row, column).map(|index| &self.cells[index])
+    }
+
+    /// Returns the cell at the given row and column mutably, if it is in the
+    /// grid.
+    pub fn cell_mut(&mut self, row: usize, column: usize) -> Option<&mut Cell> {
+        self.index(row, column).map(|index| &mut self.cells[index])
+    }
+
+    /// Writes the given text in a row, starting at the given column, with the
+    /// given colors.
+    ///
+    /// Every character takes a cell, and characters past the end of the row
+    /// are ignored.
+    pub fn write(
+        &mut self,
+        row: usize,
+        column: usize,
+        text: &str,
+        foreground: [f32; 4],
+        background: Option<[f32; 4]>,
+    ) {
+        let Some(start) = self.index(row, column) else {
+            return;
+        };
+
+        let cells = &mut self.cells[start..(row + 1) * self.columns];
+
+        for (cell, character) in cells.iter_mut().zip(text.chars()) {
+            *cell = Cell {
+                character,
+                foreground,
+                background,
+            };
+        }
+    }
+
+    /// Resets all the cells of the grid.
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+    }
+
+    fn index(&self, row: usize, column: usize) -> Option<usize> {
+        (row < self.rows && column < self.columns)
+            .then_some(row * self.columns + column)
+    }
+
+    fn cell_position(&self, row: usize, column: usize) -> (f32, f32) {
+        (
+            self.screen_position.0 + column as f32 * self.cell_size.0,
+            self.screen_position.1 + row as f32 * self.cell_size.1,
+        )
+    }
+
+    /// Returns the bounds of the grid.
+    pub(crate) fn bounds(&self) -> Rect {
+        let (x, y) = self.cell_position(self.rows, self.columns);
+
+        Rect {
+            min: point(self.screen_position.0, self.screen_position.1),
+            max: point(x, y),
+        }
+    }
+
+    /// Returns the glyphs of the characters of the grid, vertically centered
+    /// in their cells, and their extra data.
+    pub(crate) fn glyphs<F: Font>(
+        &self,
+        fonts: &[F],
+    ) -> (Vec<SectionGlyph>, Vec<Extra>) {
+        let font = fonts[self.font_id.0].as_scaled(self.scale);
+        let baseline = (self.cell_size.1 - font.ascent() + font.descent())
+            / 2.0
+            + font.ascent();
+
+        let mut glyphs = Vec::new();
+        let mut extra = Vec::new();
+
+        for (index, cell) in self.cells.iter().enumerate() {
+            if cell.character.is_whitespace() {
+                continue;
+            }
+
+            let (x, y) =
+                self.cell_position(index / self.columns, index % self.columns);
+
+            glyphs.push(SectionGlyph {
+                section_index: extra.len(),
+                byte_index: 0,
+                glyph: font.glyph_id(cell.character).with_scale_and_position(
+                    self.scale,
+                    point(x, y + baseline),
+                ),
+                font_id: self.font_id,
+            });
+
+            extra.push(Extra {
+                color: cell.foreground,
+                z: 0.0,
+            });
+        }
+
+        (glyphs, extra)
+    }
+
+    /// Returns the instances filling the backgrounds of the cells, merging
+    /// adjacent cells of the same color in a row.
+    pub(crate) fn backgrounds(&self) -> impl Iterator<Item = Instance> + '_ {
+        self.cells.chunks(self.columns.max(1)).enumerate().flat_map(
+            move |(row, cells)| {
+                let mut runs: Vec<(usize, usize, [f32; 4])> = Vec::new();
+
+                for (column, cell) in cells.iter().enumerate() {
+                    let Some(color) = cell.background else {
+                        continue;
+                    };
+
+                    match runs.last_mut() {
+                        Some((_, end, run_color))
+                            if *end == column && *run_color == color =>
+                        {
+                            *end += 1;
+                        }
+                        _ => runs.push((column, column + 1, color)),
+                    }
+                }
+
+                runs.into_iter().map(move |(start, end, color)| {
+                    let (left, top) = self.cell_position(row, start);
+                    let (right, bottom) = self.cell_position(row + 1, end);
+
+                    Instance::solid(
+                        Rect {
+                            min: point(left, top),
+                            max: point(right, bottom),
+                        },
+                        color,
+                        0.0,
+                    )
+                })
+            },
+        )
+    }
+}
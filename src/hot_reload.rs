@@ -0,0 +1,57 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use std::time::SystemTime;
+
+/// Watches a shader file, to reload it when it changes.
+#[derive(Debug)]
+pub(crate) struct Watcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl Watcher {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Watcher {
+            path,
+            modified: None,
+        }
+    }
+
+    /// Returns the contents of the file if it was modified since the last
+    /// call.
+    pub(crate) fn poll(&mut self) -> Option<String> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+
+        if self.modified == Some(modified) {
+            return None;
+        }
+
+        self.modified = Some(modified);
+
+        match std::fs::read_to_string(&self.path) {
+            Ok(source) => Some(source),
+            Err(error) => {
+                log::warn!("Failed to read {}: {error}", self.path.display());
+                None
+            }
+        }
+    }
+}
+
+/// Pops the current error scope of the device, returning its error if it is
+/// already known.
+///
+/// Errors are known right away on native platforms, but they may not be on
+/// the web.
+pub(crate) fn pop_error_scope(device: &wgpu::Device) -> Option<wgpu::Error> {
+    let mut error = pin!(device.pop_error_scope());
+
+    match error.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(error) => error,
+        Poll::Pending => None,
+    }
+}
@@ -0,0 +1,226 @@
+use glyph_brush::ab_glyph::{Font, Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    SectionText, ToSectionText,
+};
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+
+/// The soft hyphen, marking where a word may be hyphenated.
+const SOFT_HYPHEN: char = '\u{ad}';
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that hyphenates the
+/// words of a section at soft hyphens.
+///
+/// The inner layout breaks lines after soft hyphens (U+00AD), which are only
+/// drawn as a hyphen when a line breaks at them. Zero-width spaces (U+200B),
+/// word joiners (U+2060) and zero-width no-break spaces (U+FEFF) control the
+/// line breaks without being drawn. Extra break opportunities, e.g. from a
+/// hyphenation dictionary, are hyphenated like soft hyphens.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+///
+/// The characters that are not drawn are removed after line breaking, so
+/// lines that are not aligned to the left are slightly off.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hyphenation<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    hyphen: char,
+    breaks: Vec<(usize, usize)>,
+}
+
+impl<L> Hyphenation<L> {
+    /// Wraps the given layout, drawing a `-` at the soft hyphens lines break
+    /// at.
+    pub fn new(layout: L) -> Self {
+        Hyphenation {
+            layout,
+            hyphen: '-',
+            breaks: Vec::new(),
+        }
+    }
+
+    /// Sets the character drawn at the end of the lines broken at a soft
+    /// hyphen, e.g. `'\u{2010}'` for fonts with a dedicated hyphen.
+    pub fn hyphen(mut self, hyphen: char) -> Self {
+        self.hyphen = hyphen;
+        self
+    }
+
+    /// Adds break opportunities to the text with the given index in the
+    /// section, at the given byte offsets, as if a soft hyphen was there.
+    ///
+    /// The offsets must be at character boundaries of the text, like the
+    /// ones of the hyphenation points of its words.
+    pub fn with_breaks(
+        mut self,
+        index: usize,
+        breaks: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        self.breaks
+            .extend(breaks.into_iter().map(|byte| (index, byte)));
+        self.breaks.sort_unstable();
+        self.breaks.dedup();
+        self
+    }
+
+    /// Inserts a soft hyphen at the extra break opportunities of the text
+    /// with the given index.
+    fn hyphenate<'a>(&self, index: usize, text: &'a str) -> Hyphenated<'a> {
+        let start = self.breaks.partition_point(|(text, _)| *text < index);
+        let end = self.breaks.partition_point(|(text, _)| *text <= index);
+
+        let breaks = self.breaks[start..end]
+            .iter()
+            .map(|(_, byte)| *byte)
+            .filter(|byte| {
+                (1..text.len()).contains(byte) && text.is_char_boundary(*byte)
+            });
+
+        let mut hyphenated = String::new();
+        let mut inserted = Vec::new();
+        let mut last = 0;
+
+        for byte in breaks {
+            hyphenated.push_str(&text[last..byte]);
+            inserted.push((hyphenated.len(), byte));
+            hyphenated.push(SOFT_HYPHEN);
+            last = byte;
+        }
+
+        if inserted.is_empty() {
+            return Hyphenated {
+                text: Cow::Borrowed(text),
+                inserted,
+            };
+        }
+
+        hyphenated.push_str(&text[last..]);
+
+        Hyphenated {
+            text: Cow::Owned(hyphenated),
+            inserted,
+        }
+    }
+}
+
+/// A text with the soft hyphens of its extra break opportunities.
+struct Hyphenated<'a> {
+    text: Cow<'a, str>,
+    /// The byte index of every inserted soft hyphen, in the hyphenated and
+    /// in the original text, if any.
+    inserted: Vec<(usize, usize)>,
+}
+
+impl Hyphenated<'_> {
+    fn original_byte_index(&self, byte_index: usize) -> usize {
+        let before = self
+            .inserted
+            .partition_point(|(hyphenated, _)| *hyphenated < byte_index);
+
+        match self.inserted.get(before) {
+            Some((hyphenated, original)) if *hyphenated == byte_index => {
+                *original
+            }
+            _ => byte_index - before * SOFT_HYPHEN.len_utf8(),
+        }
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Hyphenation<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let texts: Vec<SectionText<'_>> = sections
+            .iter()
+            .map(ToSectionText::to_section_text)
+            .collect();
+
+        let hyphenated: Vec<Hyphenated<'_>> = texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| self.hyphenate(index, text.text))
+            .collect();
+
+        let mut glyphs = self.layout.calculate_glyphs(
+            fonts,
+            geometry,
+            &texts
+                .iter()
+                .zip(&hyphenated)
+                .map(|(text, hyphenated)| SectionText {
+                    text: &hyphenated.text,
+                    ..*text
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let mut line_y = f32::NAN;
+        let mut shift = 0.0;
+        let mut index = 0;
+
+        while index < glyphs.len() {
+            let next_y =
+                glyphs.get(index + 1).map(|next| next.glyph.position.y);
+            let glyph = &mut glyphs[index];
+
+            if glyph.glyph.position.y != line_y {
+                line_y = glyph.glyph.position.y;
+                shift = 0.0;
+            }
+
+            glyph.glyph.position.x += shift;
+
+            let text = &hyphenated[glyph.section_index];
+            let character = text.text[glyph.byte_index..].chars().next();
+
+            glyph.byte_index = text.original_byte_index(glyph.byte_index);
+
+            let hidden = match character {
+                Some(SOFT_HYPHEN) => {
+                    let breaks_line = next_y.is_some_and(|y| y != line_y);
+
+                    if breaks_line {
+                        glyph.glyph.id =
+                            fonts[glyph.font_id.0].glyph_id(self.hyphen);
+                    }
+
+                    !breaks_line
+                }
+                Some('\u{200b}' | '\u{2060}' | '\u{feff}') => true,
+                _ => false,
+            };
+
+            if hidden {
+                let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+                shift -= font.h_advance(glyph.glyph.id);
+
+                let _ = glyphs.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for Hyphenation<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.hyphen.hash(state);
+        self.breaks.hash(state);
+    }
+}
@@ -0,0 +1,157 @@
+use glyph_brush::ab_glyph::{point, Font, PxScale, Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    SectionText, ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+
+/// The character laid out in place of the texts reserving inline boxes.
+const OBJECT_REPLACEMENT: &str = "\u{FFFC}";
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that reserves boxes
+/// inline with the text of a section, e.g. for icons or images drawn by
+/// another renderer.
+///
+/// A box takes the place of a whole text of the section, whose content is
+/// ignored, so it can be empty. It is laid out like a single character of
+/// the given width, and its line is at least as tall as the box, so the text
+/// wraps around it.
+/// Nothing is drawn in the box.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout),
+/// and get the final positions of the boxes with
+/// [`GlyphBrush::inline_boxes`](struct.GlyphBrush.html#method.inline_boxes).
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineBoxes<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    boxes: Vec<(usize, f32, f32)>,
+}
+
+impl<L> InlineBoxes<L> {
+    /// Wraps the given layout, without any boxes.
+    pub fn new(layout: L) -> Self {
+        InlineBoxes {
+            layout,
+            boxes: Vec::new(),
+        }
+    }
+
+    /// Reserves a box of the given size, in pixels, in place of the text
+    /// with the given index in the section.
+    pub fn inline_box(mut self, index: usize, width: f32, height: f32) -> Self {
+        self.boxes.retain(|(i, _, _)| *i != index);
+        self.boxes.push((index, width, height));
+        self
+    }
+
+    /// Returns the size of the box reserved in place of the text with the
+    /// given index, if any.
+    pub fn box_of(&self, index: usize) -> Option<(f32, f32)> {
+        self.boxes
+            .iter()
+            .find(|(i, _, _)| *i == index)
+            .map(|(_, width, height)| (*width, *height))
+    }
+
+    /// Returns the boxes of the given glyphs laid out with this layout, with
+    /// the indices of the texts they replace.
+    pub(crate) fn rects<F: Font>(
+        &self,
+        fonts: &[F],
+        glyphs: &[SectionGlyph],
+    ) -> Vec<(usize, Rect)> {
+        glyphs
+            .iter()
+            .filter_map(|glyph| {
+                let (width, _) = self.box_of(glyph.section_index)?;
+                let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+                let position = glyph.glyph.position;
+
+                Some((
+                    glyph.section_index,
+                    Rect {
+                        min: point(position.x, position.y - font.ascent()),
+                        max: point(
+                            position.x + width,
+                            position.y - font.descent(),
+                        ),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for InlineBoxes<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let sections: Vec<SectionText<'_>> = sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                let text = section.to_section_text();
+
+                let Some((width, height)) = self.box_of(index) else {
+                    return text;
+                };
+
+                let font = &fonts[text.font_id.0];
+                let glyph = font.glyph_id('\u{FFFC}');
+                let advance = font.h_advance_unscaled(glyph);
+
+                // The height of the glyph is the height of the scale, so
+                // its advance is scaled to the width of the box.
+                let scale = PxScale {
+                    x: if advance > 0.0 {
+                        width * font.height_unscaled() / advance
+                    } else {
+                        height
+                    },
+                    y: height,
+                };
+
+                SectionText {
+                    text: OBJECT_REPLACEMENT,
+                    scale,
+                    ..text
+                }
+            })
+            .collect();
+
+        let mut glyphs =
+            self.layout.calculate_glyphs(fonts, geometry, &sections);
+
+        // Keep the glyphs of the boxes to know their positions, but draw
+        // nothing in them.
+        for glyph in &mut glyphs {
+            if self.box_of(glyph.section_index).is_some() {
+                glyph.glyph.id = fonts[glyph.font_id.0].glyph_id(' ');
+            }
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for InlineBoxes<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+
+        for (index, width, height) in &self.boxes {
+            (index, width.to_bits(), height.to_bits()).hash(state);
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use glyph_brush::ab_glyph::{Font, Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that controls the
+/// kerning applied by an inner layout.
+///
+/// Kerning can be disabled entirely, which is useful to keep proportional
+/// fonts aligned in a grid-like fashion, and manual adjustments can be
+/// injected for specific character pairs.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+///
+/// Line breaking is performed by the inner layout, so disabling kerning or
+/// adding positive adjustments may make wrapped lines slightly exceed their
+/// bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kerning<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    enabled: bool,
+    pairs: Vec<KerningPair>,
+}
+
+/// A manual kerning adjustment between two characters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KerningPair {
+    /// The character on the left side of the pair.
+    pub left: char,
+    /// The character on the right side of the pair.
+    pub right: char,
+    /// The horizontal adjustment, relative to the horizontal scale of the
+    /// right character. Negative values bring the pair closer.
+    pub adjustment: f32,
+}
+
+impl<L> Kerning<L> {
+    /// Wraps the given layout, keeping its kerning as is.
+    pub fn new(layout: L) -> Self {
+        Kerning {
+            layout,
+            enabled: true,
+            pairs: Vec::new(),
+        }
+    }
+
+    /// Sets whether the font kerning is applied.
+    ///
+    /// By default, kerning is __enabled__.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Adds a manual kerning adjustment for the given character pair.
+    ///
+    /// The `adjustment` is relative to the horizontal scale of the text, e.g.
+    /// `-0.05` brings the pair closer by 5% of an em. Adjustments are applied
+    /// on top of the font kerning, if enabled.
+    pub fn with_pair(
+        mut self,
+        left: char,
+        right: char,
+        adjustment: f32,
+    ) -> Self {
+        self.pairs.push(KerningPair {
+            left,
+            right,
+            adjustment,
+        });
+        self
+    }
+
+    fn adjustment(&self, left: char, right: char) -> f32 {
+        self.pairs
+            .iter()
+            .filter(|pair| pair.left == left && pair.right == right)
+            .map(|pair| pair.adjustment)
+            .sum()
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Kerning<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs =
+            self.layout.calculate_glyphs(fonts, geometry, sections);
+
+        if self.enabled && self.pairs.is_empty() {
+            return glyphs;
+        }
+
+        let mut previous: Option<(char, &SectionGlyph)> = None;
+        let mut shift = 0.0;
+        let mut shifts = Vec::with_capacity(glyphs.len());
+
+        for glyph in &glyphs {
+            let section = sections[glyph.section_index].to_section_text();
+            let character = section.text[glyph.byte_index..]
+                .chars()
+                .next()
+                .unwrap_or_default();
+
+            // Lines are laid out left to right, so a glyph left of the
+            // previous one starts a new line, even if the glyphs were moved
+            // vertically, e.g. by a `BaselineShift`
+            if previous.is_some_and(|(_, previous)| {
+                glyph.glyph.position.x < previous.glyph.position.x
+            }) {
+                shift = 0.0;
+                previous = None;
+            }
+
+            if let Some((left, previous)) = previous {
+                if !left.is_whitespace() {
+                    // Fonts only kern pairs of their own glyphs
+                    if !self.enabled && previous.font_id == glyph.font_id {
+                        let font =
+                            fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+
+                        shift -= font.kern(previous.glyph.id, glyph.glyph.id);
+                    }
+
+                    shift +=
+                        self.adjustment(left, character) * glyph.glyph.scale.x;
+                }
+            }
+
+            shifts.push(shift);
+            previous = Some((character, glyph));
+        }
+
+        for (glyph, shift) in glyphs.iter_mut().zip(shifts) {
+            glyph.glyph.position.x += shift;
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for Kerning<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.enabled.hash(state);
+
+        for pair in &self.pairs {
+            (pair.left, pair.right, pair.adjustment.to_bits()).hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BaselineShift;
+    use glyph_brush::ab_glyph::{FontRef, PxScale};
+    use glyph_brush::{FontId, SectionText};
+
+    fn font() -> FontRef<'static> {
+        FontRef::try_from_slice(include_bytes!(
+            "../examples/Inconsolata-Regular.ttf"
+        ))
+        .unwrap()
+    }
+
+    fn texts<'a>(texts: &[&'a str]) -> Vec<SectionText<'a>> {
+        texts
+            .iter()
+            .map(|text| SectionText {
+                text,
+                scale: PxScale::from(20.0),
+                font_id: FontId(0),
+            })
+            .collect()
+    }
+
+    /// Returns the horizontal shift of every glyph by the kerning layout,
+    /// and its vertical shift by the inner layout of the kerning.
+    fn shifts<L: GlyphPositioner>(
+        inner: L,
+        kerning: Kerning<L>,
+        texts: &[SectionText<'_>],
+    ) -> Vec<(f32, f32)> {
+        let font = font();
+        let geometry = SectionGeometry::default();
+
+        let expected =
+            Layout::default().calculate_glyphs(&[&font], &geometry, texts);
+        let inner = inner.calculate_glyphs(&[&font], &geometry, texts);
+        let glyphs = kerning.calculate_glyphs(&[&font], &geometry, texts);
+
+        assert_eq!(glyphs.len(), expected.len());
+
+        glyphs
+            .iter()
+            .zip(inner.iter().zip(&expected))
+            .map(|(glyph, (inner, expected))| {
+                (
+                    glyph.glyph.position.x - inner.glyph.position.x,
+                    expected.glyph.position.y - glyph.glyph.position.y,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pairs_shift_the_rest_of_the_line() {
+        let layout = Layout::default();
+        let kerning = Kerning::new(layout).with_pair('A', 'V', -0.1);
+
+        // Lines start unshifted
+        assert_eq!(
+            shifts(layout, kerning, &texts(&["AVA\nVAV"])),
+            [
+                (0.0, 0.0),
+                (-2.0, 0.0),
+                (-2.0, 0.0),
+                (0.0, 0.0),
+                (0.0, 0.0),
+                (-2.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn pairs_span_texts_and_skip_whitespace() {
+        let layout = Layout::default();
+        let kerning = Kerning::new(layout).with_pair('A', 'V', -0.1);
+
+        assert_eq!(
+            shifts(layout, kerning, &texts(&["A", "V A", "V"])),
+            [
+                (0.0, 0.0),
+                (-2.0, 0.0),
+                (-2.0, 0.0),
+                (-2.0, 0.0),
+                (-4.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_of_shifted_baselines() {
+        let layout = BaselineShift::new(Layout::default()).text_shift(1, 30.0);
+        let kerning = Kerning::new(layout.clone()).with_pair('A', 'V', -0.1);
+
+        // Raised glyphs continue the line, and the next line starts
+        // unshifted even if its glyphs are above the previous ones
+        assert_eq!(
+            shifts(layout, kerning, &texts(&["A", "VA\nA", "V"])),
+            [
+                (0.0, 0.0),
+                (-2.0, 30.0),
+                (-2.0, 30.0),
+                (0.0, 30.0),
+                (-2.0, 0.0)
+            ]
+        );
+    }
+}
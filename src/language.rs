@@ -0,0 +1,233 @@
+use glyph_brush::ab_glyph::{Font, Rect};
+use glyph_brush::{
+    BuiltInLineBreaker, FontId, GlyphPositioner, Layout, SectionGeometry,
+    SectionGlyph, SectionText, ToSectionText,
+};
+use std::hash::Hash;
+
+/// A BCP-47 language tag, reduced to its language, script and region
+/// subtags.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Language {
+    language: String,
+    script: Option<String>,
+    region: Option<String>,
+}
+
+impl Language {
+    /// Parses a BCP-47 language tag, like `"sr"`, `"sr-Latn"` or `"tr-TR"`.
+    ///
+    /// Variants and extensions are ignored. Returns `None` if the primary
+    /// language subtag is invalid.
+    pub fn parse(tag: &str) -> Option<Language> {
+        let mut subtags = tag.split(['-', '_']);
+
+        let language = subtags.next()?;
+
+        if !(2..=8).contains(&language.len())
+            || !language.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return None;
+        }
+
+        let mut script = None;
+        let mut region = None;
+
+        for subtag in subtags {
+            let is_alphabetic = subtag.chars().all(|c| c.is_ascii_alphabetic());
+            let is_numeric = subtag.chars().all(|c| c.is_ascii_digit());
+
+            if script.is_none()
+                && region.is_none()
+                && subtag.len() == 4
+                && is_alphabetic
+            {
+                let (first, rest) = subtag.split_at(1);
+
+                script = Some(
+                    first.to_ascii_uppercase() + &rest.to_ascii_lowercase(),
+                );
+            } else if region.is_none()
+                && ((subtag.len() == 2 && is_alphabetic)
+                    || (subtag.len() == 3 && is_numeric))
+            {
+                region = Some(subtag.to_ascii_uppercase());
+            } else {
+                break;
+            }
+        }
+
+        Some(Language {
+            language: language.to_ascii_lowercase(),
+            script,
+            region,
+        })
+    }
+
+    /// Returns the primary language subtag, e.g. `"sr"`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Returns the script subtag, e.g. `"Cyrl"`, if any.
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Returns the region subtag, e.g. `"RS"`, if any.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Returns a copy of the language with the given script subtag.
+    pub fn with_script(mut self, script: &str) -> Language {
+        self.script = Some(script.to_owned());
+        self
+    }
+
+    /// Returns whether this language falls within the given `range`.
+    ///
+    /// A range matches when its subtags are equal to the ones of this
+    /// language, ignoring the subtags the range leaves unspecified. For
+    /// instance, `sr` matches `sr-Cyrl-RS`, but `sr-Latn` does not.
+    pub fn matches(&self, range: &Language) -> bool {
+        fn subtag_matches(
+            subtag: &Option<String>,
+            range: &Option<String>,
+        ) -> bool {
+            range.is_none() || subtag == range
+        }
+
+        self.language == range.language
+            && subtag_matches(&self.script, &range.script)
+            && subtag_matches(&self.region, &range.region)
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.language)?;
+
+        if let Some(script) = &self.script {
+            write!(f, "-{script}")?;
+        }
+
+        if let Some(region) = &self.region {
+            write!(f, "-{region}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that applies language
+/// and script hints to the text of a section.
+///
+/// Glyphs are not shaped with OpenType features, so localized forms are
+/// selected by substituting fonts: a font can be registered as the localized
+/// variant of another one for a language range. Texts whose language falls
+/// within that range are laid out using the localized font instead.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Localized<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    language: Option<Language>,
+    text_languages: Vec<(usize, Language)>,
+    fonts: Vec<(Language, FontId, FontId)>,
+}
+
+impl<L> Localized<L> {
+    /// Wraps the given layout, without any language hints.
+    pub fn new(layout: L) -> Self {
+        Localized {
+            layout,
+            language: None,
+            text_languages: Vec::new(),
+            fonts: Vec::new(),
+        }
+    }
+
+    /// Sets the language of all the texts of the section.
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Sets the language of the text with the given index in the section,
+    /// overriding the language of the section.
+    pub fn text_language(mut self, index: usize, language: Language) -> Self {
+        self.text_languages.retain(|(i, _)| *i != index);
+        self.text_languages.push((index, language));
+        self
+    }
+
+    /// Registers `localized` as the font to use instead of `font` for texts
+    /// whose language matches the given `range`.
+    ///
+    /// When multiple ranges match, the first registered one is used.
+    pub fn localized_font(
+        mut self,
+        range: Language,
+        font: FontId,
+        localized: FontId,
+    ) -> Self {
+        self.fonts.push((range, font, localized));
+        self
+    }
+
+    /// Returns the language of the text with the given index, if any.
+    pub fn language_of(&self, index: usize) -> Option<&Language> {
+        self.text_languages
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, language)| language)
+            .or(self.language.as_ref())
+    }
+
+    fn font_for(&self, index: usize, font_id: FontId) -> FontId {
+        let Some(language) = self.language_of(index) else {
+            return font_id;
+        };
+
+        self.fonts
+            .iter()
+            .find(|(range, font, _)| {
+                *font == font_id && language.matches(range)
+            })
+            .map_or(font_id, |(_, _, localized)| *localized)
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Localized<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let sections: Vec<SectionText<'_>> = sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                let text = section.to_section_text();
+
+                SectionText {
+                    font_id: self.font_for(index, text.font_id),
+                    ..text
+                }
+            })
+            .collect();
+
+        self.layout.calculate_glyphs(fonts, geometry, &sections)
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
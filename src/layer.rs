@@ -0,0 +1,24 @@
+use crate::Instance;
+
+/// Merges the backgrounds, glyphs and custom instances of a draw, sorted by
+/// their z, so the ones with larger z are drawn first and covered by the ones
+/// with smaller z, like with a depth test.
+///
+/// The sort is stable, so instances with the same z keep their order:
+/// backgrounds, then glyphs, then custom instances, each in the order they
+/// were queued in.
+pub(crate) fn merge(
+    backgrounds: &[Instance],
+    glyphs: &[Instance],
+    custom: &[Instance],
+) -> Vec<Instance> {
+    let mut instances: Vec<Instance> = backgrounds
+        .iter()
+        .chain(glyphs)
+        .chain(custom)
+        .copied()
+        .collect();
+
+    instances.sort_by(|a, b| b.center()[2].total_cmp(&a.center()[2]));
+    instances
+}
@@ -0,0 +1,272 @@
+use glyph_brush::ab_glyph::{point, Font, Rect};
+use glyph_brush::{
+    DefaultSectionHasher, GlyphPositioner, SectionGeometry, SectionGlyph,
+    ToSectionText,
+};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::{Mutex, PoisonError};
+
+/// The layouts of a `GlyphBrush` addressed by the content they depend on, so
+/// identical texts queued in many places, like the labels of many buttons,
+/// are laid out once.
+#[derive(Default)]
+pub(crate) struct LayoutCache {
+    hasher: DefaultSectionHasher,
+    entries: HashMap<u64, Entry>,
+}
+
+/// The glyphs of a layout, laid out at a screen position.
+struct Entry {
+    glyphs: Vec<SectionGlyph>,
+    position: (f32, f32),
+    used: bool,
+}
+
+impl LayoutCache {
+    /// Returns the amount of bytes of CPU memory used by the layouts.
+    pub(crate) fn bytes(&self) -> u64 {
+        let glyphs: usize = self
+            .entries
+            .values()
+            .map(|entry| entry.glyphs.capacity())
+            .sum();
+
+        (self.entries.capacity() * std::mem::size_of::<(u64, Entry)>()
+            + glyphs * std::mem::size_of::<SectionGlyph>()) as u64
+    }
+
+    /// Drops the layouts not used since the last call.
+    pub(crate) fn trim(&mut self) {
+        self.entries
+            .retain(|_, entry| std::mem::replace(&mut entry.used, false));
+    }
+}
+
+/// A [`GlyphPositioner`] that reuses the glyphs of the sections with the
+/// same texts, fonts, scales, bounds and layout in a [`LayoutCache`],
+/// translated to the screen position of the section.
+///
+/// Layouts are keyed by the texts, scales and font ids of the sections,
+/// their bounds and the `Hash` of the inner layout, so the inner layout must
+/// not read anything else from the sections, and its `Hash` must cover all
+/// of its own settings. Otherwise, sections that lay out differently would
+/// share the same glyphs.
+///
+/// The inner layout must also position the glyphs relative to the screen
+/// position, like the built-in ones.
+pub(crate) struct Layout<'a, L> {
+    layout: L,
+    cache: Option<&'a Mutex<LayoutCache>>,
+}
+
+impl<'a, L> Layout<'a, L> {
+    pub(crate) fn new(
+        layout: L,
+        cache: Option<&'a Mutex<LayoutCache>>,
+    ) -> Self {
+        Layout { layout, cache }
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Layout<'_, L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let Some(cache) = self.cache else {
+            return self.layout.calculate_glyphs(fonts, geometry, sections);
+        };
+
+        let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let key = {
+            let mut hasher = cache.hasher.build_hasher();
+
+            for section in sections {
+                let text = section.to_section_text();
+
+                text.text.hash(&mut hasher);
+                text.scale.x.to_bits().hash(&mut hasher);
+                text.scale.y.to_bits().hash(&mut hasher);
+                text.font_id.hash(&mut hasher);
+            }
+
+            geometry.bounds.0.to_bits().hash(&mut hasher);
+            geometry.bounds.1.to_bits().hash(&mut hasher);
+            self.layout.hash(&mut hasher);
+
+            hasher.finish()
+        };
+
+        let (x, y) = geometry.screen_position;
+
+        if let Some(entry) = cache.entries.get_mut(&key) {
+            entry.used = true;
+
+            let offset = point(x - entry.position.0, y - entry.position.1);
+
+            return entry
+                .glyphs
+                .iter()
+                .cloned()
+                .map(|mut glyph| {
+                    glyph.glyph.position += offset;
+                    glyph
+                })
+                .collect();
+        }
+
+        let glyphs = self.layout.calculate_glyphs(fonts, geometry, sections);
+
+        let _ = cache.entries.insert(
+            key,
+            Entry {
+                glyphs: glyphs.clone(),
+                position: (x, y),
+                used: true,
+            },
+        );
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for Layout<'_, L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::{FontRef, PxScale};
+    use glyph_brush::{FontId, SectionText};
+
+    fn font() -> FontRef<'static> {
+        FontRef::try_from_slice(include_bytes!(
+            "../examples/Inconsolata-Regular.ttf"
+        ))
+        .unwrap()
+    }
+
+    fn geometry(x: f32, y: f32) -> SectionGeometry {
+        SectionGeometry {
+            screen_position: (x, y),
+            ..SectionGeometry::default()
+        }
+    }
+
+    fn texts(text: &str) -> [SectionText<'_>; 1] {
+        [SectionText {
+            text,
+            scale: PxScale::from(20.0),
+            font_id: FontId(0),
+        }]
+    }
+
+    #[test]
+    fn hits_are_translated_to_the_screen_position() {
+        let font = font();
+        let cache = Mutex::new(LayoutCache::default());
+        let inner = glyph_brush::Layout::default();
+        let layout = Layout::new(inner, Some(&cache));
+
+        let _ = layout.calculate_glyphs(
+            &[&font],
+            &geometry(0.0, 0.0),
+            &texts("Hello"),
+        );
+        let glyphs = layout.calculate_glyphs(
+            &[&font],
+            &geometry(10.0, 5.0),
+            &texts("Hello"),
+        );
+
+        assert_eq!(cache.lock().unwrap().entries.len(), 1);
+
+        let expected = inner.calculate_glyphs(
+            &[&font],
+            &geometry(10.0, 5.0),
+            &texts("Hello"),
+        );
+
+        assert_eq!(glyphs.len(), expected.len());
+
+        for (glyph, expected) in glyphs.iter().zip(&expected) {
+            assert_eq!(glyph.glyph.id, expected.glyph.id);
+            assert_eq!(glyph.byte_index, expected.byte_index);
+            assert_eq!(glyph.glyph.position, expected.glyph.position);
+        }
+    }
+
+    #[test]
+    fn misses_and_trims() {
+        let font = font();
+        let cache = Mutex::new(LayoutCache::default());
+        let layout = Layout::new(glyph_brush::Layout::default(), Some(&cache));
+
+        let _ = layout.calculate_glyphs(
+            &[&font],
+            &geometry(0.0, 0.0),
+            &texts("Hello"),
+        );
+        let _ = layout.calculate_glyphs(
+            &[&font],
+            &geometry(0.0, 0.0),
+            &texts("World"),
+        );
+
+        let bounded = SectionGeometry {
+            bounds: (30.0, f32::INFINITY),
+            ..geometry(0.0, 0.0)
+        };
+        let _ = layout.calculate_glyphs(&[&font], &bounded, &texts("Hello"));
+
+        assert_eq!(cache.lock().unwrap().entries.len(), 3);
+
+        // Trimming drops the layouts not used since the previous trim
+        cache.lock().unwrap().trim();
+        let _ = layout.calculate_glyphs(
+            &[&font],
+            &geometry(0.0, 0.0),
+            &texts("Hello"),
+        );
+        cache.lock().unwrap().trim();
+
+        assert_eq!(cache.lock().unwrap().entries.len(), 1);
+    }
+
+    #[test]
+    fn poisoned_cache_is_recovered() {
+        let font = font();
+        let cache = Mutex::new(LayoutCache::default());
+
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = cache.lock().unwrap();
+            panic!("Poisoning the layout cache");
+        });
+
+        assert!(cache.is_poisoned());
+
+        let layout = Layout::new(glyph_brush::Layout::default(), Some(&cache));
+        let glyphs = layout.calculate_glyphs(
+            &[&font],
+            &geometry(0.0, 0.0),
+            &texts("Hello"),
+        );
+
+        assert_eq!(glyphs.len(), 5);
+    }
+}
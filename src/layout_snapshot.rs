@@ -0,0 +1,155 @@
+use crate::ab_glyph::Font;
+use crate::Section;
+
+use glyph_brush::{GlyphPositioner, SectionGeometry};
+
+/// The glyphs of a list of sections laid out without a device, e.g. to
+/// compare them to a golden copy in tests and catch layout regressions
+/// across updates of the layout.
+///
+/// The layout only depends on the fonts and the sections, so it is the same
+/// on every machine. With the `serde` feature, it can be serialized to store
+/// the golden copy along the tests.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutSnapshot {
+    /// The glyphs of every section, in layout order.
+    pub sections: Vec<Vec<LaidOutGlyph>>,
+}
+
+/// A glyph of a [`LayoutSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LaidOutGlyph {
+    /// The index of the text of the section the glyph comes from.
+    pub section_index: usize,
+    /// The byte index of the character of the glyph in its text.
+    pub byte_index: usize,
+    /// The index of the font of the glyph.
+    pub font_id: usize,
+    /// The id of the glyph in its font.
+    pub glyph_id: u16,
+    /// The position of the origin of the glyph on its baseline, in pixels
+    /// from top-left.
+    pub position: (f32, f32),
+    /// The horizontal and vertical scale of the glyph, in pixels.
+    pub scale: (f32, f32),
+    /// The left, top, right and bottom bounds of the pixels covered by the
+    /// glyph, or `None` if it has no outline, like a space.
+    pub pixel_bounds: Option<[f32; 4]>,
+}
+
+impl LayoutSnapshot {
+    /// Lays out the given sections with the given fonts, which are the fonts
+    /// of the brush they are drawn with.
+    ///
+    /// The sections are laid out with their own layout, like
+    /// [`GlyphBrush::queue`](struct.GlyphBrush.html#method.queue) does, but
+    /// characters missing in their font are not replaced by fallback fonts.
+    pub fn new<F: Font>(fonts: &[F], sections: &[Section<'_>]) -> Self {
+        let sections = sections
+            .iter()
+            .map(|section| {
+                section
+                    .layout
+                    .calculate_glyphs(
+                        fonts,
+                        &SectionGeometry::from(section),
+                        &section.text,
+                    )
+                    .into_iter()
+                    .map(|glyph| LaidOutGlyph {
+                        section_index: glyph.section_index,
+                        byte_index: glyph.byte_index,
+                        font_id: glyph.font_id.0,
+                        glyph_id: glyph.glyph.id.0,
+                        position: (
+                            glyph.glyph.position.x,
+                            glyph.glyph.position.y,
+                        ),
+                        scale: (glyph.glyph.scale.x, glyph.glyph.scale.y),
+                        pixel_bounds: fonts[glyph.font_id.0]
+                            .outline_glyph(glyph.glyph)
+                            .map(|outlined| {
+                                let bounds = outlined.px_bounds();
+
+                                [
+                                    bounds.min.x,
+                                    bounds.min.y,
+                                    bounds.max.x,
+                                    bounds.max.y,
+                                ]
+                            }),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        LayoutSnapshot { sections }
+    }
+
+    /// Returns a description of the first difference between the snapshot
+    /// and a golden one, or `None` if they match.
+    ///
+    /// Positions, scales and pixel bounds match if they differ by at most
+    /// the given tolerance, in pixels, to allow for rounding differences.
+    pub fn diff(
+        &self,
+        golden: &LayoutSnapshot,
+        tolerance: f32,
+    ) -> Option<String> {
+        if self.sections.len() != golden.sections.len() {
+            return Some(format!(
+                "{} sections, expected {}",
+                self.sections.len(),
+                golden.sections.len()
+            ));
+        }
+
+        let near = |a: f32, b: f32| (a - b).abs() <= tolerance;
+
+        for (section, (glyphs, golden)) in
+            self.sections.iter().zip(&golden.sections).enumerate()
+        {
+            if glyphs.len() != golden.len() {
+                return Some(format!(
+                    "section {section}: {} glyphs, expected {}",
+                    glyphs.len(),
+                    golden.len()
+                ));
+            }
+
+            for (index, (glyph, golden)) in
+                glyphs.iter().zip(golden).enumerate()
+            {
+                let bounds_match =
+                    match (glyph.pixel_bounds, golden.pixel_bounds) {
+                        (Some(a), Some(b)) => {
+                            a.iter().zip(&b).all(|(a, b)| near(*a, *b))
+                        }
+                        (None, None) => true,
+                        _ => false,
+                    };
+
+                let matches = glyph.section_index == golden.section_index
+                    && glyph.byte_index == golden.byte_index
+                    && glyph.font_id == golden.font_id
+                    && glyph.glyph_id == golden.glyph_id
+                    && near(glyph.position.0, golden.position.0)
+                    && near(glyph.position.1, golden.position.1)
+                    && near(glyph.scale.0, golden.scale.0)
+                    && near(glyph.scale.1, golden.scale.1)
+                    && bounds_match;
+
+                if !matches {
+                    return Some(format!(
+                        "section {section}, glyph {index}: {glyph:?}, \
+                         expected {golden:?}"
+                    ));
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -3,28 +3,140 @@
 //! [`wgpu`]: https://github.com/gfx-rs/wgpu
 //! [`glyph_brush`]: https://github.com/alexheretic/glyph-brush/tree/master/glyph-brush
 #![deny(unused_results)]
+mod animation;
+mod baseline;
+mod belt;
+mod bitmap_font;
+mod budget;
 mod builder;
+mod cache_full;
+mod case;
+mod clip;
+mod composition;
+mod content;
+mod cursor;
+mod damage;
+mod density;
+mod depth;
+mod direction;
+mod emoji;
+mod fallback;
+mod figures;
+mod grid;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+mod hyphenation;
+mod inline;
+mod kerning;
+mod language;
+mod layer;
+mod layout_cache;
+mod layout_snapshot;
+mod line_background;
+mod locality;
+#[cfg(feature = "normalization")]
+mod normalization;
+mod overflow;
+mod oversized;
+mod palette;
+mod panel;
 mod pipeline;
+mod positioned;
+mod queue;
+mod recolor;
 mod region;
+mod resources;
+mod retained;
+mod runs;
+mod scissor;
+mod selection;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod shared;
+mod small_caps;
+#[cfg(feature = "png")]
+mod snapshot;
+mod stats;
+mod stream;
+mod stretch;
+mod strike;
+mod stroke;
+mod style;
+#[cfg(feature = "svg")]
+mod svg;
+mod transform_slot;
+mod viewport;
 
+pub use animation::Animation;
+pub use baseline::BaselineShift;
+pub use bitmap_font::{BitmapFont, BitmapText};
+pub use budget::{MemoryPressure, MemoryUsage};
+pub use cache_full::OnCacheFull;
+pub use case::{Case, CaseTransform};
+pub use composition::{Composition, Underline};
+pub use cursor::TextCursor;
+pub use depth::DepthOrder;
+pub use direction::{Alignment, Direction, Directional};
+pub use emoji::EmojiSequence;
+pub use fallback::{MissingGlyph, Notdef};
+pub use figures::Figures;
+pub use grid::{Cell, TextGrid};
+pub use hyphenation::Hyphenation;
+pub use inline::InlineBoxes;
+pub use kerning::{Kerning, KerningPair};
+pub use language::{Language, Localized};
+pub use layout_snapshot::{LaidOutGlyph, LayoutSnapshot};
+pub use line_background::LineBackground;
+pub use overflow::{Bounded, Overflow};
+pub use palette::PALETTE_SIZE;
+pub use panel::Panel;
+pub use pipeline::{
+    CompactInstance, Instance, InstanceMode, COMPACT_SHADER, MAX_ATLASES,
+    SHADER, STORAGE_SHADER,
+};
+pub use positioned::PositionedGlyph;
+pub use queue::GlyphQueue;
 pub use region::Region;
+pub use resources::Resources;
+pub use retained::SectionId;
+pub use runs::TextRun;
+pub use shared::SharedCache;
+pub use small_caps::SmallCaps;
+pub use stats::FrameStats;
+pub use stream::TextStream;
+pub use stretch::Stretch;
+pub use strike::StrikePolicy;
+pub use stroke::{Stroke, StrokeOrder};
+pub use style::GlyphStyle;
+pub use transform_slot::TRANSFORM_SLOTS;
+pub use viewport::Viewport;
 
-use pipeline::{Instance, Pipeline};
+use belt::Belts;
+use cache_full::VirtualCache;
+use damage::Damage;
+use fallback::Fallback;
+use pipeline::{DrawTarget, Pipeline, Settings, Transform};
+use retained::Retained;
+use stats::Tracker;
+use style::Styled;
 
 pub use builder::GlyphBrushBuilder;
 pub use glyph_brush::ab_glyph;
 pub use glyph_brush::{
-    BuiltInLineBreaker, Extra, FontId, GlyphCruncher, GlyphPositioner,
-    HorizontalAlign, Layout, LineBreak, LineBreaker, OwnedSection, OwnedText,
-    Section, SectionGeometry, SectionGlyph, SectionGlyphIter, SectionText,
-    Text, VerticalAlign,
+    BuiltInLineBreaker, DefaultSectionHasher, Extra, FontId, GlyphCruncher,
+    GlyphPositioner, HorizontalAlign, Layout, LineBreak, LineBreaker,
+    OwnedSection, OwnedText, Section, SectionGeometry, SectionGlyph,
+    SectionGlyphIter, SectionText, Text, ToSectionText, VerticalAlign,
 };
 
 use ab_glyph::{Font, Rect};
 use core::hash::BuildHasher;
 use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Mutex;
+use std::time::Duration;
 
-use glyph_brush::{BrushAction, BrushError, DefaultSectionHasher};
+use glyph_brush::{BrushAction, BrushError};
 use log::{log_enabled, warn};
 
 /// Object allowing glyph drawing, containing cache state. Manages glyph positioning cacheing,
@@ -33,7 +145,47 @@ use log::{log_enabled, warn};
 /// Build using a [`GlyphBrushBuilder`](struct.GlyphBrushBuilder.html).
 pub struct GlyphBrush<Depth, F = ab_glyph::FontArc, H = DefaultSectionHasher> {
     pipeline: Pipeline<Depth>,
-    glyph_brush: glyph_brush::GlyphBrush<Instance, Extra, F, H>,
+    glyph_brush: glyph_brush::GlyphBrush<Instance, Styled, F, H>,
+    fallback: Fallback,
+    clips: clip::ClipStack,
+    glyphs: Vec<Instance>,
+    instances: Vec<Instance>,
+    backgrounds: Vec<Instance>,
+    drew_instances: bool,
+    stats: FrameStats,
+    tracker: Tracker,
+    retained: Retained,
+    animated: Vec<animation::Animated>,
+    animation_time: Duration,
+    queued: bool,
+    drew_queued: bool,
+    shared: Option<SharedCache>,
+    shared_queue: shared::Queue,
+    belts: Belts,
+    density: (f32, f32),
+    glyph_style: GlyphStyle,
+    styled: style::Scratch,
+    supersampling: f32,
+    depth_order: Option<DepthOrder>,
+    sort_by_atlas: bool,
+    layered: bool,
+    sorted_transform: Option<Transform>,
+    damage: Option<Damage>,
+    content: Option<content::Content>,
+    layouts: Option<Mutex<layout_cache::LayoutCache>>,
+    recorded: Option<Vec<Instance>>,
+    cache_redraws: bool,
+    #[cfg(feature = "hot-reload")]
+    shader_watcher: Option<hot_reload::Watcher>,
+    #[cfg(feature = "normalization")]
+    normalize_text: bool,
+    #[cfg(feature = "svg")]
+    svg: Option<svg::SvgGlyphs>,
+    oversized: oversized::OversizedGlyphs,
+    on_cache_full: OnCacheFull,
+    memory_budget: Option<u64>,
+    on_memory_pressure:
+        Option<Box<dyn FnMut(MemoryPressure) -> bool + Send + Sync>>,
 }
 
 impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
@@ -41,13 +193,31 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
     /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued). Can be
     /// called multiple times to queue multiple sections for drawing.
     ///
+    /// Besides a [`Section`](struct.Section.html), it accepts a reference to
+    /// an [`OwnedSection`](struct.OwnedSection.html), which owns its text and
+    /// can be stored without borrowing it. This is useful for retained-mode
+    /// UIs that keep their sections around between frames:
+    ///
+    /// ```ignore
+    /// // Convert a `Section` once, e.g. when building the UI...
+    /// let label: OwnedSection = Section::default()
+    ///     .add_text(Text::new(&title))
+    ///     .to_owned();
+    ///
+    /// // ...and queue it every frame.
+    /// glyph_brush.queue(&label);
+    /// ```
+    ///
     /// Benefits from caching, see [caching behaviour](#caching-behaviour).
     #[inline]
     pub fn queue<'a, S>(&mut self, section: S)
     where
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush.queue(section)
+        let section = section.into();
+        let layout = section.layout;
+
+        self.queue_custom_layout(section, &layout)
     }
 
     /// Queues a section/layout to be drawn by the next call of
@@ -58,6 +228,9 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
     /// [`Layout`](enum.Layout.html) simply use
     /// [`queue`](struct.GlyphBrush.html#method.queue)
     ///
+    /// Like [`queue`](struct.GlyphBrush.html#method.queue), it accepts a
+    /// reference to an [`OwnedSection`](struct.OwnedSection.html).
+    ///
     /// Benefits from caching, see [caching behaviour](#caching-behaviour).
     #[inline]
     pub fn queue_custom_layout<'a, S, G>(
@@ -68,27 +241,577 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
         G: GlyphPositioner,
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush.queue_custom_layout(section, custom_layout)
+        self.normalized(section.into(), |brush, section| {
+            brush.queue_normalized(section, custom_layout)
+        });
+    }
+
+    /// Queues a section normalized like the text normalization settings
+    /// require.
+    fn queue_normalized<'a, G: GlyphPositioner>(
+        &mut self,
+        mut section: Cow<'a, Section<'a>>,
+        custom_layout: &G,
+    ) {
+        let raster_density = self.raster_density();
+
+        if raster_density != (1.0, 1.0) {
+            density::scale_section(section.to_mut(), raster_density);
+        }
+
+        self.fallback.report(self.glyph_brush.fonts(), &section);
+
+        let layout = clip::Layout::new(
+            layout_cache::Layout::new(
+                self.fallback.layout(custom_layout),
+                self.layouts.as_ref(),
+            ),
+            self.clips.current(raster_density),
+        );
+
+        #[cfg(feature = "svg")]
+        if let Some(svg) = &mut self.svg {
+            let glyphs: Vec<SectionGlyph> =
+                self.styled.styled(&section, self.glyph_style, |styled| {
+                    self.glyph_brush
+                        .glyphs_custom_layout(styled, &layout)
+                        .cloned()
+                        .collect()
+                });
+
+            let bounds = layout.bounds_rect(&SectionGeometry::from(&*section));
+            let extra: Vec<Extra> =
+                section.text.iter().map(|text| text.extra).collect();
+
+            let mut instances = svg.instances(
+                self.glyph_brush.fonts(),
+                &extra,
+                &glyphs,
+                bounds,
+                self.glyph_style,
+            );
+
+            if raster_density != (1.0, 1.0) {
+                density::unscale_instances(&mut instances, raster_density);
+            }
+
+            self.instances.extend(instances);
+        }
+
+        #[cfg(feature = "svg")]
+        let layout = svg::Layout::new(layout, self.svg.is_some());
+
+        let style = self.glyph_style;
+
+        if self.oversized.may_contain(&section) {
+            let glyphs: Vec<SectionGlyph> =
+                self.styled.styled(&section, style, |styled| {
+                    self.glyph_brush
+                        .glyphs_custom_layout(styled, &layout)
+                        .cloned()
+                        .collect()
+                });
+
+            let bounds = layout.bounds_rect(&SectionGeometry::from(&*section));
+
+            let extra: Vec<Extra> =
+                section.text.iter().map(|text| text.extra).collect();
+
+            self.oversized.queue(
+                self.glyph_brush.fonts(),
+                &extra,
+                &glyphs,
+                bounds,
+                raster_density,
+                style,
+            );
+        }
+
+        let layout = oversized::Layout::new(layout, self.oversized.size());
+
+        if let Some(content) = &mut self.content {
+            content.queue((&*section, &layout, style));
+        }
+
+        if self.shared.is_some() {
+            let fonts = self.glyph_brush.fonts();
+
+            self.shared_queue.queue(
+                self.shared_queue.hash((&*section, style), &layout),
+                || {
+                    let geometry = SectionGeometry::from(&*section);
+
+                    shared::Glyphed {
+                        glyphs: layout.calculate_glyphs(
+                            fonts,
+                            &geometry,
+                            &section.text,
+                        ),
+                        extra: section.text.iter().map(|t| t.extra).collect(),
+                        bounds: layout.bounds_rect(&geometry),
+                        style,
+                    }
+                },
+            );
+        } else {
+            self.styled.styled(&section, style, |styled| {
+                self.glyph_brush.queue_custom_layout(styled, &layout)
+            });
+        }
+
+        self.queued = true;
+    }
+
+    /// Queues the paragraphs of a [`TextStream`](struct.TextStream.html) to
+    /// be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// Only the paragraphs changed since the last call are laid out.
+    pub fn queue_stream(&mut self, stream: &mut TextStream) {
+        stream.measure(|section| {
+            let layout = self.fallback.layout(&section.layout);
+
+            let glyphs: Vec<SectionGlyph> =
+                self.styled.styled(section, self.glyph_style, |styled| {
+                    self.glyph_brush
+                        .glyphs_custom_layout(styled, &layout)
+                        .cloned()
+                        .collect()
+                });
+
+            stream::height(self.glyph_brush.fonts(), section, &glyphs)
+        });
+
+        for section in stream.sections() {
+            self.queue(section);
+        }
+    }
+
+    /// Queues a section with a [`Panel`](struct.Panel.html) drawn behind its
+    /// text, to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// The panel surrounds the bounds of the glyphs of the section, and it is
+    /// drawn behind all the glyphs of the brush at the depth of the first
+    /// text of the section.
+    pub fn queue_with_panel<'a, S>(&mut self, section: S, panel: &Panel)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+
+        if let Some(bounds) = self.glyph_bounds(&*section) {
+            let z = section.text.first().map_or(0.0, |text| text.extra.z);
+
+            self.backgrounds.push(panel.instance(bounds, z));
+        }
+
+        self.queue(section);
+    }
+
+    /// Queues a section with [`LineBackground`](struct.LineBackground.html)s
+    /// drawn behind its lines, to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// The backgrounds span the ascent and descent of the fonts of every
+    /// line, across the full width of the bounds of the section, or of its
+    /// text if its bounds are unlimited. Lines without any glyph, like empty
+    /// ones, are not counted. They are drawn behind all the glyphs of the
+    /// brush at the depth of the first text of the section.
+    pub fn queue_with_line_backgrounds<'a, S>(
+        &mut self,
+        section: S,
+        backgrounds: &[LineBackground],
+    ) where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let lines = self.selection_rects(&*section, 0..usize::MAX);
+        let z = section.text.first().map_or(0.0, |text| text.extra.z);
+
+        let bounds = section
+            .layout
+            .bounds_rect(&SectionGeometry::from(&*section));
+
+        let span = if bounds.width().is_finite() {
+            (bounds.min.x, bounds.max.x)
+        } else {
+            lines
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |span, line| {
+                    (span.0.min(line.min.x), span.1.max(line.max.x))
+                })
+        };
+
+        for background in backgrounds {
+            self.backgrounds
+                .extend(background.instances(&lines, span, z));
+        }
+
+        self.queue(section);
+    }
+
+    /// Queues a section with the decorations of the text being composed in
+    /// it by an input method, to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// The highlight of the composed text is drawn behind all the glyphs of
+    /// the brush, and its underline in front of them, at the depth of the
+    /// first text of the section.
+    pub fn queue_with_composition<'a, S>(
+        &mut self,
+        section: S,
+        composition: &Composition,
+    ) where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let lines = self.selection_rects(&*section, composition.range.clone());
+        let z = section.text.first().map_or(0.0, |text| text.extra.z);
+
+        self.backgrounds.extend(composition.highlights(&lines, z));
+        self.instances.extend(composition.underlines(&lines, z));
+
+        self.queue(section);
+    }
+
+    /// Queues a section with a [`TextCursor`](struct.TextCursor.html), i.e.
+    /// its caret and selection, to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// The highlight of the selected text is drawn behind all the glyphs of
+    /// the brush, and the caret in front of them, at the depth of the first
+    /// text of the section.
+    pub fn queue_with_cursor<'a, S>(&mut self, section: S, cursor: &TextCursor)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let lines = self.selection_rects(&*section, cursor.selection.clone());
+        let z = section.text.first().map_or(0.0, |text| text.extra.z);
+
+        self.backgrounds
+            .extend(cursor.selection_instances(&lines, z));
+
+        if let Some(caret) = self.caret_rect(&*section, cursor.caret) {
+            self.instances.push(cursor.caret_instance(caret, z));
+        }
+
+        self.queue(section);
+    }
+
+    /// Queues a section with its glyphs recolored by a callback, to be drawn
+    /// by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), e.g. to
+    /// apply syntax highlighting computed apart from the text without
+    /// splitting it into a text per token.
+    ///
+    /// The callback is given the index of every glyph in layout order and
+    /// the glyph, with the `section_index` and `byte_index` of its
+    /// character, and returns the color to draw it with, or `None` to keep
+    /// the color of its text.
+    ///
+    /// The glyphs are queued as
+    /// [pre-positioned](struct.GlyphBrush.html#method.queue_pre_positioned)
+    /// glyphs, laid out with the layout of the section.
+    pub fn queue_with_glyph_colors<'a, S>(
+        &mut self,
+        section: S,
+        color: impl FnMut(usize, &SectionGlyph) -> Option<[f32; 4]>,
+    ) where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let (mut glyphs, extra, bounds) = self.lay_out(&section.into());
+        let extra = recolor::recolor(&mut glyphs, extra, color);
+
+        self.queue_pre_positioned(glyphs, extra, bounds);
+    }
+
+    /// Queues a section with a [`Stroke`](struct.Stroke.html) outlining its
+    /// glyphs, to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// The outlines are drawn at the depth of the text of their glyph, in
+    /// the [`StrokeOrder`](enum.StrokeOrder.html) of the stroke. The glyphs
+    /// are queued as
+    /// [pre-positioned](struct.GlyphBrush.html#method.queue_pre_positioned)
+    /// glyphs, laid out with the layout of the section.
+    pub fn queue_with_stroke<'a, S>(&mut self, section: S, stroke: &Stroke)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let (glyphs, extra, bounds) = self.lay_out(&section.into());
+        let (glyphs, extra, bounds) = stroke.glyphs(glyphs, extra, bounds);
+
+        self.queue_pre_positioned(glyphs, extra, bounds);
+    }
+
+    /// Queues a section with an [`Animation`](enum.Animation.html), to be
+    /// drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued) at the
+    /// [animation time](#method.set_animation_time) of the draw.
+    ///
+    /// The section is laid out when queued, with the current
+    /// [clip region](#method.push_clip), and the animation only moves or
+    /// hides its glyphs when drawn. Like other sections, it must be queued
+    /// for every draw it is drawn by.
+    pub fn queue_animated<'a, S>(&mut self, section: S, animation: Animation)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let mut section = section.into();
+
+        self.fallback.report(self.glyph_brush.fonts(), &section);
+
+        if let Some(content) = &mut self.content {
+            content.queue((&*section, animation, self.glyph_style));
+        }
+
+        // The glyphs are kept in layout pixels and scaled with their bounds
+        // when drawn, so the bounds are clipped in layout pixels too
+        let bounds = clip::Layout::new(
+            self.fallback.layout(&section.layout),
+            self.clips.current((1.0, 1.0)),
+        )
+        .bounds_rect(&SectionGeometry::from(&*section));
+
+        // Marquees scroll lines as wide as their text within their bounds
+        if let Animation::Marquee { .. } = animation {
+            section.to_mut().bounds.0 = f32::INFINITY;
+        }
+
+        let layout = section.layout;
+        let style = self.glyph_style;
+
+        let animated =
+            self.lay_out_queued(section, &layout, |fonts, section, glyphs| {
+                animation::Animated {
+                    width: animation::width(fonts, &glyphs),
+                    glyphs,
+                    extra: section.text.iter().map(|text| text.extra).collect(),
+                    bounds,
+                    animation,
+                    style,
+                }
+            });
+
+        self.animated.push(animated);
+        self.queued = true;
+    }
+
+    /// Sets the time the animations of the sections queued with
+    /// [`queue_animated`](#method.queue_animated) are evaluated at by the
+    /// next draws, e.g. the time elapsed since the start of the
+    /// application.
+    ///
+    /// Defaults to zero.
+    pub fn set_animation_time(&mut self, time: Duration) {
+        self.animation_time = time;
+    }
+
+    /// Queues a section positioned by the baseline of its first line, to be
+    /// drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), e.g. to
+    /// align it with other baseline-positioned elements.
+    ///
+    /// The section is moved vertically, with its bounds, so the baseline of
+    /// its first line is at the y of its `screen_position` instead of where
+    /// its layout puts it. Returns the vertical offset the section was moved
+    /// by, to apply to the rectangles returned for the section as given.
+    pub fn queue_at_baseline<'a, S>(&mut self, section: S) -> f32
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let mut section = section.into();
+
+        let offset = self
+            .first_baseline(&*section)
+            .map_or(0.0, |baseline| section.screen_position.1 - baseline);
+
+        section.to_mut().screen_position.1 += offset;
+
+        self.queue(section);
+
+        offset
+    }
+
+    /// Queues a [`TextGrid`](struct.TextGrid.html) to be drawn by the next
+    /// call of [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// The characters of the grid are positioned in their cells directly,
+    /// without laying out any text, and the cell backgrounds are drawn
+    /// behind all the glyphs of the brush.
+    pub fn queue_grid(&mut self, grid: &TextGrid) {
+        let (glyphs, extra) = grid.glyphs(self.glyph_brush.fonts());
+
+        self.backgrounds.extend(grid.backgrounds());
+        self.queue_pre_positioned(glyphs, extra, grid.bounds());
+    }
+
+    /// Queues a [`BitmapText`](struct.BitmapText.html) drawn with a
+    /// [`BitmapFont`](struct.BitmapFont.html) to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// The glyphs are copied from the pages of the font, which must be set
+    /// as atlases with
+    /// [`set_atlas`](struct.GlyphBrush.html#method.set_atlas), and drawn
+    /// after the glyphs of the queued sections.
+    pub fn queue_bitmap_text(
+        &mut self,
+        font: &BitmapFont,
+        text: &BitmapText<'_>,
+    ) {
+        self.instances.extend(font.instances(text));
     }
 
     /// Queues pre-positioned glyphs to be processed by the next call of
     /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued). Can be
     /// called multiple times.
-    #[inline]
+    ///
+    /// Applications doing their own shaping and layout, e.g. with HarfBuzz,
+    /// queue the glyphs they positioned here and still draw them with the
+    /// glyph cache and pipeline of the brush. Every glyph has the id, scale
+    /// and pixel position of its `ab_glyph::Glyph` in one of the
+    /// [`fonts`](#method.fonts) of the brush, and its `section_index` is the
+    /// index of its color and depth in `extra`. Glyphs outside of `bounds`,
+    /// or of the current [clip region](#method.push_clip), are clipped.
     pub fn queue_pre_positioned(
         &mut self,
         glyphs: Vec<SectionGlyph>,
         extra: Vec<Extra>,
         bounds: Rect,
     ) {
-        self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds)
+        self.queue_styled(glyphs, extra, bounds, self.glyph_style);
+    }
+
+    /// Queues pre-positioned glyphs drawn with the given style.
+    fn queue_styled(
+        &mut self,
+        mut glyphs: Vec<SectionGlyph>,
+        extra: Vec<Extra>,
+        mut bounds: Rect,
+        style: GlyphStyle,
+    ) {
+        let raster_density = self.raster_density();
+
+        if raster_density != (1.0, 1.0) {
+            density::scale_glyphs(&mut glyphs, &mut bounds, raster_density);
+        }
+
+        if let Some(clip) = self.clips.current(raster_density) {
+            bounds = clip::intersect(bounds, clip);
+        }
+
+        if let Some(content) = &mut self.content {
+            content.queue_pre_positioned(&glyphs, &extra, bounds, style);
+        }
+
+        #[cfg(feature = "svg")]
+        if let Some(svg) = &mut self.svg {
+            let fonts = self.glyph_brush.fonts();
+            let mut instances =
+                svg.instances(fonts, &extra, &glyphs, bounds, style);
+
+            if raster_density != (1.0, 1.0) {
+                density::unscale_instances(&mut instances, raster_density);
+            }
+
+            self.instances.extend(instances);
+            svg::remove_color_glyphs(fonts, &mut glyphs);
+        }
+
+        let fonts = self.glyph_brush.fonts();
+
+        self.oversized.queue(
+            fonts,
+            &extra,
+            &glyphs,
+            bounds,
+            raster_density,
+            style,
+        );
+
+        oversized::remove_oversized(fonts, &mut glyphs, self.oversized.size());
+
+        if self.shared.is_some() {
+            self.shared_queue.queue_pre_positioned(shared::Glyphed {
+                glyphs,
+                extra,
+                bounds,
+                style,
+            });
+        } else {
+            let extra = extra
+                .into_iter()
+                .map(|extra| Styled { extra, style })
+                .collect();
+
+            self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds);
+        }
+
+        self.queued = true;
+    }
+
+    /// Moves the sections queued in a [`GlyphQueue`](struct.GlyphQueue.html)
+    /// to the brush, to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    pub fn queue_from(&mut self, queue: &GlyphQueue) {
+        for queued in queue.take() {
+            match queued {
+                queue::Queued::Section(section) => self.queue(&section),
+                queue::Queued::PrePositioned {
+                    glyphs,
+                    extra,
+                    bounds,
+                } => self.queue_pre_positioned(glyphs, extra, bounds),
+            }
+        }
+    }
+
+    /// Retains a section with the given id, drawing it on every call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued) until it is
+    /// removed. Returns the section previously retained with the same id, if
+    /// any.
+    ///
+    /// Retained sections are drawn in the order of their ids, after the
+    /// queued sections. When only retained sections are drawn and none of
+    /// them has changed, drawing reuses the uploaded instances without
+    /// processing any section.
+    pub fn insert(
+        &mut self,
+        id: SectionId,
+        section: OwnedSection,
+    ) -> Option<OwnedSection> {
+        self.retained.insert(id, section, self.glyph_style)
+    }
+
+    /// Updates the section retained with the given id, returning whether
+    /// it exists.
+    ///
+    /// See [`insert`](struct.GlyphBrush.html#method.insert).
+    pub fn update(
+        &mut self,
+        id: SectionId,
+        f: impl FnOnce(&mut OwnedSection),
+    ) -> bool {
+        self.retained.update(id, f)
+    }
+
+    /// Removes the section retained with the given id, returning it.
+    ///
+    /// See [`insert`](struct.GlyphBrush.html#method.insert).
+    pub fn remove(&mut self, id: SectionId) -> Option<OwnedSection> {
+        self.retained.remove(id)
     }
 
     /// Retains the section in the cache as if it had been used in the last
     /// draw-frame.
     ///
-    /// Should not be necessary unless using multiple draws per frame with
-    /// distinct transforms, see [caching behaviour](#caching-behaviour).
+    /// Sections temporarily hidden, e.g. in collapsed panels or inactive
+    /// tabs, keep their glyphs resident in the glyph cache this way, so they
+    /// reappear without rasterizing them again. Otherwise, it should not be
+    /// necessary unless using multiple draws per frame with distinct
+    /// transforms, see [caching behaviour](#caching-behaviour).
     #[inline]
     pub fn keep_cached_custom_layout<'a, S, G>(
         &mut self,
@@ -98,166 +821,1906 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
         S: Into<Cow<'a, Section<'a>>>,
         G: GlyphPositioner,
     {
-        self.glyph_brush
-            .keep_cached_custom_layout(section, custom_layout)
+        // The section is hashed like when it is queued, so it is normalized
+        // and laid out the same way
+        self.normalized(section.into(), |brush, section| {
+            brush.keep_cached_normalized(section, custom_layout)
+        });
+    }
+
+    /// Retains a section normalized like the text normalization settings
+    /// require.
+    fn keep_cached_normalized<'a, G: GlyphPositioner>(
+        &mut self,
+        mut section: Cow<'a, Section<'a>>,
+        custom_layout: &G,
+    ) {
+        let raster_density = self.raster_density();
+
+        if raster_density != (1.0, 1.0) {
+            density::scale_section(section.to_mut(), raster_density);
+        }
+
+        let layout = clip::Layout::new(
+            layout_cache::Layout::new(
+                self.fallback.layout(custom_layout),
+                self.layouts.as_ref(),
+            ),
+            self.clips.current(raster_density),
+        );
+
+        #[cfg(feature = "svg")]
+        let layout = svg::Layout::new(layout, self.svg.is_some());
+
+        let style = self.glyph_style;
+
+        if self.oversized.may_contain(&section) {
+            let glyphs: Vec<SectionGlyph> =
+                self.styled.styled(&section, style, |styled| {
+                    self.glyph_brush
+                        .glyphs_custom_layout(styled, &layout)
+                        .cloned()
+                        .collect()
+                });
+
+            self.oversized.keep(self.glyph_brush.fonts(), &glyphs);
+        }
+
+        let layout = oversized::Layout::new(layout, self.oversized.size());
+
+        if self.shared.is_some() {
+            self.shared_queue.keep_cached(
+                self.shared_queue.hash((&*section, style), &layout),
+            );
+        } else {
+            self.styled.styled(&section, style, |styled| {
+                self.glyph_brush.keep_cached_custom_layout(styled, &layout)
+            });
+        }
+    }
+
+    /// Retains the section in the cache as if it had been used in the last
+    /// draw-frame, e.g. while it is temporarily hidden.
+    /// See [`keep_cached_custom_layout`](#method.keep_cached_custom_layout).
+    ///
+    /// Like [`queue`](struct.GlyphBrush.html#method.queue), it accepts a
+    /// reference to an [`OwnedSection`](struct.OwnedSection.html).
+    #[inline]
+    pub fn keep_cached<'a, S>(&mut self, section: S)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+
+        self.keep_cached_custom_layout(section, &layout)
+    }
+
+    /// Pushes a clip region, in the coordinates of the sections, that clips
+    /// the glyphs of the sections queued afterwards, e.g. to nest scrollable
+    /// containers.
+    ///
+    /// The region is intersected with the ones pushed before it, until it is
+    /// popped with [`pop_clip`](#method.pop_clip). The glyphs are clipped like
+    /// to the bounds of their sections, without splitting the draw.
+    pub fn push_clip(&mut self, region: Region) {
+        self.clips.push(region);
+    }
+
+    /// Pops the clip region pushed last with
+    /// [`push_clip`](#method.push_clip), returning whether there was one.
+    pub fn pop_clip(&mut self) -> bool {
+        self.clips.pop().is_some()
+    }
+
+    /// Sets the density of the pixels on screen, horizontally and vertically,
+    /// relative to the pixels of the layout.
+    ///
+    /// Glyphs are rasterized at the scale of their layout, so drawing with a
+    /// transform that scales the text, e.g. a stretched minimap, stretches the
+    /// rasterized glyphs too. Setting the density to the scale of the
+    /// transform rasterizes the glyphs queued afterwards at their size on
+    /// screen instead, keeping their layout.
+    ///
+    /// It must not change between queuing sections and drawing them.
+    /// Defaults to `(1.0, 1.0)`.
+    pub fn set_pixel_density(&mut self, density: (f32, f32)) {
+        self.density = density;
+    }
+
+    /// Sets the [`GlyphStyle`](struct.GlyphStyle.html) the glyphs of the
+    /// sections queued afterwards are drawn with, e.g. nearest filtering for
+    /// a pixel-art font, until another style is set.
+    ///
+    /// Sections retained with [`insert`](#method.insert) keep the style set
+    /// when they are inserted. Defaults to `GlyphStyle::default()`.
+    pub fn set_glyph_style(&mut self, style: GlyphStyle) {
+        self.glyph_style = style;
+    }
+
+    /// Lays out a section like it is queued, calling `f` with the fonts, the
+    /// section as laid out and its glyphs at the position they are drawn at,
+    /// in layout pixels.
+    fn lay_out_queued<'a, G, R>(
+        &mut self,
+        section: Cow<'a, Section<'a>>,
+        custom_layout: &G,
+        f: impl FnOnce(&[F], &Section<'_>, Vec<SectionGlyph>) -> R,
+    ) -> R
+    where
+        G: GlyphPositioner,
+    {
+        self.normalized(section, |brush, section| {
+            let raster_density = brush.raster_density();
+            let mut scaled = section.clone();
+
+            if raster_density != (1.0, 1.0) {
+                density::scale_section(scaled.to_mut(), raster_density);
+            }
+
+            let layout = clip::Layout::new(
+                layout_cache::Layout::new(
+                    brush.fallback.layout(custom_layout),
+                    brush.layouts.as_ref(),
+                ),
+                brush.clips.current(raster_density),
+            );
+
+            let mut glyphs: Vec<SectionGlyph> =
+                brush.styled.styled(&scaled, brush.glyph_style, |styled| {
+                    brush
+                        .glyph_brush
+                        .glyphs_custom_layout(styled, &layout)
+                        .cloned()
+                        .collect()
+                });
+
+            if raster_density != (1.0, 1.0) {
+                density::unscale_glyphs(&mut glyphs, raster_density);
+            }
+
+            f(brush.glyph_brush.fonts(), &section, glyphs)
+        })
+    }
+
+    /// Calls `f` with the section normalized to NFC, if text normalization
+    /// is enabled and it is not normalized already, or as is otherwise.
+    fn normalized<'a, R>(
+        &mut self,
+        section: Cow<'a, Section<'a>>,
+        f: impl for<'b> FnOnce(&mut Self, Cow<'b, Section<'b>>) -> R,
+    ) -> R {
+        #[cfg(feature = "normalization")]
+        if self.normalize_text {
+            if let Some(normalized) = normalization::normalize(&section) {
+                return f(self, Cow::Owned(normalized.to_borrowed()));
+            }
+        }
+
+        f(self, section)
+    }
+
+    /// Returns the density glyphs are rasterized at, relative to the pixels of
+    /// the layout.
+    fn raster_density(&self) -> (f32, f32) {
+        (
+            self.density.0 * self.supersampling,
+            self.density.1 * self.supersampling,
+        )
+    }
+
+    /// Lays out a section to be queued as pre-positioned glyphs, returning
+    /// its glyphs, the colors and depths of its texts and its bounds.
+    fn lay_out(
+        &mut self,
+        section: &Section<'_>,
+    ) -> (Vec<SectionGlyph>, Vec<Extra>, Rect) {
+        self.fallback.report(self.glyph_brush.fonts(), section);
+
+        let layout = self.fallback.layout(&section.layout);
+
+        let glyphs: Vec<SectionGlyph> =
+            self.styled.styled(section, self.glyph_style, |styled| {
+                self.glyph_brush
+                    .glyphs_custom_layout(styled, &layout)
+                    .cloned()
+                    .collect()
+            });
+
+        (
+            glyphs,
+            section.text.iter().map(|text| text.extra).collect(),
+            layout.bounds_rect(&SectionGeometry::from(section)),
+        )
+    }
+
+    /// Replaces the WGSL source of the shader of the render pipeline, e.g. to
+    /// iterate on custom text effects without restarting the application.
+    ///
+    /// Keeps the current shader if the new one is invalid, returning the
+    /// validation error. See
+    /// [`GlyphBrushBuilder::shader`](struct.GlyphBrushBuilder.html#method.shader)
+    /// for the requirements of the shader.
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_shader(
+        &mut self,
+        device: &wgpu::Device,
+        source: impl Into<Cow<'static, str>>,
+    ) -> Result<(), String> {
+        self.pipeline.set_shader(device, source.into())
+    }
+
+    /// Returns the rectangles covering the glyphs of the given byte range of
+    /// a section, one per line, e.g. to draw a selection highlight.
+    ///
+    /// Byte offsets refer to the texts of the section joined together, as
+    /// normalized when it is queued with
+    /// [`normalize_text`](struct.GlyphBrushBuilder.html#method.normalize_text).
+    /// The rectangles follow the wrapping and alignment of the layout of the
+    /// section, and span the height of its lines.
+    pub fn selection_rects<'a, S>(
+        &mut self,
+        section: S,
+        range: Range<usize>,
+    ) -> Vec<Rect>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+
+        self.lay_out_queued(section, &layout, |fonts, section, glyphs| {
+            selection::rects(fonts, section, &glyphs, range)
+        })
+    }
+
+    /// Returns the zero-width rectangle of the caret at the given byte offset
+    /// of a section, spanning the height of its line, e.g. to position the
+    /// window of an input method.
+    ///
+    /// Byte offsets refer to the texts of the section joined together, as
+    /// normalized when it is queued with
+    /// [`normalize_text`](struct.GlyphBrushBuilder.html#method.normalize_text).
+    /// The caret is at the left edge of the glyph starting at the offset, or
+    /// at the right edge of the last glyph before it. A section without
+    /// glyphs has its caret at its screen position, or none if it has no
+    /// text.
+    pub fn caret_rect<'a, S>(&mut self, section: S, byte: usize) -> Option<Rect>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+
+        self.lay_out_queued(section, &layout, |fonts, section, glyphs| {
+            selection::caret(fonts, section, &glyphs, byte)
+        })
+    }
+
+    /// Returns the y of the baseline of the first line of a section, as laid
+    /// out by its layout, or `None` if it has no glyphs.
+    pub fn first_baseline<'a, S>(&mut self, section: S) -> Option<f32>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+
+        self.lay_out_queued(section, &layout, |_, _, glyphs| {
+            glyphs.first().map(|glyph| glyph.glyph.position.y)
+        })
+    }
+
+    /// Lays out a section, returning its glyphs with the byte ranges of the
+    /// text they came from, e.g. to map clicks on the glyphs back to the
+    /// text.
+    ///
+    /// The ranges are in the text with the `section_index` of each glyph, as
+    /// normalized when it is queued.
+    /// A glyph covers the bytes up to the next glyph of the same text, so
+    /// characters that are not drawn, like line breaks, belong to the glyph
+    /// before them.
+    pub fn glyph_clusters<'a, S>(
+        &mut self,
+        section: S,
+    ) -> Vec<(SectionGlyph, Range<usize>)>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+
+        self.lay_out_queued(section, &layout, |_, section, glyphs| {
+            let clusters = selection::clusters(section, &glyphs);
+
+            glyphs.into_iter().zip(clusters).collect()
+        })
+    }
+
+    /// Returns the emoji sequences of a section, like emoji with a skin tone
+    /// modifier or families joined with zero width joiners, and whether
+    /// their font composes them into a single glyph.
+    ///
+    /// Sequences the font does not compose fall back to drawing their emoji
+    /// one after the other, e.g. to warn about an emoji font that is too old
+    /// for some sequences.
+    pub fn emoji_sequences<'a, S>(&self, section: S) -> Vec<EmojiSequence>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        emoji::sequences(
+            self.glyph_brush.fonts(),
+            &self.fallback.fonts,
+            &section.into(),
+        )
+    }
+
+    /// Lays out a section, returning its runs of text with their bounds and
+    /// line breaks, e.g. to expose the text to screen readers through
+    /// AccessKit.
+    ///
+    /// Every run covers a single text of the section on a single line, in
+    /// layout order.
+    pub fn text_runs<'a, S>(&mut self, section: S) -> Vec<TextRun>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+
+        self.lay_out_queued(section, &layout, |fonts, section, glyphs| {
+            runs::runs(fonts, section, &glyphs)
+        })
+    }
+
+    /// Lays out a section, returning its glyphs in the final position they
+    /// are drawn at when it is queued, with their pixel bounds, in layout
+    /// order.
+    pub fn positioned_glyphs<'a, S>(
+        &mut self,
+        section: S,
+    ) -> Vec<PositionedGlyph>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+        let layout = section.layout;
+
+        self.lay_out_queued(section, &layout, |fonts, _, glyphs| {
+            positioned::positioned(fonts, &glyphs)
+        })
+    }
+
+    /// Lays out a section with the given [`InlineBoxes`](struct.InlineBoxes.html)
+    /// layout, returning the final bounds of its boxes with the indices of
+    /// the texts they replace, in layout order.
+    pub fn inline_boxes<'a, S, L>(
+        &mut self,
+        section: S,
+        layout: &InlineBoxes<L>,
+    ) -> Vec<(usize, Rect)>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+        L: GlyphPositioner + std::hash::Hash,
+    {
+        self.lay_out_queued(section.into(), layout, |fonts, _, glyphs| {
+            layout.rects(fonts, &glyphs)
+        })
+    }
+
+    /// Returns the available fonts.
+    ///
+    /// The `FontId` corresponds to the index of the font data.
+    #[inline]
+    pub fn fonts(&self) -> &[F] {
+        self.glyph_brush.fonts()
+    }
+
+    /// Adds an additional font to the one(s) initially added on build.
+    ///
+    /// Returns a new [`FontId`](struct.FontId.html) to reference this font.
+    pub fn add_font(&mut self, font: F) -> FontId {
+        self.glyph_brush.add_font(font)
+    }
+
+    /// Sets the fonts used, in order, to draw the characters that are missing
+    /// in the font selected for them.
+    ///
+    /// By default, there are no fallback fonts and the `.notdef` glyph of the
+    /// selected font is drawn instead.
+    pub fn set_fallback_fonts(&mut self, fonts: Vec<FontId>) {
+        self.fallback.fonts = fonts;
+        self.fallback.reset();
+        self.retained.dirty = true;
+    }
+
+    /// Sets what is drawn for characters that have no glyph in their font nor
+    /// in any of the fallback fonts.
+    ///
+    /// By default, the `.notdef` glyph of the font is drawn.
+    pub fn set_notdef(&mut self, notdef: Notdef) {
+        self.fallback.notdef = notdef;
+        self.retained.dirty = true;
+    }
+
+    /// Sets a callback that is notified whenever a queued section contains
+    /// a character with no glyph in the font selected for it.
+    ///
+    /// Every character is reported once per font. The report includes the
+    /// fallback font that handled the character, if any. See
+    /// [`set_fallback_fonts`](struct.GlyphBrush.html#method.set_fallback_fonts).
+    pub fn on_missing_glyph(
+        &mut self,
+        callback: impl FnMut(MissingGlyph) + Send + Sync + 'static,
+    ) {
+        self.fallback.on_missing = Some(Box::new(callback));
+        self.fallback.reset();
+    }
+
+    /// Sets the amount of bytes of GPU memory the brush should use, if any.
+    /// See [`MemoryUsage::gpu`].
+    ///
+    /// Before processing the queued sections, a brush over its budget
+    /// shrinks its glyph cache, evicting the glyphs not drawn in the last
+    /// frame, as long as the cache can still hold the glyphs that were. The
+    /// cache still grows beyond the budget when a frame needs it. A shared
+    /// cache is never shrunk.
+    ///
+    /// No budget is set by default.
+    pub fn set_memory_budget(&mut self, budget: Option<u64>) {
+        self.memory_budget = budget;
+    }
+
+    /// Sets a callback that is notified whenever the brush is over its
+    /// memory budget, before evicting glyphs. See
+    /// [`set_memory_budget`](struct.GlyphBrush.html#method.set_memory_budget).
+    ///
+    /// The callback returns whether the brush should evict glyphs, e.g.
+    /// `false` to keep them while the application frees memory elsewhere.
+    pub fn on_memory_pressure(
+        &mut self,
+        callback: impl FnMut(MemoryPressure) -> bool + Send + Sync + 'static,
+    ) {
+        self.on_memory_pressure = Some(Box::new(callback));
+    }
+
+    /// Returns the memory used by the brush, e.g. to account for text
+    /// rendering in the memory budget of an engine.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let shared = self.shared.as_ref().map(SharedCache::lock);
+
+        let instances: usize = [
+            &self.glyphs,
+            &self.instances,
+            &self.backgrounds,
+            self.recorded.as_ref().unwrap_or(&Vec::new()),
+        ]
+        .iter()
+        .map(|instances| instances.capacity())
+        .sum();
+
+        let layouts = self
+            .layouts
+            .as_ref()
+            .and_then(|layouts| layouts.lock().ok())
+            .map_or(0, |layouts| layouts.bytes());
+
+        let usage = MemoryUsage {
+            atlas: self.pipeline.atlas_bytes()
+                + shared
+                    .as_ref()
+                    .map_or(0, |shared| shared.texture.gpu_bytes()),
+            instances: self.pipeline.all_instance_buffer_bytes(),
+            uniforms: self.pipeline.uniform_bytes(),
+            caches: self.pipeline.cpu_bytes()
+                + shared
+                    .as_ref()
+                    .map_or(0, |shared| shared.texture.cpu_bytes())
+                + (instances * std::mem::size_of::<Instance>()) as u64
+                + layouts,
+        };
+
+        #[cfg(feature = "svg")]
+        let usage = match &self.svg {
+            Some(svg) => MemoryUsage {
+                atlas: usage.atlas + svg.gpu_bytes(),
+                caches: usage.caches + svg.cpu_bytes(),
+                ..usage
+            },
+            None => usage,
+        };
+
+        usage
+    }
+}
+
+impl<D, F, H> GlyphBrush<D, F, H> {
+    /// Returns the statistics of the last frame drawn, useful to display the
+    /// cost of text rendering in a debug overlay.
+    pub fn frame_stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Returns the layout of the bind group `0` of the render pipeline, which
+    /// contains the transform uniform (binding `0`, a `mat4x4<f32>` visible to
+    /// the vertex stage), the cache sampler (binding `1`), the palette
+    /// (binding `2`) and the transform slots (binding `3`).
+    ///
+    /// Useful to build custom render pipelines that consume the glyph cache
+    /// and instances of the brush. See [`Instance`](struct.Instance.html).
+    pub fn uniform_layout(&self) -> &wgpu::BindGroupLayout {
+        self.pipeline.uniform_layout()
+    }
+
+    /// Returns the bind group `0` of the render pipeline.
+    ///
+    /// See [`uniform_layout`](struct.GlyphBrush.html#method.uniform_layout).
+    pub fn uniforms(&self) -> &wgpu::BindGroup {
+        self.pipeline.uniforms()
+    }
+
+    /// Returns the layout of the bind group `1` of the render pipeline, which
+    /// contains the glyph cache texture (binding `0`, a filterable
+    /// `texture_2d<f32>` visible to the fragment stage) and, when using
+    /// [`InstanceMode::Storage`](enum.InstanceMode.html#variant.Storage), the
+    /// instance buffer (binding `1`, a read-only storage buffer visible to the
+    /// vertex stage). Bindings `2` and onwards contain the additional atlases
+    /// set with [`set_atlas`](struct.GlyphBrush.html#method.set_atlas).
+    pub fn cache_layout(&self) -> &wgpu::BindGroupLayout {
+        self.pipeline.cache_layout()
+    }
+
+    /// Returns the bind group `1` of the render pipeline.
+    ///
+    /// It is recreated when the glyph cache grows or, when using
+    /// [`InstanceMode::Storage`](enum.InstanceMode.html#variant.Storage), the
+    /// instance buffer grows. It should be fetched again after every draw.
+    pub fn cache_bind_group(&self) -> &wgpu::BindGroup {
+        self.pipeline.cache_bind_group()
+    }
+
+    /// Sets the texture view of the atlas with the given `index`, or resets
+    /// it to the glyph cache if `None`.
+    ///
+    /// The atlas `0` is always the glyph cache. Up to
+    /// [`MAX_ATLASES`](constant.MAX_ATLASES.html) atlases are bound at the
+    /// same time as a small fixed array of textures, instead of a binding
+    /// array, so every backend supports them. They must be filterable
+    /// `texture_2d<f32>` views, and are sampled as RGBA in the fragment
+    /// shader. Instances select their atlas with an index.
+    ///
+    /// # Panics
+    /// Panics if `index` is `0` or not lower than `MAX_ATLASES`.
+    pub fn set_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        index: usize,
+        atlas: Option<wgpu::TextureView>,
+    ) {
+        self.pipeline.set_atlas(device, index, atlas);
+    }
+
+    /// Queues custom instances to be drawn after the glyphs of the queued
+    /// sections by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued).
+    ///
+    /// Use [`Instance::new`] with the index of an atlas set with
+    /// [`set_atlas`](struct.GlyphBrush.html#method.set_atlas) to draw color
+    /// emoji or other images in the same pass as the text.
+    pub fn queue_instances(
+        &mut self,
+        instances: impl IntoIterator<Item = Instance>,
+    ) {
+        self.instances.extend(instances);
+    }
+
+    /// Returns the areas whose text changed in the last draw, compared to the
+    /// draw before it, e.g. to limit the area of a partial present.
+    ///
+    /// The areas are in the coordinates of the queued sections, before the
+    /// transform of the draw is applied. Glyphs, backgrounds and custom
+    /// instances that appeared, disappeared, moved or changed color are
+    /// reported, but changes of the palette colors, the atlases or the
+    /// transform are not.
+    ///
+    /// Damage is only tracked when enabled with
+    /// [`GlyphBrushBuilder::track_damage`](struct.GlyphBrushBuilder.html#method.track_damage),
+    /// otherwise it is always empty.
+    pub fn damage(&self) -> &[Rect] {
+        self.damage.as_ref().map_or(&[], Damage::rects)
+    }
+
+    /// Returns a hash of the content queued for the next draw: the queued
+    /// sections with their layout, the pre-positioned glyphs, the custom
+    /// instances and decorations, the retained sections, and the animated
+    /// sections at the current animation time.
+    ///
+    /// Hashes are only comparable between the frames of the same brush.
+    /// Returns `None` unless the content is tracked with
+    /// [`track_content`](struct.GlyphBrushBuilder.html#method.track_content).
+    pub fn content_hash(&self) -> Option<u64> {
+        let content = self.content.as_ref()?;
+
+        let retained: Vec<_> = self
+            .retained
+            .sections
+            .iter()
+            .map(|(id, (section, style))| (id, section.to_borrowed(), style))
+            .collect();
+
+        let animated: Vec<Vec<u32>> = self
+            .animated
+            .iter()
+            .map(|animated| {
+                let offsets = animated.offsets(self.animation_time);
+
+                offsets.into_iter().map(f32::to_bits).collect()
+            })
+            .collect();
+
+        Some(content.hash(
+            self.backgrounds.iter().chain(&self.instances),
+            (retained, animated),
+        ))
+    }
+
+    /// Returns whether the content queued for the next draw differs from the
+    /// one of the last draw, or nothing was drawn yet. It is always dirty
+    /// unless the content is tracked with
+    /// [`track_content`](struct.GlyphBrushBuilder.html#method.track_content).
+    ///
+    /// Applications that redraw on demand can skip drawing and presenting a
+    /// frame when text is their only dynamic content and it is not dirty.
+    /// The content is compared with its
+    /// [`content_hash`](#method.content_hash), so changes of the transform,
+    /// the palette colors, the transform slots or the atlases are not taken
+    /// into account.
+    pub fn is_dirty(&self) -> bool {
+        match (&self.content, self.content_hash()) {
+            (Some(content), Some(hash)) => content.differs(hash),
+            _ => true,
+        }
+    }
+
+    /// Returns the instances of the last draw, in the order they are drawn:
+    /// backgrounds, glyphs and custom instances, unless they are
+    /// [layered](struct.GlyphBrushBuilder.html#method.layered).
+    ///
+    /// Their positions are in the coordinates of the queued sections, before
+    /// the transform of the draw is applied. The instances of oversized
+    /// glyphs, drawn from their own textures, are not included.
+    ///
+    /// Instances are only recorded when enabled with
+    /// [`GlyphBrushBuilder::record_instances`](struct.GlyphBrushBuilder.html#method.record_instances),
+    /// otherwise they are always empty.
+    pub fn recorded_instances(&self) -> &[Instance] {
+        self.recorded.as_deref().unwrap_or(&[])
+    }
+
+    /// Sets the color of the palette entry with the given index.
+    ///
+    /// Glyphs queued with the entry as their
+    /// [`GlyphStyle::palette`](struct.GlyphStyle.html#structfield.palette)
+    /// are drawn with their color multiplied by the current color of the
+    /// entry, so switching themes only uploads the palette on the next draw.
+    /// Palette colors are white by default.
+    pub fn set_palette_color(&mut self, index: u8, color: [f32; 4]) {
+        self.pipeline.set_palette_color(index, color);
+    }
+
+    /// Sets the transform of the slot with the given index, from `1` to
+    /// [`TRANSFORM_SLOTS`](constant.TRANSFORM_SLOTS.html) excluded.
+    ///
+    /// Glyphs queued with the slot as their
+    /// [`GlyphStyle::transform_slot`](struct.GlyphStyle.html#structfield.transform_slot)
+    /// are drawn with the current transform of their slot instead of the one
+    /// of the draw call, so the slots are only uploaded on the next draw when
+    /// changed. Slots are the identity by default.
+    ///
+    /// Their glyphs are still clipped and sorted by depth like the others,
+    /// in the coordinates of the sections.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is `0` or not lower than `TRANSFORM_SLOTS`.
+    pub fn set_transform_slot(&mut self, slot: u8, transform: [f32; 16]) {
+        self.pipeline.set_transform_slot(slot, transform);
+    }
+
+    /// Clears the render target with the given color in the render pass of
+    /// the next draw, instead of drawing over its contents.
+    ///
+    /// It saves a dedicated clear pass in applications drawing only text,
+    /// like terminal emulators. The whole target is cleared, even when the
+    /// draw is limited to a region.
+    pub fn clear_next_draw(&mut self, color: wgpu::Color) {
+        self.pipeline.clear_next_draw(color);
+    }
+
+    /// Resolves the multi-sampled render target of the next draw into the
+    /// given view, e.g. the texture of the swapchain, at the end of its
+    /// render pass.
+    ///
+    /// It saves resolving the target separately when the text is the last
+    /// thing drawn onto it. The view must have the format of the target and
+    /// a single sample.
+    pub fn resolve_next_draw(&mut self, view: &wgpu::TextureView) {
+        self.pipeline.resolve_next_draw(view);
+    }
+
+    /// Stores the render target of the next draw with the given operation at
+    /// the end of its render pass.
+    ///
+    /// Discarding a multi-sampled target once it is
+    /// [resolved](#method.resolve_next_draw) saves writing it back to memory
+    /// on tiled GPUs, like most mobile ones, when the text is the last thing
+    /// drawn onto it. Defaults to [`wgpu::StoreOp::Store`].
+    pub fn store_next_draw(&mut self, store: wgpu::StoreOp) {
+        self.pipeline.store_next_draw(store);
+    }
+
+    /// Sets the value of a pipeline-overridable constant of the shader.
+    ///
+    /// Changing a constant recreates the render pipelines on their next draw,
+    /// so it is meant for tuning effects, not for animating them. See
+    /// [`GlyphBrushBuilder::pipeline_constant`](struct.GlyphBrushBuilder.html#method.pipeline_constant).
+    pub fn set_pipeline_constant(&mut self, name: &str, value: f64) {
+        self.pipeline.set_constant(name, value);
+    }
+
+    /// Sets the bind group to bind at the given `index` during draws, after
+    /// the bind groups of the brush. In other words, the index `0`
+    /// corresponds to the bind group `2` of the render pipeline.
+    ///
+    /// The bind group must match the layout provided in
+    /// [`GlyphBrushBuilder::bind_group_layouts`](struct.GlyphBrushBuilder.html#method.bind_group_layouts)
+    /// at the same index.
+    pub fn set_bind_group(
+        &mut self,
+        index: usize,
+        bind_group: wgpu::BindGroup,
+    ) {
+        self.pipeline.set_bind_group(index, bind_group);
+    }
+
+    /// Returns the instance buffer uploaded by the last draw.
+    ///
+    /// When the instances do not fit in a single buffer within the limits of
+    /// the device, they are split into multiple buffers drawn separately, and
+    /// only the first one is returned.
+    ///
+    /// Its layout is described by [`Instance`](struct.Instance.html), or by
+    /// [`CompactInstance`](struct.CompactInstance.html) when using
+    /// [`InstanceMode::Compact`](enum.InstanceMode.html#variant.Compact).
+    pub fn instances(&self) -> &wgpu::Buffer {
+        self.pipeline.instances()
+    }
+
+    /// Returns the amount of instances uploaded by the last draw to the
+    /// [`instances`](struct.GlyphBrush.html#method.instances) buffer.
+    pub fn instance_count(&self) -> u32 {
+        self.pipeline.instance_count()
+    }
+
+    /// Returns the GPU resources read and written by draws, e.g. to declare
+    /// the dependencies of the render pass of the brush in a render graph.
+    ///
+    /// Queried ahead of a draw, they are the resources it uses, unless it
+    /// grows the glyph cache or the instance buffers, which replaces them.
+    /// The resources must be queried again after such a draw, which
+    /// [`frame_stats`](struct.GlyphBrush.html#method.frame_stats) reports
+    /// with its `cache_resizes` and `instance_capacity`.
+    pub fn resources(&self) -> Resources<'_> {
+        self.pipeline.resources()
+    }
+}
+
+impl<D, F, H> GlyphBrush<D, F, H>
+where
+    F: Font + Sync,
+    H: BuildHasher,
+{
+    /// Draws the glyph cache into the given region of the target, outlining
+    /// the regions occupied by glyphs, to diagnose cache thrashing and
+    /// fragmentation.
+    ///
+    /// The cache is drawn as white coverage over a dark background, stretched
+    /// to fill the region. It is meant to be called after drawing the queued
+    /// sections, so it shows the cache state of the frame. Its render
+    /// pipelines are created on first use for every format and sample count
+    /// of target.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    pub fn draw_debug_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        region: Region,
+    ) {
+        let (width, height) = self.cache_dimensions();
+
+        let rects: Vec<[f32; 4]> = self
+            .tracker
+            .glyphs()
+            .map(|[left, top, right, bottom]| {
+                [
+                    *left as f32 / width as f32,
+                    *top as f32 / height as f32,
+                    *right as f32 / width as f32,
+                    *bottom as f32 / height as f32,
+                ]
+            })
+            .collect();
+
+        self.pipeline.draw_debug_atlas(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            &rects,
+            region,
+        );
+    }
+
+    fn process_queued(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        transform: Transform,
+    ) -> Result<(), String> {
+        #[cfg(feature = "hot-reload")]
+        if let Some(source) = self
+            .shader_watcher
+            .as_mut()
+            .and_then(hot_reload::Watcher::poll)
+        {
+            if let Err(error) = self.reload_shader(device, source) {
+                warn!("Failed to reload shader: {error}");
+            }
+        }
+
+        if let Some(hash) = self.content_hash() {
+            if let Some(content) = &mut self.content {
+                content.draw(hash);
+            }
+        }
+
+        // A shared cache may be reordered by other brushes, invalidating the
+        // uploaded instances
+        let idle = !self.queued
+            && !self.drew_queued
+            && !self.retained.dirty
+            && self.instances.is_empty()
+            && self.backgrounds.is_empty()
+            && !self.drew_instances
+            && self.shared.is_none()
+            && (self.depth_order.is_none()
+                || self.sorted_transform == Some(transform));
+
+        if idle {
+            if let Some(damage) = &mut self.damage {
+                damage.clear();
+            }
+
+            self.stats = FrameStats {
+                cache_hits: self.glyphs.len(),
+                cache_misses: 0,
+                ..self.stats
+            };
+
+            return Ok(());
+        }
+
+        let queued = self.queued;
+        let retained = std::mem::take(&mut self.retained.sections);
+
+        let glyph_style = self.glyph_style;
+
+        for (section, style) in retained.values() {
+            self.glyph_style = *style;
+            self.queue(section);
+        }
+
+        self.retained.sections = retained;
+        self.queued = queued;
+        self.glyph_style = glyph_style;
+
+        for animated in std::mem::take(&mut self.animated) {
+            let offsets = animated.offsets(self.animation_time);
+
+            self.queue_styled(
+                animated.glyphs(&offsets),
+                animated.extra,
+                animated.bounds,
+                animated.style,
+            );
+        }
+
+        if self.shared.is_none() {
+            self.relieve_memory_pressure(device);
+        }
+
+        #[cfg(feature = "svg")]
+        if let Some(svg) = &mut self.svg {
+            svg.flush(device, staging_belt, encoder, &mut self.pipeline);
+        }
+
+        let processed = if self.shared.is_some() {
+            self.process_shared(device, staging_belt, encoder).map(Some)
+        } else {
+            self.process_own(device, staging_belt, encoder)
+        };
+
+        // The glyphs of a draw failing for a full cache are not drawn
+        let (verts, result) = match processed {
+            Ok(verts) => (verts, Ok(())),
+            Err(error) => (Some(Vec::new()), Err(error)),
+        };
+
+        self.oversized
+            .flush(device, staging_belt, encoder, &mut self.pipeline);
+
+        let texture_dimensions = self.cache_dimensions();
+
+        let (glyphs_changed, (cache_hits, cache_misses)) = match verts {
+            Some(mut verts) => {
+                let raster_density = self.raster_density();
+
+                if raster_density != (1.0, 1.0) {
+                    density::unscale_instances(&mut verts, raster_density);
+                }
+
+                let hits_and_misses =
+                    self.tracker.draw(&verts, texture_dimensions);
+
+                if self.sort_by_atlas {
+                    locality::sort(&mut verts);
+                }
+
+                self.glyphs = verts;
+                (true, hits_and_misses)
+            }
+            None => (false, (self.glyphs.len(), 0)),
+        };
+
+        let sorted = match self.depth_order {
+            Some(order)
+                if glyphs_changed
+                    || self.sorted_transform != Some(transform) =>
+            {
+                depth::sort(&mut self.glyphs, order, &transform);
+                depth::sort(&mut self.instances, order, &transform);
+
+                self.sorted_transform = Some(transform);
+                true
+            }
+            Some(order) => {
+                depth::sort(&mut self.instances, order, &transform);
+                false
+            }
+            None => false,
+        };
+
+        if glyphs_changed
+            || sorted
+            || self.drew_instances
+            || !self.instances.is_empty()
+            || !self.backgrounds.is_empty()
+        {
+            if self.layered {
+                let layered = layer::merge(
+                    &self.backgrounds,
+                    &self.glyphs,
+                    &self.instances,
+                );
+
+                self.pipeline.upload(
+                    device,
+                    staging_belt,
+                    encoder,
+                    &[],
+                    &layered,
+                    &[],
+                );
+
+                if let Some(recorded) = &mut self.recorded {
+                    *recorded = layered;
+                }
+            } else {
+                self.pipeline.upload(
+                    device,
+                    staging_belt,
+                    encoder,
+                    &self.backgrounds,
+                    &self.glyphs,
+                    &self.instances,
+                );
+
+                if let Some(recorded) = &mut self.recorded {
+                    recorded.clear();
+                    recorded.extend(
+                        self.backgrounds
+                            .iter()
+                            .chain(&self.glyphs)
+                            .chain(&self.instances),
+                    );
+                }
+            }
+
+            if let Some(damage) = &mut self.damage {
+                damage.draw(
+                    self.backgrounds
+                        .iter()
+                        .chain(&self.glyphs)
+                        .chain(&self.instances),
+                );
+            }
+        } else if let Some(damage) = &mut self.damage {
+            damage.clear();
+        }
+
+        self.stats = FrameStats {
+            glyphs: self.glyphs.len(),
+            custom_instances: self.instances.len(),
+            instance_bytes: self.pipeline.instance_bytes(),
+            instance_capacity: self.pipeline.instance_capacity(),
+            cache_hits,
+            cache_misses,
+            atlas_occupancy: self.tracker.occupancy(texture_dimensions) * 100.0,
+            ..self.stats
+        };
+
+        // Without redraws, the glyphs are laid out and uploaded again by the
+        // next draw, so they are only kept to size the cache within a budget
+        if !self.cache_redraws && self.memory_budget.is_none() {
+            self.glyphs = Vec::new();
+        }
+
+        self.drew_instances =
+            !self.instances.is_empty() || !self.backgrounds.is_empty();
+        self.instances.clear();
+        self.backgrounds.clear();
+
+        self.drew_queued = self.queued;
+        self.queued = false;
+        self.retained.dirty = false;
+        if let Some(content) = &mut self.content {
+            content.clear();
+        }
+
+        if let Some(Ok(layouts)) = self.layouts.as_mut().map(Mutex::get_mut) {
+            layouts.trim();
+        }
+
+        result
+    }
+
+    /// Shrinks the glyph cache if the brush is over its memory budget and
+    /// the callback allows it.
+    fn relieve_memory_pressure(&mut self, device: &wgpu::Device) {
+        let Some(budget) = self.memory_budget else {
+            return;
+        };
+
+        let usage = self.memory_usage().gpu();
+
+        if usage <= budget {
+            return;
+        }
+
+        let evict = self
+            .on_memory_pressure
+            .as_mut()
+            .is_none_or(|callback| callback(MemoryPressure { usage, budget }));
+
+        if !evict {
+            return;
+        }
+
+        let (width, height) = self.glyph_brush.texture_dimensions();
+        let cache_bytes = u64::from(width) * u64::from(height);
+
+        if let Some((width, height)) = budget::shrunk_cache_size(
+            &self.glyphs,
+            (width, height),
+            usage.saturating_sub(cache_bytes),
+            budget,
+        ) {
+            self.pipeline.resize_cache(device, width, height);
+            self.glyph_brush.resize_texture(width, height);
+            self.tracker.reset();
+        }
+    }
+
+    /// Processes the queued sections using the own cache of the brush,
+    /// returning the new instances or `None` if they did not change.
+    fn process_own(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Result<Option<Vec<Instance>>, String> {
+        let pipeline = &mut self.pipeline;
+
+        let mut brush_action;
+        let tracker = &mut self.tracker;
+        let mut overflow: Option<VirtualCache> = None;
+        let max_dimension = device.limits().max_texture_dimension_2d;
+
+        loop {
+            brush_action = self.glyph_brush.process_queued(
+                |rect, tex_data| {
+                    let mut update =
+                        |rect: glyph_brush::Rectangle<u32>, tex_data: &[u8]| {
+                            let offset =
+                                [rect.min[0] as u16, rect.min[1] as u16];
+                            let size =
+                                [rect.width() as u16, rect.height() as u16];
+
+                            pipeline.update_cache(offset, size, tex_data);
+                            tracker.upload([
+                                rect.min[0],
+                                rect.min[1],
+                                rect.max[0],
+                                rect.max[1],
+                            ]);
+                        };
+
+                    match &mut overflow {
+                        Some(overflow) => {
+                            overflow.upload(rect, tex_data, update);
+                        }
+                        None => update(rect, tex_data),
+                    }
+                },
+                Instance::from_vertex,
+            );
+
+            pipeline.flush_cache(device, staging_belt, encoder);
+
+            match brush_action {
+                Ok(_) => break,
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    let dimensions = self.glyph_brush.texture_dimensions();
+
+                    if let Some(overflow) = &mut overflow {
+                        let Some((width, height)) = overflow.grow() else {
+                            let (width, height) = overflow.dimensions();
+
+                            self.glyph_brush.resize_texture(width, height);
+                            tracker.reset();
+
+                            return Err(cache_full_error((width, height)));
+                        };
+
+                        self.glyph_brush.resize_texture(width, height);
+                        continue;
+                    }
+
+                    let Some((new_width, new_height)) =
+                        grown_cache_size(dimensions, suggested, max_dimension)
+                    else {
+                        // The glyphs are packed into a larger cache on the
+                        // CPU to drain the queue, uploading the ones that
+                        // fit in the glyph cache
+                        let mut full =
+                            VirtualCache::new(self.on_cache_full, dimensions);
+
+                        if let Some((width, height)) = full.grow() {
+                            self.glyph_brush.resize_texture(width, height);
+                        }
+
+                        overflow = Some(full);
+                        continue;
+                    };
+
+                    pipeline.resize_cache(device, new_width, new_height);
+                    self.glyph_brush.resize_texture(new_width, new_height);
+                    tracker.reset();
+                    self.stats.cache_resizes += 1;
+                }
+            }
+        }
+
+        let verts = match brush_action.unwrap() {
+            BrushAction::Draw(verts) => verts,
+            BrushAction::ReDraw => return Ok(None),
+        };
+
+        let Some(overflow) = overflow else {
+            return Ok(Some(verts));
+        };
+
+        // Every glyph is packed again next draw, as the packing of the larger
+        // cache does not match the glyph cache
+        let (width, height) = overflow.dimensions();
+        self.glyph_brush.resize_texture(width, height);
+        tracker.reset();
+
+        self.overflow(overflow, verts).map(Some)
+    }
+
+    /// Returns the instances of the glyphs of a draw that did not fit in the
+    /// glyph cache, following the [`OnCacheFull`] policy of the brush.
+    fn overflow(
+        &mut self,
+        overflow: VirtualCache,
+        instances: Vec<Instance>,
+    ) -> Result<Vec<Instance>, String> {
+        let dimensions = overflow.dimensions();
+        let (instances, temporaries) = overflow.finish(instances);
+
+        match self.on_cache_full {
+            OnCacheFull::Error => Err(cache_full_error(dimensions)),
+            OnCacheFull::ClearAndRetry => {
+                self.oversized.queue_temporaries(temporaries);
+
+                Ok(instances)
+            }
+            OnCacheFull::SkipNewGlyphs => Ok(instances),
+        }
+    }
+
+    /// Processes the queued sections using the shared cache of the brush,
+    /// returning the new instances.
+    fn process_shared(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Result<Vec<Instance>, String> {
+        let shared = self.shared.as_ref().expect("Shared cache");
+        let mut state = shared.lock();
+        let shared::State {
+            draw_cache,
+            texture,
+            generation,
+        } = &mut *state;
+
+        let fonts = self.glyph_brush.fonts();
+        let tracker = &mut self.tracker;
+        let mut overflow: Option<VirtualCache> = None;
+        let max_dimension = device.limits().max_texture_dimension_2d;
+
+        loop {
+            for glyph in self.shared_queue.glyphs() {
+                draw_cache.queue_glyph(glyph.font_id.0, glyph.glyph.clone());
+            }
+
+            let result = draw_cache.cache_queued(fonts, |rect, tex_data| {
+                let mut update =
+                    |rect: glyph_brush::Rectangle<u32>, tex_data: &[u8]| {
+                        let offset = [rect.min[0] as u16, rect.min[1] as u16];
+                        let size = [rect.width() as u16, rect.height() as u16];
+
+                        texture.update(offset, size, tex_data);
+                        tracker.upload([
+                            rect.min[0],
+                            rect.min[1],
+                            rect.max[0],
+                            rect.max[1],
+                        ]);
+                    };
+
+                match &mut overflow {
+                    Some(overflow) => overflow.upload(rect, tex_data, update),
+                    None => update(rect, tex_data),
+                }
+            });
+
+            texture.flush(device, staging_belt, encoder);
+
+            if result.is_ok() {
+                break;
+            }
+
+            let (width, height) = draw_cache.dimensions();
+
+            let grown = match &mut overflow {
+                Some(overflow) => overflow.grow(),
+                None => match grown_cache_size(
+                    (width, height),
+                    (width * 2, height * 2),
+                    max_dimension,
+                ) {
+                    Some((new_width, new_height)) => {
+                        texture.resize(device, new_width, new_height);
+                        tracker.reset();
+                        self.stats.cache_resizes += 1;
+
+                        Some((new_width, new_height))
+                    }
+                    None => {
+                        let mut full = VirtualCache::new(
+                            self.on_cache_full,
+                            (width, height),
+                        );
+                        let grown = full.grow();
+
+                        overflow = Some(full);
+                        grown
+                    }
+                },
+            };
+
+            let Some((new_width, new_height)) = grown else {
+                let (width, height) = overflow
+                    .as_ref()
+                    .map(VirtualCache::dimensions)
+                    .unwrap_or((width, height));
+
+                draw_cache
+                    .to_builder()
+                    .dimensions(width, height)
+                    .rebuild(draw_cache);
+
+                *generation += 1;
+                tracker.reset();
+                self.shared_queue.finish();
+
+                return Err(cache_full_error((width, height)));
+            };
+
+            draw_cache
+                .to_builder()
+                .dimensions(new_width, new_height)
+                .rebuild(draw_cache);
+
+            *generation += 1;
+        }
+
+        let instances = self.shared_queue.instances(draw_cache);
+
+        // Every glyph is packed again next draw, as the packing of the larger
+        // cache does not match the glyph cache
+        if let Some((width, height)) =
+            overflow.as_ref().map(VirtualCache::dimensions)
+        {
+            draw_cache
+                .to_builder()
+                .dimensions(width, height)
+                .rebuild(draw_cache);
+
+            *generation += 1;
+            tracker.reset();
+        }
+
+        self.pipeline
+            .bind_shared_cache(device, texture, *generation);
+        self.shared_queue.finish();
+
+        drop(state);
+
+        match overflow {
+            Some(overflow) => self.overflow(overflow, instances),
+            None => Ok(instances),
+        }
+    }
+}
+
+impl<D, F: Font, H: BuildHasher> GlyphBrush<D, F, H> {
+    /// Creates a `GlyphBrush` drawing with the given pipeline and laying out
+    /// with the given `glyph_brush`, configured by the given settings.
+    fn from_parts(
+        pipeline: Pipeline<D>,
+        glyph_brush: glyph_brush::GlyphBrush<Instance, Styled, F, H>,
+        settings: &Settings,
+        cache_redraws: bool,
+    ) -> Self {
+        GlyphBrush {
+            pipeline,
+            glyph_brush,
+            fallback: Fallback::default(),
+            clips: clip::ClipStack::default(),
+            glyphs: Vec::new(),
+            instances: Vec::new(),
+            backgrounds: Vec::new(),
+            drew_instances: false,
+            stats: FrameStats::default(),
+            tracker: Tracker::default(),
+            retained: Retained::default(),
+            animated: Vec::new(),
+            animation_time: Duration::ZERO,
+            queued: false,
+            drew_queued: false,
+            shared: settings.shared_cache.clone(),
+            shared_queue: shared::Queue::default(),
+            belts: Belts::new(settings.staging_belt_chunk_size),
+            density: (1.0, 1.0),
+            glyph_style: GlyphStyle::default(),
+            styled: style::Scratch::default(),
+            supersampling: settings.supersampling as f32,
+            depth_order: settings.depth_order,
+            sort_by_atlas: settings.sort_by_atlas,
+            layered: settings.layered,
+            sorted_transform: None,
+            damage: settings.track_damage.then(Damage::default),
+            recorded: settings.record_instances.then(Vec::new),
+            cache_redraws,
+            content: settings.track_content.then(content::Content::default),
+            layouts: settings
+                .layout_cache
+                .then(|| Mutex::new(layout_cache::LayoutCache::default())),
+            #[cfg(feature = "hot-reload")]
+            shader_watcher: settings
+                .shader_path
+                .clone()
+                .map(hot_reload::Watcher::new),
+            #[cfg(feature = "normalization")]
+            normalize_text: settings.normalize_text,
+            #[cfg(feature = "svg")]
+            svg: settings.svg_atlas.map(|atlas| {
+                svg::SvgGlyphs::new(
+                    atlas,
+                    settings.pad_glyphs,
+                    settings.strike_policy,
+                )
+            }),
+            oversized: oversized::OversizedGlyphs::new(
+                settings.oversized_glyph_size,
+            ),
+            on_cache_full: settings.on_cache_full,
+            memory_budget: None,
+            on_memory_pressure: None,
+        }
+    }
+
+    fn cache_dimensions(&self) -> (u32, u32) {
+        match &self.shared {
+            Some(shared) => shared.dimensions(),
+            None => self.glyph_brush.texture_dimensions(),
+        }
+    }
+}
+
+/// Returns the size to grow a glyph cache of the given dimensions to.
+///
+/// Returns `None` if the cache cannot grow beyond the given maximum
+/// dimension, the largest texture supported by the device.
+fn grown_cache_size(
+    (width, height): (u32, u32),
+    suggested: (u32, u32),
+    max_dimension: u32,
+) -> Option<(u32, u32)> {
+    // Caches grow to the largest size supported by every device first
+    let max_image_dimension = 2048;
+
+    let new = if (suggested.0 > max_image_dimension
+        || suggested.1 > max_image_dimension)
+        && (width < max_image_dimension || height < max_image_dimension)
+    {
+        (max_image_dimension, max_image_dimension)
+    } else {
+        suggested
+    };
+
+    let new = (new.0.min(max_dimension), new.1.min(max_dimension));
+
+    if new.0 <= width && new.1 <= height {
+        return None;
+    }
+
+    if log_enabled!(log::Level::Warn) {
+        warn!(
+            "Increasing glyph texture size {old:?} -> {new:?}. \
+             Consider building with `.initial_cache_size({new:?})` to avoid \
+             resizing",
+            old = (width, height),
+        );
+    }
+
+    Some(new)
+}
+
+/// Returns the error of a draw whose glyphs do not fit in a glyph cache of
+/// the given dimensions.
+fn cache_full_error((width, height): (u32, u32)) -> String {
+    format!(
+        "The glyphs of the draw do not fit in the glyph cache of \
+         {width}x{height} pixels"
+    )
+}
+
+impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
+    fn new(
+        device: &wgpu::Device,
+        settings: &Settings,
+        render_format: Option<wgpu::TextureFormat>,
+        raw_builder: glyph_brush::GlyphBrushBuilder<F, H>,
+    ) -> Self {
+        let cache_redraws =
+            raw_builder.cache_glyph_positioning && raw_builder.cache_redraws;
+        let glyph_brush = raw_builder.build();
+        let (cache_width, cache_height) = glyph_brush.texture_dimensions();
+        let pipeline = Pipeline::<()>::new(
+            device,
+            settings,
+            render_format,
+            cache_width,
+            cache_height,
+        );
+
+        GlyphBrush::from_parts(pipeline, glyph_brush, settings, cache_redraws)
+    }
+
+    /// Draws all queued sections onto a render target.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// If the target is empty, e.g. the surface of a minimized window, the
+    /// queue is processed but the render pass is skipped.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    pub fn draw_queued(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        target_width: u32,
+        target_height: u32,
+    ) -> Result<(), String> {
+        self.draw_queued_with_offset(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            target_width,
+            target_height,
+            (0.0, 0.0),
+            None,
+        )
+    }
+
+    /// Draws all queued sections onto a render target, applying a position
+    /// transform (e.g. a projection).
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    pub fn draw_queued_with_transform(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        transform: [f32; 16],
+    ) -> Result<(), String> {
+        let transform = Transform::new(transform);
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
+        self.pipeline.draw(
+            device,
+            staging_belt,
+            encoder,
+            DrawTarget {
+                view: target,
+                transform,
+                region: None,
+                viewport: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Draws all queued sections onto a render target, applying a view and a
+    /// projection matrix to their positions.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// The matrices are multiplied on the GPU, so world-space text can share
+    /// the matrices of the scene camera as they are, e.g. with the `view`
+    /// placing the text in the world.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    pub fn draw_queued_with_view_projection(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        view: [f32; 16],
+        projection: [f32; 16],
+    ) -> Result<(), String> {
+        let transform = Transform { projection, view };
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
+        self.pipeline.draw(
+            device,
+            staging_belt,
+            encoder,
+            DrawTarget {
+                view: target,
+                transform,
+                region: None,
+                viewport: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Draws all queued sections onto a render target, translated by the
+    /// given offset in pixels and clipped to an optional scissoring region.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// The offset is applied on the GPU, so scrolling a block of text that
+    /// is drawn on its own only updates a uniform, without laying it out or
+    /// uploading its glyphs again. Sections are culled to their bounds before
+    /// the offset is applied, so scrolled sections usually have unbounded
+    /// heights and rely on the `region` to clip them instead.
+    ///
+    /// If the target or the `region` is empty, the queue is processed but
+    /// the render pass is skipped.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_queued_with_offset(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        target_width: u32,
+        target_height: u32,
+        offset: (f32, f32),
+        region: Option<Region>,
+    ) -> Result<(), String> {
+        let transform = Transform::with_offset(
+            orthographic_projection(target_width, target_height),
+            offset,
+        );
+        let region = target_region(target_width, target_height, region);
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
+        self.pipeline.draw(
+            device,
+            staging_belt,
+            encoder,
+            DrawTarget {
+                view: target,
+                transform,
+                region,
+                viewport: None,
+            },
+        );
+
+        Ok(())
     }
 
-    /// Retains the section in the cache as if it had been used in the last
-    /// draw-frame.
+    /// Draws all queued sections onto a render target, applying a position
+    /// transform (e.g. a projection) and a scissoring region.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
     ///
-    /// Should not be necessary unless using multiple draws per frame with
-    /// distinct transforms, see [caching behaviour](#caching-behaviour).
+    /// If the region is empty, the queue is processed but the render pass is
+    /// skipped.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
     #[inline]
-    pub fn keep_cached<'a, S>(&mut self, section: S)
-    where
-        S: Into<Cow<'a, Section<'a>>>,
-    {
-        self.glyph_brush.keep_cached(section)
+    pub fn draw_queued_with_transform_and_scissoring(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        transform: [f32; 16],
+        region: Region,
+    ) -> Result<(), String> {
+        let transform = Transform::new(transform);
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
+        self.pipeline.draw(
+            device,
+            staging_belt,
+            encoder,
+            DrawTarget {
+                view: target,
+                transform,
+                region: Some(region),
+                viewport: None,
+            },
+        );
+
+        Ok(())
     }
 
-    /// Returns the available fonts.
+    /// Draws all queued sections into a viewport of a render target, with
+    /// coordinates relative to the top-left corner of the viewport.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
     ///
-    /// The `FontId` corresponds to the index of the font data.
+    /// Glyphs outside of the viewport are clipped. A scissoring `region`,
+    /// in pixels of the render target, can clip them further.
+    ///
+    /// If the viewport or the region is empty, e.g. in a minimized window,
+    /// the queue is processed but the render pass is skipped.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
     #[inline]
-    pub fn fonts(&self) -> &[F] {
-        self.glyph_brush.fonts()
-    }
+    pub fn draw_queued_with_viewport(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        viewport: Viewport,
+        region: Option<Region>,
+    ) -> Result<(), String> {
+        let transform = Transform::new(viewport.projection());
 
-    /// Adds an additional font to the one(s) initially added on build.
-    ///
-    /// Returns a new [`FontId`](struct.FontId.html) to reference this font.
-    pub fn add_font(&mut self, font: F) -> FontId {
-        self.glyph_brush.add_font(font)
+        self.process_queued(device, staging_belt, encoder, transform)?;
+        self.pipeline.draw(
+            device,
+            staging_belt,
+            encoder,
+            DrawTarget {
+                view: target,
+                transform,
+                region,
+                viewport: Some(viewport),
+            },
+        );
+
+        Ok(())
     }
-}
 
-impl<D, F, H> GlyphBrush<D, F, H>
-where
-    F: Font + Sync,
-    H: BuildHasher,
-{
-    fn process_queued(
+    /// Draws all queued sections onto a render target, using staging belts
+    /// owned by the brush.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// Unlike [`draw_queued`](#method.draw_queued), the staging belts are
+    /// finished and recalled by the brush. The command encoder must be
+    /// submitted to the given `queue` before drawing again, so use
+    /// [`draw_queued`](#method.draw_queued) to draw multiple times with the
+    /// same encoder.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    pub fn draw_queued_owned(
         &mut self,
         device: &wgpu::Device,
-        staging_belt: &mut wgpu::util::StagingBelt,
+        queue: &wgpu::Queue,
         encoder: &mut wgpu::CommandEncoder,
-    ) {
-        let pipeline = &mut self.pipeline;
+        target: &wgpu::TextureView,
+        target_width: u32,
+        target_height: u32,
+    ) -> Result<(), String> {
+        let mut staging_belt = self.belts.acquire(queue);
 
-        let mut brush_action;
+        let result = self.draw_queued(
+            device,
+            &mut staging_belt,
+            encoder,
+            target,
+            target_width,
+            target_height,
+        );
 
-        loop {
-            brush_action = self.glyph_brush.process_queued(
-                |rect, tex_data| {
-                    let offset = [rect.min[0] as u16, rect.min[1] as u16];
-                    let size = [rect.width() as u16, rect.height() as u16];
-
-                    pipeline.update_cache(
-                        device,
-                        staging_belt,
-                        encoder,
-                        offset,
-                        size,
-                        tex_data,
-                    );
-                },
-                Instance::from_vertex,
-            );
+        self.belts.release(staging_belt);
 
-            match brush_action {
-                Ok(_) => break,
-                Err(BrushError::TextureTooSmall { suggested }) => {
-                    // TODO: Obtain max texture dimensions using `wgpu`
-                    // This is currently not possible I think. Ask!
-                    let max_image_dimension = 2048;
-
-                    let (new_width, new_height) = if (suggested.0
-                        > max_image_dimension
-                        || suggested.1 > max_image_dimension)
-                        && (self.glyph_brush.texture_dimensions().0
-                            < max_image_dimension
-                            || self.glyph_brush.texture_dimensions().1
-                                < max_image_dimension)
-                    {
-                        (max_image_dimension, max_image_dimension)
-                    } else {
-                        suggested
-                    };
+        result
+    }
 
-                    if log_enabled!(log::Level::Warn) {
-                        warn!(
-                            "Increasing glyph texture size {old:?} -> {new:?}. \
-                             Consider building with `.initial_cache_size({new:?})` to avoid \
-                             resizing",
-                            old = self.glyph_brush.texture_dimensions(),
-                            new = (new_width, new_height),
-                        );
-                    }
+    /// Renders all queued sections into a PNG image of the given size with a
+    /// transparent background, and writes it to the given path.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// The sections are drawn exactly like onto an sRGB render target, so
+    /// labels or badges can be generated offline with the same rasterization
+    /// as at runtime. The commands are submitted to the given `queue`, and
+    /// the call blocks until the image is read back. Requires the `png`
+    /// feature.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[cfg(feature = "png")]
+    pub fn render_to_png(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: impl AsRef<std::path::Path>,
+        width: u32,
+        height: u32,
+    ) -> Result<(), String> {
+        let snapshot = snapshot::Snapshot::new(device, width, height)?;
 
-                    pipeline.increase_cache_size(device, new_width, new_height);
-                    self.glyph_brush.resize_texture(new_width, new_height);
-                }
-            }
-        }
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("wgpu_glyph::snapshot"),
+            });
 
-        match brush_action.unwrap() {
-            BrushAction::Draw(verts) => {
-                self.pipeline.upload(device, staging_belt, encoder, &verts);
-            }
-            BrushAction::ReDraw => {}
-        };
+        self.draw_queued_owned(
+            device,
+            queue,
+            &mut encoder,
+            snapshot.view(),
+            width,
+            height,
+        )?;
+
+        snapshot.save(device, queue, encoder, path.as_ref())
     }
 }
 
-impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
+impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
     fn new(
         device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
-        multisample: wgpu::MultisampleState,
-        render_format: wgpu::TextureFormat,
+        settings: &Settings,
+        render_format: Option<wgpu::TextureFormat>,
+        depth_stencil_state: wgpu::DepthStencilState,
         raw_builder: glyph_brush::GlyphBrushBuilder<F, H>,
     ) -> Self {
+        let cache_redraws =
+            raw_builder.cache_glyph_positioning && raw_builder.cache_redraws;
         let glyph_brush = raw_builder.build();
         let (cache_width, cache_height) = glyph_brush.texture_dimensions();
-        GlyphBrush {
-            pipeline: Pipeline::<()>::new(
-                device,
-                filter_mode,
-                multisample,
-                render_format,
-                cache_width,
-                cache_height,
-            ),
-            glyph_brush,
-        }
+        let pipeline = Pipeline::<wgpu::DepthStencilState>::new(
+            device,
+            settings,
+            render_format,
+            depth_stencil_state,
+            cache_width,
+            cache_height,
+        );
+
+        GlyphBrush::from_parts(pipeline, glyph_brush, settings, cache_redraws)
+    }
+
+    /// Discards the depth and stencil of the depth attachment of the next
+    /// draw at the end of its render pass, whatever the store operations of
+    /// the attachment.
+    ///
+    /// It saves writing them back to memory on tiled GPUs, like most mobile
+    /// ones, when the text is the last thing drawn with the attachment.
+    pub fn discard_depth_next_draw(&mut self) {
+        self.pipeline.discard_depth_next_draw();
     }
 
     /// Draws all queued sections onto a render target.
     /// See [`queue`](struct.GlyphBrush.html#method.queue).
     ///
+    /// If the target is empty, e.g. the surface of a minimized window, the
+    /// queue is processed but the render pass is skipped.
+    ///
     /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// Trims the cache, see [caching behaviour](#caching-behaviour).
-    ///
-    /// # Panics
-    /// Panics if the provided `target` has a texture format that does not match
-    /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_queued(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
         target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
         target_width: u32,
         target_height: u32,
     ) -> Result<(), String> {
-        self.draw_queued_with_transform(
+        self.draw_queued_with_offset(
             device,
             staging_belt,
             encoder,
             target,
-            orthographic_projection(target_width, target_height),
+            depth_stencil_attachment,
+            target_width,
+            target_height,
+            (0.0, 0.0),
+            None,
         )
     }
 
@@ -268,10 +2731,6 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
     /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// Trims the cache, see [caching behaviour](#caching-behaviour).
-    ///
-    /// # Panics
-    /// Panics if the provided `target` has a texture format that does not match
-    /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
     pub fn draw_queued_with_transform(
         &mut self,
@@ -279,93 +2738,89 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
         target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
         transform: [f32; 16],
     ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
+        let transform = Transform::new(transform);
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
         self.pipeline.draw(
             device,
             staging_belt,
             encoder,
-            target,
-            transform,
-            None,
+            DrawTarget {
+                view: target,
+                transform,
+                region: None,
+                viewport: None,
+            },
+            depth_stencil_attachment,
         );
 
         Ok(())
     }
 
-    /// Draws all queued sections onto a render target, applying a position
-    /// transform (e.g. a projection) and a scissoring region.
+    /// Draws all queued sections onto a render target, applying a view and a
+    /// projection matrix to their positions.
     /// See [`queue`](struct.GlyphBrush.html#method.queue).
     ///
+    /// The matrices are multiplied on the GPU, so world-space text can share
+    /// the matrices of the scene camera as they are, e.g. with the `view`
+    /// placing the text in the world.
+    ///
     /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// Trims the cache, see [caching behaviour](#caching-behaviour).
-    ///
-    /// # Panics
-    /// Panics if the provided `target` has a texture format that does not match
-    /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
-    pub fn draw_queued_with_transform_and_scissoring(
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_queued_with_view_projection(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
         target: &wgpu::TextureView,
-        transform: [f32; 16],
-        region: Region,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        view: [f32; 16],
+        projection: [f32; 16],
     ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
+        let transform = Transform { projection, view };
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
         self.pipeline.draw(
             device,
             staging_belt,
             encoder,
-            target,
-            transform,
-            Some(region),
+            DrawTarget {
+                view: target,
+                transform,
+                region: None,
+                viewport: None,
+            },
+            depth_stencil_attachment,
         );
 
         Ok(())
     }
-}
-
-impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
-    fn new(
-        device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
-        multisample: wgpu::MultisampleState,
-        render_format: wgpu::TextureFormat,
-        depth_stencil_state: wgpu::DepthStencilState,
-        raw_builder: glyph_brush::GlyphBrushBuilder<F, H>,
-    ) -> Self {
-        let glyph_brush = raw_builder.build();
-        let (cache_width, cache_height) = glyph_brush.texture_dimensions();
-        GlyphBrush {
-            pipeline: Pipeline::<wgpu::DepthStencilState>::new(
-                device,
-                filter_mode,
-                multisample,
-                render_format,
-                depth_stencil_state,
-                cache_width,
-                cache_height,
-            ),
-            glyph_brush,
-        }
-    }
 
-    /// Draws all queued sections onto a render target.
+    /// Draws all queued sections onto a render target, translated by the
+    /// given offset in pixels and clipped to an optional scissoring region.
     /// See [`queue`](struct.GlyphBrush.html#method.queue).
     ///
+    /// The offset is applied on the GPU, so scrolling a block of text that
+    /// is drawn on its own only updates a uniform, without laying it out or
+    /// uploading its glyphs again. Sections are culled to their bounds before
+    /// the offset is applied, so scrolled sections usually have unbounded
+    /// heights and rely on the `region` to clip them instead.
+    ///
+    /// If the target or the `region` is empty, the queue is processed but
+    /// the render pass is skipped.
+    ///
     /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// Trims the cache, see [caching behaviour](#caching-behaviour).
-    ///
-    /// # Panics
-    /// Panics if the provided `target` has a texture format that does not match
-    /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
-    pub fn draw_queued(
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_queued_with_offset(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
@@ -374,30 +2829,45 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
         depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
         target_width: u32,
         target_height: u32,
+        offset: (f32, f32),
+        region: Option<Region>,
     ) -> Result<(), String> {
-        self.draw_queued_with_transform(
+        let transform = Transform::with_offset(
+            orthographic_projection(target_width, target_height),
+            offset,
+        );
+        let region = target_region(target_width, target_height, region);
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
+        self.pipeline.draw(
             device,
             staging_belt,
             encoder,
-            target,
+            DrawTarget {
+                view: target,
+                transform,
+                region,
+                viewport: None,
+            },
             depth_stencil_attachment,
-            orthographic_projection(target_width, target_height),
-        )
+        );
+
+        Ok(())
     }
 
     /// Draws all queued sections onto a render target, applying a position
-    /// transform (e.g. a projection).
+    /// transform (e.g. a projection) and a scissoring region.
     /// See [`queue`](struct.GlyphBrush.html#method.queue).
     ///
+    /// If the region is empty, the queue is processed but the render pass is
+    /// skipped.
+    ///
     /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// Trims the cache, see [caching behaviour](#caching-behaviour).
-    ///
-    /// # Panics
-    /// Panics if the provided `target` has a texture format that does not match
-    /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
-    pub fn draw_queued_with_transform(
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_queued_with_transform_and_scissoring(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
@@ -405,62 +2875,138 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
         target: &wgpu::TextureView,
         depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
         transform: [f32; 16],
+        region: Region,
     ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
+        let transform = Transform::new(transform);
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
+
         self.pipeline.draw(
             device,
             staging_belt,
             encoder,
-            target,
+            DrawTarget {
+                view: target,
+                transform,
+                region: Some(region),
+                viewport: None,
+            },
             depth_stencil_attachment,
-            transform,
-            None,
         );
 
         Ok(())
     }
 
-    /// Draws all queued sections onto a render target, applying a position
-    /// transform (e.g. a projection) and a scissoring region.
+    /// Draws all queued sections into a viewport of a render target, with
+    /// coordinates relative to the top-left corner of the viewport.
     /// See [`queue`](struct.GlyphBrush.html#method.queue).
     ///
+    /// Glyphs outside of the viewport are clipped. A scissoring `region`,
+    /// in pixels of the render target, can clip them further.
+    ///
+    /// If the viewport or the region is empty, e.g. in a minimized window,
+    /// the queue is processed but the render pass is skipped.
+    ///
     /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// Trims the cache, see [caching behaviour](#caching-behaviour).
-    ///
-    /// # Panics
-    /// Panics if the provided `target` has a texture format that does not match
-    /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
-    pub fn draw_queued_with_transform_and_scissoring(
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_queued_with_viewport(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
         target: &wgpu::TextureView,
         depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
-        transform: [f32; 16],
-        region: Region,
+        viewport: Viewport,
+        region: Option<Region>,
     ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
+        let transform = Transform::new(viewport.projection());
+
+        self.process_queued(device, staging_belt, encoder, transform)?;
 
         self.pipeline.draw(
             device,
             staging_belt,
             encoder,
-            target,
+            DrawTarget {
+                view: target,
+                transform,
+                region,
+                viewport: Some(viewport),
+            },
             depth_stencil_attachment,
-            transform,
-            Some(region),
         );
 
         Ok(())
     }
+
+    /// Draws all queued sections onto a render target, using staging belts
+    /// owned by the brush.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// Unlike [`draw_queued`](#method.draw_queued), the staging belts are
+    /// finished and recalled by the brush. The command encoder must be
+    /// submitted to the given `queue` before drawing again, so use
+    /// [`draw_queued`](#method.draw_queued) to draw multiple times with the
+    /// same encoder.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_queued_owned(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        target_width: u32,
+        target_height: u32,
+    ) -> Result<(), String> {
+        let mut staging_belt = self.belts.acquire(queue);
+
+        let result = self.draw_queued(
+            device,
+            &mut staging_belt,
+            encoder,
+            target,
+            depth_stencil_attachment,
+            target_width,
+            target_height,
+        );
+
+        self.belts.release(staging_belt);
+
+        result
+    }
+}
+
+/// Returns the scissoring region of a draw into a target of the given size,
+/// which is empty if the target is, e.g. the surface of a minimized window,
+/// so the draw is skipped.
+fn target_region(
+    width: u32,
+    height: u32,
+    region: Option<Region>,
+) -> Option<Region> {
+    let target = Region {
+        x: 0,
+        y: 0,
+        width,
+        height,
+    };
+
+    if target.is_empty() {
+        Some(target)
+    } else {
+        region
+    }
 }
 
 /// Helper function to generate a generate a transform matrix.
+#[rustfmt::skip]
 pub fn orthographic_projection(width: u32, height: u32) -> [f32; 16] {
-    #[cfg_attr(rustfmt, rustfmt_skip)]
     [
         2.0 / width as f32, 0.0, 0.0, 0.0,
         0.0, -2.0 / height as f32, 0.0, 0.0,
@@ -469,6 +3015,51 @@ pub fn orthographic_projection(width: u32, height: u32) -> [f32; 16] {
     ]
 }
 
+/// Converts an sRGB-encoded color, like the colors of CSS or design tools, to
+/// the linear color expected by [`Text::with_color`].
+///
+/// The alpha is linear already, so it is only normalized.
+pub fn srgb_color([r, g, b, a]: [u8; 4]) -> [f32; 4] {
+    fn to_linear(component: u8) -> f32 {
+        let component = f32::from(component) / 255.0;
+
+        if component <= 0.04045 {
+            component / 12.92
+        } else {
+            ((component + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    [
+        to_linear(r),
+        to_linear(g),
+        to_linear(b),
+        f32::from(a) / 255.0,
+    ]
+}
+
+/// Sets the color of a [`Text`] from an sRGB-encoded color, like the colors
+/// of CSS or design tools.
+pub trait SrgbColor {
+    /// Sets the color from an sRGB-encoded color, converted to linear with
+    /// [`srgb_color`].
+    fn with_srgb_color(self, color: [u8; 4]) -> Self;
+}
+
+impl SrgbColor for Text<'_> {
+    #[inline]
+    fn with_srgb_color(self, color: [u8; 4]) -> Self {
+        self.with_color(srgb_color(color))
+    }
+}
+
+impl SrgbColor for OwnedText {
+    #[inline]
+    fn with_srgb_color(self, color: [u8; 4]) -> Self {
+        self.with_color(srgb_color(color))
+    }
+}
+
 impl<D, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<D, F, H> {
     #[inline]
     fn glyphs_custom_layout<'a, 'b, S, L>(
@@ -480,8 +3071,13 @@ impl<D, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<D, F, H> {
         L: GlyphPositioner + std::hash::Hash,
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush
-            .glyphs_custom_layout(section, custom_layout)
+        let section = section.into();
+
+        let layout = self.fallback.layout(custom_layout);
+
+        self.styled.styled(&section, self.glyph_style, |styled| {
+            self.glyph_brush.glyphs_custom_layout(styled, &layout)
+        })
     }
 
     #[inline]
@@ -499,8 +3095,13 @@ impl<D, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<D, F, H> {
         L: GlyphPositioner + std::hash::Hash,
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush
-            .glyph_bounds_custom_layout(section, custom_layout)
+        let section = section.into();
+
+        let layout = self.fallback.layout(custom_layout);
+
+        self.styled.styled(&section, self.glyph_style, |styled| {
+            self.glyph_brush.glyph_bounds_custom_layout(styled, &layout)
+        })
     }
 }
 
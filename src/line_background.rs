@@ -0,0 +1,53 @@
+use crate::ab_glyph::{point, Rect};
+use crate::Instance;
+
+use std::ops::Range;
+
+/// A background color filling whole lines of a section, like the highlight
+/// of the current line of a code editor or the lines of a diff.
+///
+/// Queue it with
+/// [`GlyphBrush::queue_with_line_backgrounds`](struct.GlyphBrush.html#method.queue_with_line_backgrounds).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineBackground {
+    /// The range of the indices of the lines, starting at 0.
+    pub lines: Range<usize>,
+    /// The color of the background.
+    pub color: [f32; 4],
+}
+
+impl LineBackground {
+    /// Creates a [`LineBackground`] of the given range of lines.
+    pub fn new(lines: Range<usize>, color: [f32; 4]) -> Self {
+        LineBackground { lines, color }
+    }
+
+    /// Creates a [`LineBackground`] of a single line.
+    pub fn line(line: usize, color: [f32; 4]) -> Self {
+        LineBackground::new(line..line + 1, color)
+    }
+
+    /// Returns the instances of the background of the given lines, spanning
+    /// from `left` to `right`.
+    pub(crate) fn instances<'a>(
+        &'a self,
+        lines: &'a [Rect],
+        (left, right): (f32, f32),
+        z: f32,
+    ) -> impl Iterator<Item = Instance> + 'a {
+        lines
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| self.lines.contains(index))
+            .map(move |(_, line)| {
+                Instance::solid(
+                    Rect {
+                        min: point(left, line.min.y),
+                        max: point(right, line.max.y),
+                    },
+                    self.color,
+                    z,
+                )
+            })
+    }
+}
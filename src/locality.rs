@@ -0,0 +1,17 @@
+use crate::Instance;
+
+/// Sorts instances by the region of the atlas they sample, atlas by atlas,
+/// row by row, so consecutive fragments sample nearby texels.
+///
+/// The sort is stable, so instances sampling the same region keep the order
+/// they were queued in.
+pub(crate) fn sort(instances: &mut [Instance]) {
+    instances.sort_by(|a, b| {
+        let (a_rect, b_rect) = (a.tex_rect(), b.tex_rect());
+
+        (a.atlas() & 0xff)
+            .cmp(&(b.atlas() & 0xff))
+            .then(a_rect.min.y.total_cmp(&b_rect.min.y))
+            .then(a_rect.min.x.total_cmp(&b_rect.min.x))
+    });
+}
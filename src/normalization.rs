@@ -0,0 +1,27 @@
+use crate::{OwnedSection, Section};
+
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// Returns the section with its texts normalized to NFC, if any of them is
+/// not normalized already.
+pub(crate) fn normalize(section: &Section<'_>) -> Option<OwnedSection> {
+    let normalized = |text: &str| match is_nfc_quick(text.chars()) {
+        IsNormalized::Yes => true,
+        IsNormalized::No => false,
+        IsNormalized::Maybe => unicode_normalization::is_nfc(text),
+    };
+
+    if section.text.iter().all(|text| normalized(text.text)) {
+        return None;
+    }
+
+    let mut section = section.to_owned();
+
+    for text in &mut section.text {
+        if !normalized(&text.text) {
+            text.text = text.text.nfc().collect();
+        }
+    }
+
+    Some(section)
+}
@@ -0,0 +1,285 @@
+use glyph_brush::ab_glyph::{point, Font, Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    ToSectionText, VerticalAlign,
+};
+use std::hash::{Hash, Hasher};
+
+/// How the text of a section overflows its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Overflow {
+    /// Glyphs outside of the bounds are drawn.
+    Visible,
+    /// Glyphs are clipped to the bounds, in pixels.
+    #[default]
+    Hidden,
+    /// The text is shifted up and left by the given offset, in pixels, and
+    /// clipped to the bounds.
+    ///
+    /// The bounds stay in place, so the text scrolls within them. Changing
+    /// the offset lays the section out again, but its glyphs are not
+    /// rasterized again.
+    Scroll {
+        /// The horizontal and vertical scroll offset.
+        offset: (f32, f32),
+    },
+}
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that sets how the text
+/// of a section overflows its bounds.
+///
+/// Sections are clipped to their bounds by default, which is the same as
+/// [`Overflow::Hidden`]. Their lines can also be limited to a maximum count,
+/// e.g. to cap the previews of a list at a few lines whatever their height,
+/// and the whole text aligned vertically within the bounds, like the label
+/// of a button or the content of a table cell.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounded<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    overflow: Overflow,
+    max_lines: Option<usize>,
+    ellipsis: bool,
+    vertical_align: Option<VerticalAlign>,
+}
+
+impl<L> Bounded<L> {
+    /// Wraps the given layout, clipping the text to its bounds.
+    pub fn new(layout: L) -> Self {
+        Bounded {
+            layout,
+            overflow: Overflow::Hidden,
+            max_lines: None,
+            ellipsis: false,
+            vertical_align: None,
+        }
+    }
+
+    /// Sets the overflow behavior of the section.
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Sets the maximum amount of lines drawn, leaving out the glyphs of the
+    /// lines after them.
+    ///
+    /// Lines without any glyph, like empty ones, are not counted.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    /// Sets whether an ellipsis (`…`) ends the last line drawn when lines are
+    /// left out, replacing the glyphs at its end that would not fit within
+    /// the bounds with it.
+    ///
+    /// By default, no ellipsis is drawn.
+    pub fn ellipsis(mut self, ellipsis: bool) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    /// Aligns the lines of the section as a whole to the top, the center or
+    /// the bottom of the height of its bounds, from the top of the ascent of
+    /// their first line to the bottom of the descent of their last one.
+    ///
+    /// Text taller than its bounds is aligned to their top, so it can still
+    /// be scrolled through. By default, the lines are where the wrapped
+    /// layout puts them, relative to the screen position of the section.
+    pub fn vertical_align(mut self, vertical_align: VerticalAlign) -> Self {
+        self.vertical_align = Some(vertical_align);
+        self
+    }
+
+    /// Moves the glyphs vertically to align them within the given bounds,
+    /// as if they were not scrolled by the given offset.
+    fn align<F: Font>(
+        &self,
+        fonts: &[F],
+        bounds: Rect,
+        scroll: f32,
+        glyphs: &mut [SectionGlyph],
+    ) {
+        let Some(vertical_align) = self.vertical_align else {
+            return;
+        };
+
+        if !bounds.height().is_finite() || glyphs.is_empty() {
+            return;
+        }
+
+        let (top, bottom) = glyphs.iter().fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(top, bottom), glyph| {
+                let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+                let y = glyph.glyph.position.y;
+
+                (top.min(y - font.ascent()), bottom.max(y - font.descent()))
+            },
+        );
+
+        let space = (bounds.height() - (bottom - top)).max(0.0);
+
+        let aligned_top = match vertical_align {
+            VerticalAlign::Top => bounds.min.y,
+            VerticalAlign::Center => bounds.min.y + space / 2.0,
+            VerticalAlign::Bottom => bounds.min.y + space,
+        };
+
+        let shift = aligned_top - (top + scroll);
+
+        for glyph in glyphs {
+            glyph.glyph.position.y += shift;
+        }
+    }
+
+    /// Leaves out the glyphs of the lines after the maximum amount, ending
+    /// the last line with an ellipsis if enabled.
+    fn truncate<F: Font>(
+        &self,
+        fonts: &[F],
+        right: f32,
+        glyphs: &mut Vec<SectionGlyph>,
+    ) {
+        let Some(max_lines) = self.max_lines else {
+            return;
+        };
+
+        let mut lines = 0;
+        let mut line_y = f32::NAN;
+
+        let Some(end) = glyphs.iter().position(|glyph| {
+            if glyph.glyph.position.y != line_y {
+                line_y = glyph.glyph.position.y;
+                lines += 1;
+            }
+
+            lines > max_lines
+        }) else {
+            return;
+        };
+
+        let cut = glyphs.split_off(end);
+
+        if !self.ellipsis || max_lines == 0 {
+            return;
+        }
+
+        let (Some(first_cut), Some(last)) = (cut.first(), glyphs.last()) else {
+            return;
+        };
+
+        let line_y = last.glyph.position.y;
+        let font_id = last.font_id;
+        let font = fonts[font_id.0].as_scaled(last.glyph.scale);
+        let ellipsis = font.glyph_id('…');
+        let ellipsis_advance = font.h_advance(ellipsis);
+
+        let mut section_index = first_cut.section_index;
+        let mut byte_index = first_cut.byte_index;
+        let mut position = point(
+            last.glyph.position.x + font.h_advance(last.glyph.id),
+            line_y,
+        );
+
+        // Glyphs are replaced from the end of the line until the ellipsis
+        // fits, starting with trailing whitespace
+        while let Some(last) =
+            glyphs.last().filter(|last| last.glyph.position.y == line_y)
+        {
+            let scaled = fonts[last.font_id.0].as_scaled(last.glyph.scale);
+            let end = last.glyph.position.x + scaled.h_advance(last.glyph.id);
+            let blank = scaled.outline_glyph(last.glyph.clone()).is_none();
+
+            if !blank && end + ellipsis_advance <= right {
+                position.x = end;
+                break;
+            }
+
+            section_index = last.section_index;
+            byte_index = last.byte_index;
+            position.x = last.glyph.position.x;
+
+            let _ = glyphs.pop();
+        }
+
+        glyphs.push(SectionGlyph {
+            section_index,
+            byte_index,
+            glyph: ellipsis.with_scale_and_position(font.scale(), position),
+            font_id,
+        });
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Bounded<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut scroll = 0.0;
+
+        let mut glyphs = match self.overflow {
+            Overflow::Visible | Overflow::Hidden => {
+                self.layout.calculate_glyphs(fonts, geometry, sections)
+            }
+            Overflow::Scroll { offset: (x, y) } => {
+                let (left, top) = geometry.screen_position;
+                scroll = y;
+
+                let scrolled = SectionGeometry {
+                    screen_position: (left - x, top - y),
+                    ..*geometry
+                };
+
+                self.layout.calculate_glyphs(fonts, &scrolled, sections)
+            }
+        };
+
+        let bounds = self.layout.bounds_rect(geometry);
+
+        self.truncate(fonts, bounds.max.x, &mut glyphs);
+        self.align(fonts, bounds, scroll, &mut glyphs);
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        match self.overflow {
+            Overflow::Visible => Rect {
+                min: point(f32::NEG_INFINITY, f32::NEG_INFINITY),
+                max: point(f32::INFINITY, f32::INFINITY),
+            },
+            Overflow::Hidden | Overflow::Scroll { .. } => {
+                self.layout.bounds_rect(geometry)
+            }
+        }
+    }
+}
+
+impl<L: Hash> Hash for Bounded<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.max_lines.hash(state);
+        self.ellipsis.hash(state);
+        self.vertical_align.hash(state);
+
+        match self.overflow {
+            Overflow::Visible => 0.hash(state),
+            Overflow::Hidden => 1.hash(state),
+            Overflow::Scroll { offset: (x, y) } => {
+                2.hash(state);
+                (x.to_bits(), y.to_bits()).hash(state);
+            }
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use crate::ab_glyph::{point, Font, Glyph, GlyphId, Rect};
+use crate::cache_full::Temporary;
+use crate::pipeline::{OversizedGlyph, Pipeline};
+use crate::style::Styled;
+use crate::{Extra, GlyphStyle, Instance, Section, SectionGlyph};
+
+use glyph_brush::{FontId, GlyphPositioner, SectionGeometry, ToSectionText};
+use std::collections::{hash_map, HashMap};
+use std::hash::{Hash, Hasher};
+
+/// The glyphs of a `GlyphBrush` too large for its glyph cache, like the ones
+/// of huge display text.
+///
+/// Every oversized glyph is rasterized into a dedicated texture, drawn with
+/// its own bind group, instead of growing the glyph cache to fit it. The
+/// textures of the glyphs not drawn in a frame are dropped.
+pub(crate) struct OversizedGlyphs {
+    size: u32,
+    glyphs: HashMap<Key, Entry>,
+    /// The glyphs out of a full glyph cache, drawn from temporary textures
+    /// by the next flush only.
+    temporaries: Vec<(u64, Temporary)>,
+    next_id: u64,
+}
+
+/// A glyph rasterized at a specific scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    font_id: FontId,
+    glyph_id: GlyphId,
+    scale: (u32, u32),
+}
+
+impl Key {
+    fn new(glyph: &SectionGlyph) -> Key {
+        Key {
+            font_id: glyph.font_id,
+            glyph_id: glyph.glyph.id,
+            scale: (
+                glyph.glyph.scale.x.to_bits(),
+                glyph.glyph.scale.y.to_bits(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    id: u64,
+    size: [u16; 2],
+    /// The pixel bounds of the glyph relative to its origin.
+    bounds: Rect,
+    /// The coverage of the glyph, until it is uploaded.
+    coverage: Option<Vec<u8>>,
+    /// The instances of the glyph queued since the last flush.
+    instances: Vec<Instance>,
+    /// Whether the glyph was kept since the last flush, without being drawn.
+    kept: bool,
+}
+
+impl OversizedGlyphs {
+    pub(crate) fn new(size: u32) -> OversizedGlyphs {
+        OversizedGlyphs {
+            size,
+            glyphs: HashMap::new(),
+            temporaries: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Returns the size glyphs are oversized above, in pixels.
+    pub(crate) fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns whether the given section may have oversized glyphs.
+    pub(crate) fn may_contain<X>(&self, section: &Section<'_, X>) -> bool {
+        section
+            .text
+            .iter()
+            .any(|text| may_exceed(text.scale.x.max(text.scale.y), self.size))
+    }
+
+    /// Queues the oversized glyphs among the given glyphs, with the extra of
+    /// their section text and the style of their section, rasterizing the
+    /// ones that are not drawn yet.
+    pub(crate) fn queue<F: Font>(
+        &mut self,
+        fonts: &[F],
+        extra: &[Extra],
+        glyphs: &[SectionGlyph],
+        bounds: Rect,
+        density: (f32, f32),
+        style: GlyphStyle,
+    ) {
+        for glyph in glyphs {
+            let font = &fonts[glyph.font_id.0];
+
+            if !is_oversized(font, &glyph.glyph, self.size) {
+                continue;
+            }
+
+            let entry = match self.glyphs.entry(Key::new(glyph)) {
+                hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                hash_map::Entry::Vacant(entry) => {
+                    // The glyph is rasterized at its origin and shifted when
+                    // drawn, so it is reused at any position.
+                    let Some(outlined) = font.outline_glyph(Glyph {
+                        position: point(0.0, 0.0),
+                        ..glyph.glyph.clone()
+                    }) else {
+                        continue;
+                    };
+
+                    let bounds = outlined.px_bounds();
+                    let width = bounds.width() as usize;
+                    let height = bounds.height() as usize;
+
+                    if width > usize::from(u16::MAX)
+                        || height > usize::from(u16::MAX)
+                    {
+                        continue;
+                    }
+
+                    let mut coverage = vec![0; width * height];
+
+                    outlined.draw(|x, y, c| {
+                        let index = y as usize * width + x as usize;
+
+                        if let Some(pixel) = coverage.get_mut(index) {
+                            *pixel = (c * 255.0) as u8;
+                        }
+                    });
+
+                    self.next_id += 1;
+
+                    entry.insert(Entry {
+                        id: self.next_id,
+                        size: [width as u16, height as u16],
+                        bounds,
+                        coverage: Some(coverage),
+                        instances: Vec::new(),
+                        kept: false,
+                    })
+                }
+            };
+
+            // Snapped to whole pixels, so the texels are not blurred
+            let position = point(
+                glyph.glyph.position.x.round(),
+                glyph.glyph.position.y.round(),
+            );
+
+            let mut instance =
+                Instance::from_vertex(glyph_brush::GlyphVertex {
+                    tex_coords: Rect {
+                        min: point(0.0, 0.0),
+                        max: point(1.0, 1.0),
+                    },
+                    pixel_coords: Rect {
+                        min: position + entry.bounds.min,
+                        max: position + entry.bounds.max,
+                    },
+                    bounds,
+                    extra: &Styled {
+                        extra: extra[glyph.section_index],
+                        style,
+                    },
+                });
+
+            if density != (1.0, 1.0) {
+                instance.unscale(density);
+            }
+
+            entry.instances.push(instance);
+        }
+    }
+
+    /// Keeps the textures of the oversized glyphs among the given glyphs
+    /// until the next flush, as if they were queued.
+    pub(crate) fn keep<F: Font>(
+        &mut self,
+        fonts: &[F],
+        glyphs: &[SectionGlyph],
+    ) {
+        for glyph in glyphs {
+            if !is_oversized(&fonts[glyph.font_id.0], &glyph.glyph, self.size) {
+                continue;
+            }
+
+            if let Some(entry) = self.glyphs.get_mut(&Key::new(glyph)) {
+                entry.kept = true;
+            }
+        }
+    }
+
+    /// Queues glyphs out of a full glyph cache, drawn from temporary textures
+    /// by the next flush. See [`crate::OnCacheFull::ClearAndRetry`].
+    pub(crate) fn queue_temporaries(&mut self, temporaries: Vec<Temporary>) {
+        for temporary in temporaries {
+            self.next_id += 1;
+            self.temporaries.push((self.next_id, temporary));
+        }
+    }
+
+    /// Uploads the oversized glyphs queued since the last call, dropping the
+    /// ones that were neither queued nor kept.
+    pub(crate) fn flush<D>(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &mut Pipeline<D>,
+    ) {
+        self.glyphs
+            .retain(|_, entry| entry.kept || !entry.instances.is_empty());
+
+        let glyphs: Vec<OversizedGlyph<'_>> = self
+            .glyphs
+            .values()
+            .map(|entry| OversizedGlyph {
+                id: entry.id,
+                size: entry.size,
+                coverage: entry.coverage.as_deref(),
+                instances: &entry.instances,
+            })
+            .chain(self.temporaries.iter().map(|(id, temporary)| {
+                OversizedGlyph {
+                    id: *id,
+                    size: temporary.size,
+                    coverage: Some(&temporary.coverage),
+                    instances: std::slice::from_ref(&temporary.instance),
+                }
+            }))
+            .collect();
+
+        pipeline.upload_oversized(device, staging_belt, encoder, &glyphs);
+        self.temporaries.clear();
+
+        for entry in self.glyphs.values_mut() {
+            entry.coverage = None;
+            entry.instances.clear();
+            entry.kept = false;
+        }
+    }
+}
+
+/// Returns whether the glyphs of the given scale may be larger than the
+/// given size, with a generous margin for glyphs larger than their scale.
+fn may_exceed(scale: f32, size: u32) -> bool {
+    scale * 4.0 > size as f32
+}
+
+/// Returns whether the pixel bounds of the given glyph are larger than the
+/// given size.
+fn is_oversized<F: Font>(font: &F, glyph: &Glyph, size: u32) -> bool {
+    if !may_exceed(glyph.scale.x.max(glyph.scale.y), size) {
+        return false;
+    }
+
+    font.outline_glyph(glyph.clone()).is_some_and(|outlined| {
+        let bounds = outlined.px_bounds();
+
+        bounds.width() > size as f32 || bounds.height() > size as f32
+    })
+}
+
+/// Removes the glyphs larger than the given size.
+pub(crate) fn remove_oversized<F: Font>(
+    fonts: &[F],
+    glyphs: &mut Vec<SectionGlyph>,
+    size: u32,
+) {
+    glyphs.retain(|glyph| {
+        !is_oversized(&fonts[glyph.font_id.0], &glyph.glyph, size)
+    });
+}
+
+/// A [`GlyphPositioner`] that leaves out the glyphs larger than a size, so
+/// they are not stored in the glyph cache.
+#[derive(Debug)]
+pub(crate) struct Layout<L> {
+    layout: L,
+    size: u32,
+}
+
+impl<L> Layout<L> {
+    pub(crate) fn new(layout: L, size: u32) -> Self {
+        Layout { layout, size }
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Layout<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs =
+            self.layout.calculate_glyphs(fonts, geometry, sections);
+
+        remove_oversized(fonts, &mut glyphs, self.size);
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for Layout<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.size.hash(state);
+    }
+}
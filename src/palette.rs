@@ -0,0 +1,6 @@
+/// The amount of colors in the palette of a `GlyphBrush`.
+pub const PALETTE_SIZE: usize = 256;
+
+/// The bit of the atlas of an [`Instance`](struct.Instance.html) that marks
+/// its color as multiplied by a palette color.
+pub(crate) const PALETTE_FLAG: u32 = 1 << 31;
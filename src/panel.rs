@@ -0,0 +1,57 @@
+use crate::ab_glyph::{point, Rect};
+use crate::Instance;
+
+/// A background panel drawn behind the text of a section, like the one of a
+/// tooltip or a speech bubble.
+///
+/// Queue it with
+/// [`GlyphBrush::queue_with_panel`](struct.GlyphBrush.html#method.queue_with_panel).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Panel {
+    /// The color of the panel.
+    pub color: [f32; 4],
+    /// The radius of the corners of the panel, in pixels.
+    pub corner_radius: f32,
+    /// The space between the text and the edges of the panel, in pixels.
+    pub padding: f32,
+}
+
+impl Panel {
+    /// Creates a [`Panel`] with the given color, without rounded corners nor
+    /// padding.
+    pub fn new(color: [f32; 4]) -> Self {
+        Panel {
+            color,
+            corner_radius: 0.0,
+            padding: 0.0,
+        }
+    }
+
+    /// Sets the radius of the corners of the panel.
+    pub fn with_corner_radius(mut self, corner_radius: f32) -> Self {
+        self.corner_radius = corner_radius;
+        self
+    }
+
+    /// Sets the space between the text and the edges of the panel.
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the instance of the panel around the given text bounds.
+    pub(crate) fn instance(&self, bounds: Rect, z: f32) -> Instance {
+        let rect = Rect {
+            min: point(
+                bounds.min.x - self.padding,
+                bounds.min.y - self.padding,
+            ),
+            max: point(
+                bounds.max.x + self.padding,
+                bounds.max.y + self.padding,
+            ),
+        };
+
+        Instance::rounded(rect, self.color, z, self.corner_radius)
+    }
+}
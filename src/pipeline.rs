@@ -1,7 +1,13 @@
 mod cache;
+mod debug;
 
-use crate::Region;
-use cache::Cache;
+use crate::palette::PALETTE_SIZE;
+use crate::scissor::Bounds;
+use crate::style::Styled;
+use crate::transform_slot::{SLOT_SHIFT, TRANSFORM_SLOTS};
+use crate::{GlyphStyle, Region, Resources, Viewport};
+pub(crate) use cache::Cache;
+use debug::Debug;
 
 use bytemuck::{Pod, Zeroable};
 use core::num::NonZeroU64;
@@ -9,33 +15,312 @@ use glyph_brush::ab_glyph::{point, Rect};
 use std::marker::PhantomData;
 use std::mem;
 
+/// The WGSL source of the default glyph shader.
+pub const SHADER: &str = concat!(
+    include_str!("shader/glyph.wgsl"),
+    include_str!("shader/glyph_common.wgsl"),
+);
+
+/// The WGSL source of the glyph shader used with
+/// [`InstanceMode::Storage`].
+pub const STORAGE_SHADER: &str = concat!(
+    include_str!("shader/glyph_storage.wgsl"),
+    include_str!("shader/glyph_common.wgsl"),
+);
+
+/// The WGSL source of the glyph shader used with
+/// [`InstanceMode::Compact`].
+pub const COMPACT_SHADER: &str = concat!(
+    include_str!("shader/glyph_compact.wgsl"),
+    include_str!("shader/glyph_common.wgsl"),
+);
+
+/// The way glyph instances are fed to the vertex shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstanceMode {
+    /// Instances are bound as a per-instance vertex buffer.
+    #[default]
+    Vertex,
+    /// Instances are bound as a read-only storage buffer in the binding `1`
+    /// of the bind group `1`, and the vertex shader reads them using the
+    /// instance index.
+    ///
+    /// Requires support for storage buffers in vertex shaders, which is not
+    /// available on WebGL.
+    Storage,
+    /// Instances are bound as a per-instance vertex buffer using the compact
+    /// [`CompactInstance`] encoding, which roughly halves the instance
+    /// bandwidth.
+    ///
+    /// Positions are limited to the `[-8192, 8192)` pixel range with a
+    /// quarter pixel precision, and colors to 8 bits per channel.
+    Compact,
+}
+
+impl InstanceMode {
+    fn instance_size(self) -> u64 {
+        match self {
+            InstanceMode::Vertex | InstanceMode::Storage => {
+                mem::size_of::<Instance>() as u64
+            }
+            InstanceMode::Compact => mem::size_of::<CompactInstance>() as u64,
+        }
+    }
+
+    fn buffer_usage(self) -> wgpu::BufferUsages {
+        match self {
+            InstanceMode::Vertex | InstanceMode::Compact => {
+                wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST
+            }
+            InstanceMode::Storage => {
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST
+            }
+        }
+    }
+}
+
+/// The configuration of a [`Pipeline`].
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub filter_mode: wgpu::FilterMode,
+    pub multisample: wgpu::MultisampleState,
+    pub write_mask: wgpu::ColorWrites,
+    pub primitive: wgpu::PrimitiveState,
+    pub shader: Option<crate::Cow<'static, str>>,
+    pub bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+    pub instance_mode: InstanceMode,
+    pub shared_cache: Option<crate::SharedCache>,
+    pub staging_belt_chunk_size: wgpu::BufferAddress,
+    pub frames_in_flight: usize,
+    pub label: crate::Cow<'static, str>,
+    pub supersampling: u32,
+    pub constants: Vec<(String, f64)>,
+    pub depth_order: Option<crate::DepthOrder>,
+    pub sort_by_atlas: bool,
+    pub auto_scissor: bool,
+    pub layered: bool,
+    pub track_damage: bool,
+    pub record_instances: bool,
+    pub track_content: bool,
+    pub layout_cache: bool,
+    #[cfg(feature = "hot-reload")]
+    pub shader_path: Option<std::path::PathBuf>,
+    #[cfg(feature = "normalization")]
+    pub normalize_text: bool,
+    pub oversized_glyph_size: u32,
+    pub on_cache_full: crate::OnCacheFull,
+    #[cfg(feature = "svg")]
+    pub svg_atlas: Option<usize>,
+    #[cfg(feature = "svg")]
+    pub pad_glyphs: bool,
+    pub strike_policy: crate::StrikePolicy,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            filter_mode: wgpu::FilterMode::Linear,
+            multisample: wgpu::MultisampleState::default(),
+            write_mask: wgpu::ColorWrites::ALL,
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                front_face: wgpu::FrontFace::Cw,
+                strip_index_format: Some(wgpu::IndexFormat::Uint16),
+                ..Default::default()
+            },
+            shader: None,
+            bind_group_layouts: Vec::new(),
+            instance_mode: InstanceMode::Vertex,
+            shared_cache: None,
+            staging_belt_chunk_size: 64 * 1024,
+            frames_in_flight: 1,
+            label: crate::Cow::Borrowed("wgpu_glyph"),
+            supersampling: 1,
+            constants: Vec::new(),
+            depth_order: None,
+            sort_by_atlas: false,
+            auto_scissor: false,
+            layered: false,
+            track_damage: false,
+            record_instances: false,
+            track_content: false,
+            layout_cache: false,
+            #[cfg(feature = "hot-reload")]
+            shader_path: None,
+            #[cfg(feature = "normalization")]
+            normalize_text: false,
+            oversized_glyph_size: 1024,
+            on_cache_full: crate::OnCacheFull::default(),
+            #[cfg(feature = "svg")]
+            svg_atlas: None,
+            #[cfg(feature = "svg")]
+            pad_glyphs: true,
+            strike_policy: crate::StrikePolicy::default(),
+        }
+    }
+}
+
+/// The atlas index of instances filled with a solid color.
+const SOLID_ATLAS: u32 = 255;
+
+/// The atlas index of instances filled with a solid color and rounded
+/// corners, whose radius is stored in the bits 17 to 27 of the atlas.
+const ROUNDED_ATLAS: u32 = 254;
+
+/// The maximum amount of atlases that can be bound at the same time,
+/// including the glyph cache.
+pub const MAX_ATLASES: usize = 4;
+
 pub struct Pipeline<Depth> {
-    transform: wgpu::Buffer,
-    sampler: wgpu::Sampler,
+    label: String,
+    /// The transform buffer of every frame in flight, with its uniforms.
+    transforms: Vec<(wgpu::Buffer, wgpu::BindGroup)>,
+    /// The index of the transform buffer written last.
+    transform_frame: usize,
+    palette: wgpu::Buffer,
+    palette_colors: Vec<[f32; 4]>,
+    palette_changed: bool,
+    transform_slots: wgpu::Buffer,
+    transform_slot_matrices: [[f32; 16]; TRANSFORM_SLOTS],
+    transform_slots_changed: bool,
     cache: Cache,
     uniform_layout: wgpu::BindGroupLayout,
-    uniforms: wgpu::BindGroup,
-    raw: wgpu::RenderPipeline,
-    instances: wgpu::Buffer,
+    cache_layout: wgpu::BindGroupLayout,
+    atlases: [Option<wgpu::TextureView>; MAX_ATLASES - 1],
+    shared_cache: Option<(u64, wgpu::TextureView)>,
+    bind_groups: Vec<Option<wgpu::BindGroup>>,
+    layout: wgpu::PipelineLayout,
+    shader: Shader,
+    state: RenderState,
+    raw: Vec<(Target, wgpu::RenderPipeline)>,
+    instance_mode: InstanceMode,
+    /// The instance chunks of every frame in flight.
+    frames: Vec<Vec<Chunk>>,
+    /// The index of the instance chunks written last.
+    frame: usize,
+    oversized: Vec<Oversized>,
+    max_chunk_instances: usize,
+    compact_instances: Vec<CompactInstance>,
     current_instances: usize,
-    supported_instances: usize,
-    current_transform: [f32; 16],
+    current_transform: Transform,
+    /// The color to clear the target with in the next render pass.
+    clear: Option<wgpu::Color>,
+    /// The view to resolve the target into in the next render pass.
+    resolve: Option<wgpu::TextureView>,
+    /// The store operation of the target in the next render pass.
+    store: wgpu::StoreOp,
+    /// Whether the depth and stencil of the depth attachment are discarded
+    /// at the end of the next render pass.
+    discard_depth: bool,
+    /// Whether render passes are scissored to the bounds of the instances.
+    auto_scissor: bool,
+    /// The bounds of the uploaded instances, if render passes are scissored
+    /// to them.
+    bounds: Bounds,
+    /// The bounds of the instances of the oversized glyphs, if render passes
+    /// are scissored to them.
+    oversized_bounds: Bounds,
+    debug: Option<Debug>,
     depth: PhantomData<Depth>,
 }
 
+/// The view a [`Pipeline`] draws into, with the transform, scissoring region
+/// and viewport of the draw.
+pub struct DrawTarget<'a> {
+    pub view: &'a wgpu::TextureView,
+    pub transform: Transform,
+    pub region: Option<Region>,
+    pub viewport: Option<Viewport>,
+}
+
+/// The pipeline constants set by the options of the builder, which are only
+/// set for the shaders declaring them.
+const OPTIONAL_CONSTANTS: [&str; 3] =
+    ["srgb_colors", "flip_y", "edge_sharpness"];
+
+/// A shader module with the pipeline-overridable constants declared by its
+/// source.
+struct Shader {
+    module: wgpu::ShaderModule,
+    overrides: Vec<String>,
+}
+
+impl Shader {
+    /// Returns the given constants the render pipelines of the shader are
+    /// created with, leaving out the optional ones it does not declare.
+    fn constants<'a>(
+        &self,
+        constants: &'a [(String, f64)],
+    ) -> Vec<(&'a str, f64)> {
+        constants
+            .iter()
+            .filter(|(name, _)| {
+                !OPTIONAL_CONSTANTS.contains(&name.as_str())
+                    || self.overrides.contains(name)
+            })
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect()
+    }
+}
+
+/// The state shared by the render pipelines of a [`Pipeline`], whatever
+/// their target.
+#[derive(Debug, Clone)]
+struct RenderState {
+    multisample: wgpu::MultisampleState,
+    write_mask: wgpu::ColorWrites,
+    primitive: wgpu::PrimitiveState,
+    depth_stencil: Option<wgpu::DepthStencilState>,
+    constants: Vec<(String, f64)>,
+}
+
+/// The attachments a render pipeline is compatible with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Target {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    depth_format: Option<wgpu::TextureFormat>,
+}
+
+/// A part of the instances of a [`Pipeline`], stored in its own buffer to
+/// respect the buffer size limits of the device.
+struct Chunk {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    capacity: usize,
+    count: usize,
+}
+
+/// A glyph too large for the glyph cache, drawn after the other instances
+/// from its own texture, bound in place of the glyph cache.
+struct Oversized {
+    id: u64,
+    texture: Cache,
+    chunk: Chunk,
+}
+
+/// The coverage and instances of an oversized glyph to upload to a
+/// [`Pipeline`].
+pub(crate) struct OversizedGlyph<'a> {
+    /// The identifier of the glyph, stable while it is drawn every frame.
+    pub id: u64,
+    pub size: [u16; 2],
+    /// The coverage of the glyph, only needed the first time it is drawn.
+    pub coverage: Option<&'a [u8]>,
+    pub instances: &'a [Instance],
+}
+
 impl Pipeline<()> {
     pub fn new(
         device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
-        multisample: wgpu::MultisampleState,
-        render_format: wgpu::TextureFormat,
+        settings: &Settings,
+        render_format: Option<wgpu::TextureFormat>,
         cache_width: u32,
         cache_height: u32,
     ) -> Pipeline<()> {
         build(
             device,
-            filter_mode,
-            multisample,
+            settings,
             render_format,
             None,
             cache_width,
@@ -48,37 +333,24 @@ impl Pipeline<()> {
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
-        target: &wgpu::TextureView,
-        transform: [f32; 16],
-        region: Option<Region>,
+        target: DrawTarget<'_>,
     ) {
-        draw(
-            self,
-            device,
-            staging_belt,
-            encoder,
-            target,
-            None,
-            transform,
-            region,
-        );
+        draw(self, device, staging_belt, encoder, target, None);
     }
 }
 
 impl Pipeline<wgpu::DepthStencilState> {
     pub fn new(
         device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
-        multisample: wgpu::MultisampleState,
-        render_format: wgpu::TextureFormat,
+        settings: &Settings,
+        render_format: Option<wgpu::TextureFormat>,
         depth_stencil_state: wgpu::DepthStencilState,
         cache_width: u32,
         cache_height: u32,
     ) -> Pipeline<wgpu::DepthStencilState> {
         build(
             device,
-            filter_mode,
-            multisample,
+            settings,
             render_format,
             Some(depth_stencil_state),
             cache_width,
@@ -91,10 +363,8 @@ impl Pipeline<wgpu::DepthStencilState> {
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
-        target: &wgpu::TextureView,
+        target: DrawTarget<'_>,
         depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
-        transform: [f32; 16],
-        region: Option<Region>,
     ) {
         draw(
             self,
@@ -103,88 +373,722 @@ impl Pipeline<wgpu::DepthStencilState> {
             encoder,
             target,
             Some(depth_stencil_attachment),
-            transform,
-            region,
         );
     }
 }
 
 impl<Depth> Pipeline<Depth> {
+    pub fn uniform_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.uniform_layout
+    }
+
+    pub fn uniforms(&self) -> &wgpu::BindGroup {
+        &self.transforms[self.transform_frame].1
+    }
+
+    pub fn cache_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.cache_layout
+    }
+
+    pub fn cache_bind_group(&self) -> &wgpu::BindGroup {
+        &self.chunks()[0].bind_group
+    }
+
+    pub fn set_bind_group(
+        &mut self,
+        index: usize,
+        bind_group: wgpu::BindGroup,
+    ) {
+        if self.bind_groups.len() <= index {
+            self.bind_groups.resize(index + 1, None);
+        }
+
+        self.bind_groups[index] = Some(bind_group);
+    }
+
+    pub fn instances(&self) -> &wgpu::Buffer {
+        &self.chunks()[0].buffer
+    }
+
+    pub fn instance_count(&self) -> u32 {
+        self.chunks()[0].count as u32
+    }
+
+    pub fn instance_capacity(&self) -> usize {
+        self.frames
+            .iter()
+            .flatten()
+            .map(|chunk| chunk.capacity)
+            .sum()
+    }
+
+    pub fn instance_buffer_bytes(&self) -> u64 {
+        self.instance_capacity() as u64 * self.instance_mode.instance_size()
+    }
+
+    /// Returns the amount of bytes of GPU memory used by the glyph cache and
+    /// the textures of the oversized glyphs.
+    pub fn atlas_bytes(&self) -> u64 {
+        self.cache.gpu_bytes()
+            + self
+                .oversized
+                .iter()
+                .map(|oversized| oversized.texture.gpu_bytes())
+                .sum::<u64>()
+    }
+
+    /// Returns the amount of bytes of GPU memory used by the instance
+    /// buffers of every frame in flight, the oversized glyphs and the debug
+    /// overlay.
+    pub fn all_instance_buffer_bytes(&self) -> u64 {
+        let oversized: usize = self
+            .oversized
+            .iter()
+            .map(|oversized| oversized.chunk.capacity)
+            .sum();
+
+        self.instance_buffer_bytes()
+            + oversized as u64 * self.instance_mode.instance_size()
+            + self.debug.as_ref().map_or(0, Debug::buffer_bytes)
+    }
+
+    /// Returns the amount of bytes of GPU memory used by the transform of
+    /// every frame in flight, the palette and the transform slots.
+    pub fn uniform_bytes(&self) -> u64 {
+        self.transforms
+            .iter()
+            .map(|(buffer, _)| buffer.size())
+            .sum::<u64>()
+            + self.palette.size()
+            + self.transform_slots.size()
+    }
+
+    /// Returns the amount of bytes of CPU memory used by the copies of the
+    /// textures and the instances converted before upload.
+    pub fn cpu_bytes(&self) -> u64 {
+        self.cache.cpu_bytes()
+            + self
+                .oversized
+                .iter()
+                .map(|oversized| oversized.texture.cpu_bytes())
+                .sum::<u64>()
+            + (self.compact_instances.capacity()
+                * mem::size_of::<CompactInstance>()) as u64
+    }
+
+    pub fn instance_bytes(&self) -> u64 {
+        self.current_instances as u64 * self.instance_mode.instance_size()
+    }
+
     pub fn update_cache(
         &mut self,
-        device: &wgpu::Device,
-        staging_belt: &mut wgpu::util::StagingBelt,
-        encoder: &mut wgpu::CommandEncoder,
         offset: [u16; 2],
         size: [u16; 2],
         data: &[u8],
     ) {
-        self.cache
-            .update(device, staging_belt, encoder, offset, size, data);
+        self.cache.update(offset, size, data);
     }
 
-    pub fn increase_cache_size(
+    pub fn flush_cache(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        self.cache.flush(device, staging_belt, encoder);
+    }
+
+    pub fn resize_cache(
         &mut self,
         device: &wgpu::Device,
         width: u32,
         height: u32,
     ) {
-        self.cache = Cache::new(device, width, height);
+        self.cache.resize(device, width, height);
+        self.recreate_cache_bind_group(device);
+    }
+
+    pub fn draw_debug_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        rects: &[[f32; 4]],
+        region: Region,
+    ) {
+        let cache_view = self
+            .shared_cache
+            .as_ref()
+            .map_or(&self.cache.view, |(_, view)| view);
+
+        let debug = self.debug.get_or_insert_with(|| {
+            // The overlay is blended over the text it inspects
+            Debug::new(
+                device,
+                &self.label,
+                wgpu::MultisampleState {
+                    alpha_to_coverage_enabled: false,
+                    ..self.state.multisample
+                },
+            )
+        });
+
+        debug.upload(device, staging_belt, encoder, rects);
+        debug.draw(device, encoder, target, cache_view, region);
+    }
+
+    pub fn set_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        index: usize,
+        atlas: Option<wgpu::TextureView>,
+    ) {
+        assert!(
+            (1..MAX_ATLASES).contains(&index),
+            "Invalid atlas index: {index}"
+        );
+
+        self.atlases[index - 1] = atlas;
+        self.recreate_cache_bind_group(device);
+    }
+
+    pub fn set_palette_color(&mut self, index: u8, color: [f32; 4]) {
+        self.palette_colors[usize::from(index)] = color;
+        self.palette_changed = true;
+    }
+
+    pub fn set_transform_slot(&mut self, slot: u8, transform: [f32; 16]) {
+        assert!(
+            (1..TRANSFORM_SLOTS).contains(&usize::from(slot)),
+            "Invalid transform slot: {slot}"
+        );
+
+        self.transform_slot_matrices[usize::from(slot)] = transform;
+        self.transform_slots_changed = true;
+    }
+
+    /// Clears the target with the given color in the next render pass.
+    pub fn clear_next_draw(&mut self, color: wgpu::Color) {
+        self.clear = Some(color);
+    }
+
+    /// Resolves the multi-sampled target into the given view in the next
+    /// render pass.
+    pub fn resolve_next_draw(&mut self, view: &wgpu::TextureView) {
+        self.resolve = Some(view.clone());
+    }
+
+    /// Stores the target with the given operation in the next render pass.
+    pub fn store_next_draw(&mut self, store: wgpu::StoreOp) {
+        self.store = store;
+    }
 
-        self.uniforms = create_uniforms(
+    /// Discards the depth and stencil of the depth attachment at the end of
+    /// the next render pass.
+    pub fn discard_depth_next_draw(&mut self) {
+        self.discard_depth = true;
+    }
+
+    /// Sets the value of a pipeline-overridable constant of the shader.
+    ///
+    /// The render pipelines are recreated on their next use if it changes.
+    pub fn set_constant(&mut self, name: &str, value: f64) {
+        match self
+            .state
+            .constants
+            .iter_mut()
+            .find(|(current, _)| current == name)
+        {
+            Some((_, current)) if *current == value => return,
+            Some((_, current)) => *current = value,
+            None => self.state.constants.push((name.to_string(), value)),
+        }
+
+        self.raw.clear();
+    }
+
+    /// Replaces the shader of the pipeline, recreating its render pipelines.
+    ///
+    /// Keeps the current shader if the new one is invalid, returning the
+    /// validation error.
+    #[cfg(feature = "hot-reload")]
+    pub fn set_shader(
+        &mut self,
+        device: &wgpu::Device,
+        source: crate::Cow<'static, str>,
+    ) -> Result<(), String> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let shader = create_shader(device, &self.label, source);
+
+        let raw: Vec<_> = self
+            .raw
+            .iter()
+            .map(|(target, _)| {
+                let raw = create_render_pipeline(
+                    device,
+                    &self.label,
+                    &self.layout,
+                    &shader,
+                    self.instance_mode,
+                    &self.state,
+                    *target,
+                );
+
+                (*target, raw)
+            })
+            .collect();
+
+        if let Some(error) = crate::hot_reload::pop_error_scope(device) {
+            return Err(error.to_string());
+        }
+
+        self.shader = shader;
+        self.raw = raw;
+
+        Ok(())
+    }
+
+    /// Returns the index of the render pipeline compatible with the given
+    /// target, creating it if needed.
+    fn render_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        target: Target,
+    ) -> usize {
+        if let Some(index) = self
+            .raw
+            .iter()
+            .position(|(compatible, _)| *compatible == target)
+        {
+            return index;
+        }
+
+        let raw = create_render_pipeline(
             device,
-            &self.uniform_layout,
-            &self.transform,
-            &self.sampler,
-            &self.cache.view,
+            &self.label,
+            &self.layout,
+            &self.shader,
+            self.instance_mode,
+            &self.state,
+            target,
         );
+
+        self.raw.push((target, raw));
+        self.raw.len() - 1
     }
 
-    pub fn upload(
+    /// Binds the texture of a shared cache instead of the own cache of the
+    /// pipeline, rebinding it only when the given generation changes.
+    pub fn bind_shared_cache(
         &mut self,
         device: &wgpu::Device,
-        staging_belt: &mut wgpu::util::StagingBelt,
-        encoder: &mut wgpu::CommandEncoder,
-        instances: &[Instance],
+        cache: &Cache,
+        generation: u64,
     ) {
-        if instances.is_empty() {
-            self.current_instances = 0;
+        if matches!(&self.shared_cache, Some((current, _)) if *current == generation)
+        {
             return;
         }
 
-        if instances.len() > self.supported_instances {
-            self.instances = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("wgpu_glyph::Pipeline instances"),
-                size: mem::size_of::<Instance>() as u64
-                    * instances.len() as u64,
-                usage: wgpu::BufferUsages::VERTEX
-                    | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
+        self.shared_cache = Some((generation, cache.view.clone()));
+        self.recreate_cache_bind_group(device);
+    }
+
+    /// Binds the texture of the given cache as the atlas with the given
+    /// index, e.g. for the color glyphs of the brush.
+    #[cfg(feature = "svg")]
+    pub fn bind_atlas_cache(
+        &mut self,
+        device: &wgpu::Device,
+        index: usize,
+        cache: &Cache,
+    ) {
+        self.set_atlas(device, index, Some(cache.view.clone()));
+    }
+
+    pub fn resources(&self) -> Resources<'_> {
+        let atlases = self.atlases.iter().flatten().map(|view| view.texture());
+        let oversized = self
+            .oversized
+            .iter()
+            .map(|oversized| oversized.texture.view.texture());
 
-            self.supported_instances = instances.len();
+        Resources {
+            transform: &self.transforms[self.transform_frame].0,
+            palette: &self.palette,
+            instances: self
+                .frames
+                .iter()
+                .flatten()
+                .map(|chunk| &chunk.buffer)
+                .collect(),
+            glyph_cache: self.cache_view().texture(),
+            textures: atlases.chain(oversized).collect(),
+        }
+    }
+
+    /// Returns the instance chunks written last.
+    fn chunks(&self) -> &[Chunk] {
+        &self.frames[self.frame]
+    }
+
+    fn cache_view(&self) -> &wgpu::TextureView {
+        self.shared_cache
+            .as_ref()
+            .map_or(&self.cache.view, |(_, view)| view)
+    }
+
+    fn recreate_cache_bind_group(&mut self, device: &wgpu::Device) {
+        for frame in 0..self.frames.len() {
+            for i in 0..self.frames[frame].len() {
+                self.frames[frame][i].bind_group = self
+                    .create_chunk_bind_group(
+                        device,
+                        &self.frames[frame][i].buffer,
+                    );
+            }
         }
 
-        let instances_bytes = bytemuck::cast_slice(instances);
+        for i in 0..self.oversized.len() {
+            let oversized = &self.oversized[i];
 
-        if let Some(size) = NonZeroU64::new(instances_bytes.len() as u64) {
-            let mut instances_view = staging_belt.write_buffer(
-                encoder,
-                &self.instances,
-                0,
-                size,
+            self.oversized[i].chunk.bind_group = create_cache_bind_group(
                 device,
+                &self.label,
+                &self.cache_layout,
+                &oversized.texture.view,
+                &self.atlases,
+                self.instance_mode,
+                &oversized.chunk.buffer,
             );
+        }
+    }
+
+    fn create_chunk_bind_group(
+        &self,
+        device: &wgpu::Device,
+        buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        create_cache_bind_group(
+            device,
+            &self.label,
+            &self.cache_layout,
+            self.cache_view(),
+            &self.atlases,
+            self.instance_mode,
+            buffer,
+        )
+    }
+
+    fn create_chunk(&self, device: &wgpu::Device, capacity: usize) -> Chunk {
+        let buffer =
+            create_instances(device, &self.label, self.instance_mode, capacity);
+        let bind_group = self.create_chunk_bind_group(device, &buffer);
+
+        Chunk {
+            buffer,
+            bind_group,
+            capacity,
+            count: 0,
+        }
+    }
+
+    /// Uploads the given instances, splitting them into chunks when they do
+    /// not fit in a single buffer.
+    ///
+    /// The instances are written to the chunks of the next frame in flight,
+    /// which the GPU is likely done reading if the frames in flight are not
+    /// exceeded. Submissions are not tracked, as wgpu orders the writes after
+    /// the previous draws either way.
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        backgrounds: &[Instance],
+        glyphs: &[Instance],
+        custom: &[Instance],
+    ) {
+        let total = backgrounds.len() + glyphs.len() + custom.len();
+        let max = self.max_chunk_instances;
 
-            instances_view.copy_from_slice(instances_bytes);
+        if self.auto_scissor {
+            self.bounds =
+                Bounds::of(backgrounds.iter().chain(glyphs).chain(custom));
         }
 
-        self.current_instances = instances.len();
+        self.current_instances = total;
+        self.frame = (self.frame + 1) % self.frames.len();
+
+        let frame = self.frame;
+
+        for (i, start) in (0..total).step_by(max).enumerate() {
+            let count = (total - start).min(max);
+
+            if i == self.frames[frame].len() {
+                let chunk = self.create_chunk(device, count);
+                self.frames[frame].push(chunk);
+            } else if self.frames[frame][i].capacity < count {
+                self.frames[frame][i] = self.create_chunk(device, count);
+            }
+
+            self.frames[frame][i].count = count;
+        }
+
+        for chunk in self.frames[frame].iter_mut().skip(total.div_ceil(max)) {
+            chunk.count = 0;
+        }
+
+        let mut index = 0;
+
+        for mut instances in [backgrounds, glyphs, custom] {
+            while !instances.is_empty() {
+                let chunk = &self.frames[frame][index / max];
+                let offset = index % max;
+                let (part, rest) =
+                    instances.split_at(instances.len().min(max - offset));
+
+                write_instances(
+                    device,
+                    staging_belt,
+                    encoder,
+                    &chunk.buffer,
+                    offset as u64 * self.instance_mode.instance_size(),
+                    instance_bytes(
+                        self.instance_mode,
+                        &mut self.compact_instances,
+                        part,
+                    ),
+                );
+
+                index += part.len();
+                instances = rest;
+            }
+        }
+    }
+
+    /// Uploads the instances of the given oversized glyphs, creating the
+    /// textures of the new ones and dropping the ones not given.
+    pub fn upload_oversized(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        glyphs: &[OversizedGlyph<'_>],
+    ) {
+        self.oversized
+            .retain(|oversized| glyphs.iter().any(|g| g.id == oversized.id));
+
+        if self.auto_scissor {
+            self.oversized_bounds =
+                Bounds::of(glyphs.iter().flat_map(|glyph| glyph.instances));
+        }
+
+        for glyph in glyphs {
+            let index = match self
+                .oversized
+                .iter()
+                .position(|oversized| oversized.id == glyph.id)
+            {
+                Some(index) => index,
+                None => {
+                    let Some(coverage) = glyph.coverage else {
+                        continue;
+                    };
+
+                    let max = device.limits().max_texture_dimension_2d;
+
+                    if u32::from(glyph.size[0]) > max
+                        || u32::from(glyph.size[1]) > max
+                    {
+                        continue;
+                    }
+
+                    let mut texture = Cache::new(
+                        device,
+                        &format!("{}::oversized", self.label),
+                        u32::from(glyph.size[0]),
+                        u32::from(glyph.size[1]),
+                    );
+
+                    texture.update([0, 0], glyph.size, coverage);
+                    texture.flush(device, staging_belt, encoder);
+
+                    let chunk = self.create_oversized_chunk(
+                        device,
+                        &texture,
+                        glyph.instances.len(),
+                    );
+
+                    self.oversized.push(Oversized {
+                        id: glyph.id,
+                        texture,
+                        chunk,
+                    });
+
+                    self.oversized.len() - 1
+                }
+            };
+
+            let instances = &glyph.instances
+                [..glyph.instances.len().min(self.max_chunk_instances)];
+            let count = instances.len();
+
+            if self.oversized[index].chunk.capacity < count {
+                self.oversized[index].chunk = self.create_oversized_chunk(
+                    device,
+                    &self.oversized[index].texture,
+                    count,
+                );
+            }
+
+            let chunk = &mut self.oversized[index].chunk;
+            chunk.count = count;
+
+            if count > 0 {
+                write_instances(
+                    device,
+                    staging_belt,
+                    encoder,
+                    &chunk.buffer,
+                    0,
+                    instance_bytes(
+                        self.instance_mode,
+                        &mut self.compact_instances,
+                        instances,
+                    ),
+                );
+            }
+        }
+    }
+
+    fn create_oversized_chunk(
+        &self,
+        device: &wgpu::Device,
+        texture: &Cache,
+        capacity: usize,
+    ) -> Chunk {
+        let capacity = capacity.clamp(1, self.max_chunk_instances);
+        let buffer =
+            create_instances(device, &self.label, self.instance_mode, capacity);
+
+        let bind_group = create_cache_bind_group(
+            device,
+            &self.label,
+            &self.cache_layout,
+            &texture.view,
+            &self.atlases,
+            self.instance_mode,
+            &buffer,
+        );
+
+        Chunk {
+            buffer,
+            bind_group,
+            capacity,
+            count: 0,
+        }
+    }
+}
+
+/// Returns the bytes of the given instances in the layout of the given
+/// mode, encoding compact instances into the given buffer.
+fn instance_bytes<'a>(
+    instance_mode: InstanceMode,
+    compact_instances: &'a mut Vec<CompactInstance>,
+    instances: &'a [Instance],
+) -> &'a [u8] {
+    if instance_mode == InstanceMode::Compact {
+        compact_instances.clear();
+        compact_instances.extend(instances.iter().map(CompactInstance::from));
+
+        bytemuck::cast_slice(compact_instances)
+    } else {
+        bytemuck::cast_slice(instances)
     }
 }
 
+/// Writes the given instance bytes into the buffer of a chunk, starting at
+/// the given byte offset.
+fn write_instances(
+    device: &wgpu::Device,
+    staging_belt: &mut wgpu::util::StagingBelt,
+    encoder: &mut wgpu::CommandEncoder,
+    buffer: &wgpu::Buffer,
+    offset: u64,
+    bytes: &[u8],
+) {
+    let mut instances_view = staging_belt.write_buffer(
+        encoder,
+        buffer,
+        offset,
+        NonZeroU64::new(bytes.len() as u64).expect("Non-empty instances"),
+        device,
+    );
+
+    instances_view.copy_from_slice(bytes);
+}
+
+/// The matrices applied to the positions of glyphs, bound as the `Globals`
+/// uniform of the glyph shaders.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Zeroable, Pod)]
+pub struct Transform {
+    /// The projection matrix, applied last.
+    pub projection: [f32; 16],
+    /// The view matrix, applied first.
+    pub view: [f32; 16],
+}
+
+impl Transform {
+    /// Creates a [`Transform`] with the given projection and an identity
+    /// view.
+    pub fn new(projection: [f32; 16]) -> Self {
+        Transform {
+            projection,
+            view: IDENTITY_MATRIX,
+        }
+    }
+
+    /// Creates a [`Transform`] with the given projection and a view that
+    /// translates positions by the given offset.
+    #[rustfmt::skip]
+    pub fn with_offset(projection: [f32; 16], (x, y): (f32, f32)) -> Self {
+        Transform {
+            projection,
+            view: [
+                1.0, 0.0, 0.0, 0.0,
+                0.0, 1.0, 0.0, 0.0,
+                0.0, 0.0, 1.0, 0.0,
+                x, y, 0.0, 1.0,
+            ],
+        }
+    }
+
+    /// Returns the depth of the given position after the transform, i.e. its
+    /// `z` in normalized device coordinates.
+    pub(crate) fn depth(&self, position: [f32; 3]) -> f32 {
+        let [x, y, z] = position;
+        let [.., z, w] =
+            multiply(&self.projection, multiply(&self.view, [x, y, z, 1.0]));
+
+        z / w
+    }
+}
+
+/// Multiplies a column-major matrix by a vector.
+pub(crate) fn multiply(matrix: &[f32; 16], vector: [f32; 4]) -> [f32; 4] {
+    std::array::from_fn(|row| {
+        (0..4)
+            .map(|column| matrix[column * 4 + row] * vector[column])
+            .sum()
+    })
+}
+
 // Helpers
-#[cfg_attr(rustfmt, rustfmt_skip)]
+#[rustfmt::skip]
 const IDENTITY_MATRIX: [f32; 16] = [
     1.0, 0.0, 0.0, 0.0,
     0.0, 1.0, 0.0, 0.0,
@@ -194,37 +1098,63 @@ const IDENTITY_MATRIX: [f32; 16] = [
 
 fn build<D>(
     device: &wgpu::Device,
-    filter_mode: wgpu::FilterMode,
-    multisample: wgpu::MultisampleState,
-    render_format: wgpu::TextureFormat,
+    settings: &Settings,
+    render_format: Option<wgpu::TextureFormat>,
     depth_stencil: Option<wgpu::DepthStencilState>,
     cache_width: u32,
     cache_height: u32,
 ) -> Pipeline<D> {
     use wgpu::util::DeviceExt;
 
-    let transform =
+    let label = &settings.label;
+
+    let frames_in_flight = settings.frames_in_flight.max(1);
+
+    let transforms: Vec<wgpu::Buffer> = (0..frames_in_flight)
+        .map(|_| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label}::Pipeline transform")),
+                contents: bytemuck::bytes_of(&Transform::new(IDENTITY_MATRIX)),
+                usage: wgpu::BufferUsages::UNIFORM
+                    | wgpu::BufferUsages::COPY_DST,
+            })
+        })
+        .collect();
+
+    let palette_colors = vec![[1.0; 4]; PALETTE_SIZE];
+
+    let palette =
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{label}::Pipeline palette")),
+            contents: bytemuck::cast_slice(&palette_colors),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+    let transform_slot_matrices = [IDENTITY_MATRIX; TRANSFORM_SLOTS];
+
+    let transform_slots =
         device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: None,
-            contents: bytemuck::cast_slice(&IDENTITY_MATRIX),
+            label: Some(&format!("{label}::Pipeline transform slots")),
+            contents: bytemuck::cast_slice(&transform_slot_matrices),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
     let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some(&format!("{label}::Pipeline sampler")),
         address_mode_u: wgpu::AddressMode::ClampToEdge,
         address_mode_v: wgpu::AddressMode::ClampToEdge,
         address_mode_w: wgpu::AddressMode::ClampToEdge,
-        mag_filter: filter_mode,
-        min_filter: filter_mode,
-        mipmap_filter: filter_mode,
+        mag_filter: settings.filter_mode,
+        min_filter: settings.filter_mode,
+        mipmap_filter: settings.filter_mode,
         ..Default::default()
     });
 
-    let cache = Cache::new(device, cache_width, cache_height);
+    let cache = Cache::new(device, label, cache_width, cache_height);
 
     let uniform_layout =
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("wgpu_glyph::Pipeline uniforms"),
+            label: Some(&format!("{label}::Pipeline uniforms")),
             entries: &[
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
@@ -233,7 +1163,7 @@ fn build<D>(
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
                         min_binding_size: wgpu::BufferSize::new(
-                            mem::size_of::<[f32; 16]>() as u64,
+                            mem::size_of::<Transform>() as u64,
                         ),
                     },
                     count: None,
@@ -248,83 +1178,320 @@ fn build<D>(
                 },
                 wgpu::BindGroupLayoutEntry {
                     binding: 2,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        sample_type: wgpu::TextureSampleType::Float {
-                            filterable: true,
-                        },
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        multisampled: false,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(
+                            mem::size_of::<[[f32; 4]; PALETTE_SIZE]>() as u64,
+                        ),
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(
+                            mem::size_of::<[[f32; 16]; TRANSFORM_SLOTS]>()
+                                as u64,
+                        ),
                     },
                     count: None,
                 },
             ],
         });
 
-    let uniforms = create_uniforms(
-        device,
-        &uniform_layout,
-        &transform,
-        &sampler,
-        &cache.view,
-    );
+    let instance_mode = settings.instance_mode;
 
-    let instances = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("wgpu_glyph::Pipeline instances"),
-        size: mem::size_of::<Instance>() as u64
-            * Instance::INITIAL_AMOUNT as u64,
-        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        mapped_at_creation: false,
-    });
+    let texture_entry = wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    };
+
+    let instances_entry = wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::VERTEX,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+
+    let mut cache_entries = vec![texture_entry];
+
+    if instance_mode == InstanceMode::Storage {
+        cache_entries.push(instances_entry);
+    }
+
+    cache_entries.extend((1..MAX_ATLASES).map(|i| {
+        wgpu::BindGroupLayoutEntry {
+            binding: 1 + i as u32,
+            ..texture_entry
+        }
+    }));
+
+    let cache_layout =
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(&format!("{label}::Pipeline cache")),
+            entries: &cache_entries,
+        });
+
+    let transforms: Vec<(wgpu::Buffer, wgpu::BindGroup)> = transforms
+        .into_iter()
+        .map(|transform| {
+            let uniforms = create_uniforms(
+                device,
+                label,
+                &uniform_layout,
+                &transform,
+                &palette,
+                &transform_slots,
+                &sampler,
+            );
+
+            (transform, uniforms)
+        })
+        .collect();
+
+    let limits = device.limits();
+    let max_chunk_bytes = if instance_mode == InstanceMode::Storage {
+        limits
+            .max_buffer_size
+            .min(u64::from(limits.max_storage_buffer_binding_size))
+    } else {
+        limits.max_buffer_size
+    };
+    let max_chunk_instances =
+        (max_chunk_bytes / instance_mode.instance_size()) as usize;
+
+    let atlases: [Option<wgpu::TextureView>; MAX_ATLASES - 1] =
+        Default::default();
+
+    let frames: Vec<Vec<Chunk>> = (0..frames_in_flight)
+        .map(|_| {
+            let capacity = Instance::INITIAL_AMOUNT.min(max_chunk_instances);
+            let buffer =
+                create_instances(device, label, instance_mode, capacity);
+
+            let bind_group = create_cache_bind_group(
+                device,
+                label,
+                &cache_layout,
+                &cache.view,
+                &atlases,
+                instance_mode,
+                &buffer,
+            );
+
+            vec![Chunk {
+                buffer,
+                bind_group,
+                capacity,
+                count: 0,
+            }]
+        })
+        .collect();
 
     let layout =
         device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: None,
+            label: Some(&format!("{label}::Pipeline layout")),
             push_constant_ranges: &[],
-            bind_group_layouts: &[&uniform_layout],
+            bind_group_layouts: &[&uniform_layout, &cache_layout]
+                .into_iter()
+                .chain(&settings.bind_group_layouts)
+                .collect::<Vec<_>>(),
         });
 
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Glyph Shader"),
-        source: wgpu::ShaderSource::Wgsl(crate::Cow::Borrowed(include_str!(
-            "shader/glyph.wgsl"
-        ))),
+    let shader = create_shader(
+        device,
+        label,
+        settings.shader.clone().unwrap_or(crate::Cow::Borrowed(
+            match instance_mode {
+                InstanceMode::Vertex => SHADER,
+                InstanceMode::Storage => STORAGE_SHADER,
+                InstanceMode::Compact => COMPACT_SHADER,
+            },
+        )),
+    );
+
+    let state = RenderState {
+        multisample: settings.multisample,
+        write_mask: settings.write_mask,
+        primitive: settings.primitive,
+        depth_stencil,
+        constants: settings.constants.clone(),
+    };
+
+    // Without a format, every render pipeline is created on its first draw
+    let raw = render_format
+        .map(|format| {
+            let target = Target {
+                format,
+                sample_count: state.multisample.count,
+                depth_format: state
+                    .depth_stencil
+                    .as_ref()
+                    .map(|depth| depth.format),
+            };
+
+            let raw = create_render_pipeline(
+                device,
+                label,
+                &layout,
+                &shader,
+                instance_mode,
+                &state,
+                target,
+            );
+
+            (target, raw)
+        })
+        .into_iter()
+        .collect();
+
+    Pipeline {
+        label: label.to_string(),
+        transforms,
+        transform_frame: 0,
+        palette,
+        palette_colors,
+        palette_changed: false,
+        transform_slots,
+        transform_slot_matrices,
+        transform_slots_changed: false,
+        cache,
+        uniform_layout,
+        cache_layout,
+        atlases,
+        shared_cache: None,
+        bind_groups: Vec::new(),
+        layout,
+        shader,
+        state,
+        raw,
+        instance_mode,
+        frames,
+        frame: 0,
+        oversized: Vec::new(),
+        max_chunk_instances,
+        compact_instances: Vec::new(),
+        current_instances: 0,
+        current_transform: Transform::zeroed(),
+        clear: None,
+        resolve: None,
+        store: wgpu::StoreOp::Store,
+        discard_depth: false,
+        auto_scissor: settings.auto_scissor,
+        bounds: Bounds::Empty,
+        oversized_bounds: Bounds::Empty,
+        debug: None,
+        depth: PhantomData,
+    }
+}
+
+fn create_shader(
+    device: &wgpu::Device,
+    label: &str,
+    source: crate::Cow<'static, str>,
+) -> Shader {
+    // Overrides are declared at the start of a line, after their attributes
+    let overrides = source
+        .lines()
+        .filter_map(|line| {
+            let (attributes, declaration) = line.split_once("override ")?;
+            let attributes = attributes.trim();
+
+            if !(attributes.is_empty() || attributes.starts_with('@')) {
+                return None;
+            }
+
+            Some(
+                declaration
+                    .trim_start()
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || *c == '_')
+                    .collect(),
+            )
+        })
+        .collect();
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(&format!("{label}::Pipeline shader")),
+        source: wgpu::ShaderSource::Wgsl(source),
     });
 
-    let raw = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: None,
+    Shader { module, overrides }
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    label: &str,
+    layout: &wgpu::PipelineLayout,
+    shader: &Shader,
+    instance_mode: InstanceMode,
+    state: &RenderState,
+    target: Target,
+) -> wgpu::RenderPipeline {
+    let vertex_buffers = [Instance::layout(), CompactInstance::layout()];
+
+    // Alpha-to-coverage needs multiple samples, so single-sampled targets
+    // keep blending
+    let alpha_to_coverage =
+        state.multisample.alpha_to_coverage_enabled && target.sample_count > 1;
+
+    let constants = shader.constants(&state.constants);
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(&format!("{label}::Pipeline {:?}", target.format)),
         cache: None,
-        layout: Some(&layout),
+        layout: Some(layout),
         vertex: wgpu::VertexState {
-            module: &shader,
+            module: &shader.module,
             entry_point: Some("vs_main"),
-            buffers: &[wgpu::VertexBufferLayout {
-                array_stride: mem::size_of::<Instance>() as u64,
-                step_mode: wgpu::VertexStepMode::Instance,
-                attributes: &wgpu::vertex_attr_array![
-                    0 => Float32x3,
-                    1 => Float32x2,
-                    2 => Float32x2,
-                    3 => Float32x2,
-                    4 => Float32x4,
-                ],
-            }],
-            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            buffers: match instance_mode {
+                InstanceMode::Vertex => &vertex_buffers[..1],
+                InstanceMode::Storage => &[],
+                InstanceMode::Compact => &vertex_buffers[1..],
+            },
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &constants,
+                ..Default::default()
+            },
         },
-        primitive: wgpu::PrimitiveState {
-            topology: wgpu::PrimitiveTopology::TriangleStrip,
-            front_face: wgpu::FrontFace::Cw,
-            strip_index_format: Some(wgpu::IndexFormat::Uint16),
-            ..Default::default()
+        primitive: state.primitive,
+        depth_stencil: state.depth_stencil.as_ref().map(|depth_stencil| {
+            wgpu::DepthStencilState {
+                format: target.depth_format.unwrap_or(depth_stencil.format),
+                ..depth_stencil.clone()
+            }
+        }),
+        multisample: wgpu::MultisampleState {
+            count: target.sample_count,
+            alpha_to_coverage_enabled: alpha_to_coverage,
+            ..state.multisample
         },
-        depth_stencil,
-        multisample,
         fragment: Some(wgpu::FragmentState {
-            module: &shader,
-            entry_point: Some("fs_main"),
+            module: &shader.module,
+            entry_point: Some(if target.format.components() == 1 {
+                "fs_mask"
+            } else {
+                "fs_main"
+            }),
             targets: &[Some(wgpu::ColorTargetState {
-                format: render_format,
-                blend: Some(wgpu::BlendState {
+                format: target.format,
+                // The alpha of the text already masks its samples
+                blend: (!alpha_to_coverage).then_some(wgpu::BlendState {
                     color: wgpu::BlendComponent {
                         src_factor: wgpu::BlendFactor::SrcAlpha,
                         dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
@@ -336,26 +1503,15 @@ fn build<D>(
                         operation: wgpu::BlendOperation::Add,
                     },
                 }),
-                write_mask: wgpu::ColorWrites::ALL,
+                write_mask: state.write_mask,
             })],
-            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &constants,
+                ..Default::default()
+            },
         }),
         multiview: None,
-    });
-
-    Pipeline {
-        transform,
-        sampler,
-        cache,
-        uniform_layout,
-        uniforms,
-        raw,
-        instances,
-        current_instances: 0,
-        supported_instances: Instance::INITIAL_AMOUNT,
-        current_transform: [0.0; 16],
-        depth: PhantomData,
-    }
+    })
 }
 
 fn draw<D>(
@@ -363,34 +1519,164 @@ fn draw<D>(
     device: &wgpu::Device,
     staging_belt: &mut wgpu::util::StagingBelt,
     encoder: &mut wgpu::CommandEncoder,
-    target: &wgpu::TextureView,
+    target: DrawTarget<'_>,
     depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment>,
-    transform: [f32; 16],
-    region: Option<Region>,
 ) {
+    let DrawTarget {
+        view: target,
+        transform,
+        region,
+        viewport,
+    } = target;
+
     if transform != pipeline.current_transform {
+        // Written to the buffer of the next frame in flight, like instances
+        pipeline.transform_frame =
+            (pipeline.transform_frame + 1) % pipeline.transforms.len();
+
         let mut transform_view = staging_belt.write_buffer(
             encoder,
-            &pipeline.transform,
+            &pipeline.transforms[pipeline.transform_frame].0,
             0,
-            unsafe { NonZeroU64::new_unchecked(16 * 4) },
+            unsafe {
+                NonZeroU64::new_unchecked(mem::size_of::<Transform>() as u64)
+            },
             device,
         );
 
-        transform_view.copy_from_slice(bytemuck::cast_slice(&transform));
+        transform_view.copy_from_slice(bytemuck::bytes_of(&transform));
 
         pipeline.current_transform = transform;
     }
 
+    if pipeline.palette_changed {
+        let mut palette_view = staging_belt.write_buffer(
+            encoder,
+            &pipeline.palette,
+            0,
+            unsafe {
+                NonZeroU64::new_unchecked(
+                    mem::size_of::<[[f32; 4]; PALETTE_SIZE]>() as u64,
+                )
+            },
+            device,
+        );
+
+        palette_view
+            .copy_from_slice(bytemuck::cast_slice(&pipeline.palette_colors));
+
+        pipeline.palette_changed = false;
+    }
+
+    if pipeline.transform_slots_changed {
+        let mut transform_slots_view = staging_belt.write_buffer(
+            encoder,
+            &pipeline.transform_slots,
+            0,
+            unsafe {
+                NonZeroU64::new_unchecked(mem::size_of::<
+                    [[f32; 16]; TRANSFORM_SLOTS],
+                >() as u64)
+            },
+            device,
+        );
+
+        transform_slots_view.copy_from_slice(bytemuck::cast_slice(
+            &pipeline.transform_slot_matrices,
+        ));
+
+        pipeline.transform_slots_changed = false;
+    }
+
+    let raw = pipeline.render_pipeline(
+        device,
+        Target {
+            format: target.texture().format(),
+            sample_count: target.texture().sample_count(),
+            depth_format: depth_stencil_attachment
+                .as_ref()
+                .map(|attachment| attachment.view.texture().format()),
+        },
+    );
+
+    let size = target.texture().size();
+
+    let flip_y = pipeline
+        .shader
+        .constants(&pipeline.state.constants)
+        .into_iter()
+        .any(|(name, value)| name == "flip_y" && value != 0.0);
+
+    // The region is flipped with the text, across the viewport
+    let mut region = region.map(|region| {
+        let region = if flip_y {
+            match viewport {
+                Some(viewport) => region.flip_y(viewport.y, viewport.height),
+                None => region.flip_y(0.0, size.height as f32),
+            }
+        } else {
+            region
+        };
+
+        region.clamp(size.width, size.height)
+    });
+
+    if pipeline.auto_scissor {
+        let covered = pipeline.bounds.union(pipeline.oversized_bounds).region(
+            &transform,
+            viewport,
+            (size.width, size.height),
+            flip_y,
+        );
+
+        if let Some(covered) = covered {
+            region = Some(
+                region.map_or(covered, |region| region.intersect(covered)),
+            );
+        }
+    }
+
+    // Scissoring to an empty region is fine, but empty viewports are invalid
+    let visible = !region.is_some_and(|region| region.is_empty())
+        && !viewport.is_some_and(|viewport| viewport.is_empty());
+
+    // The pass still clears or resolves the target if it was requested
+    if !visible && pipeline.clear.is_none() && pipeline.resolve.is_none() {
+        return;
+    }
+
+    let resolve_target = pipeline.resolve.take();
+
+    let depth_stencil_attachment =
+        depth_stencil_attachment.map(|mut attachment| {
+            if mem::take(&mut pipeline.discard_depth) {
+                if let Some(ops) = &mut attachment.depth_ops {
+                    ops.store = wgpu::StoreOp::Discard;
+                }
+
+                if let Some(ops) = &mut attachment.stencil_ops {
+                    ops.store = wgpu::StoreOp::Discard;
+                }
+            }
+
+            attachment
+        });
+
     let mut render_pass =
         encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("wgpu_glyph::pipeline render pass"),
+            label: Some(&format!("{}::Pipeline render pass", pipeline.label)),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: target,
-                resolve_target: None,
+                resolve_target: resolve_target.as_ref(),
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
-                    store: wgpu::StoreOp::Store,
+                    load: match pipeline.clear.take() {
+                        Some(color) => wgpu::LoadOp::Clear(color),
+                        None => wgpu::LoadOp::Load,
+                    },
+                    store: mem::replace(
+                        &mut pipeline.store,
+                        wgpu::StoreOp::Store,
+                    ),
                 },
                 depth_slice: None,
             })],
@@ -399,9 +1685,28 @@ fn draw<D>(
             occlusion_query_set: None,
         });
 
-    render_pass.set_pipeline(&pipeline.raw);
-    render_pass.set_bind_group(0, &pipeline.uniforms, &[]);
-    render_pass.set_vertex_buffer(0, pipeline.instances.slice(..));
+    if !visible {
+        return;
+    }
+
+    render_pass.set_pipeline(&pipeline.raw[raw].1);
+    render_pass.set_bind_group(0, pipeline.uniforms(), &[]);
+
+    for (i, bind_group) in pipeline.bind_groups.iter().enumerate() {
+        if let Some(bind_group) = bind_group {
+            render_pass.set_bind_group(2 + i as u32, bind_group, &[]);
+        }
+    }
+    if let Some(viewport) = viewport {
+        render_pass.set_viewport(
+            viewport.x,
+            viewport.y,
+            viewport.width,
+            viewport.height,
+            viewport.min_depth,
+            viewport.max_depth,
+        );
+    }
 
     if let Some(region) = region {
         render_pass.set_scissor_rect(
@@ -412,19 +1717,36 @@ fn draw<D>(
         );
     }
 
-    render_pass.draw(0..4, 0..pipeline.current_instances as u32);
+    let oversized = pipeline.oversized.iter().map(|oversized| &oversized.chunk);
+
+    for chunk in pipeline
+        .chunks()
+        .iter()
+        .chain(oversized)
+        .filter(|chunk| chunk.count > 0)
+    {
+        render_pass.set_bind_group(1, &chunk.bind_group, &[]);
+
+        if pipeline.instance_mode != InstanceMode::Storage {
+            render_pass.set_vertex_buffer(0, chunk.buffer.slice(..));
+        }
+
+        render_pass.draw(0..4, 0..chunk.count as u32);
+    }
 }
 
 fn create_uniforms(
     device: &wgpu::Device,
+    label: &str,
     layout: &wgpu::BindGroupLayout,
     transform: &wgpu::Buffer,
+    palette: &wgpu::Buffer,
+    transform_slots: &wgpu::Buffer,
     sampler: &wgpu::Sampler,
-    cache: &wgpu::TextureView,
 ) -> wgpu::BindGroup {
     device.create_bind_group(&wgpu::BindGroupDescriptor {
-        label: Some("wgpu_glyph::Pipeline uniforms"),
-        layout: layout,
+        label: Some(&format!("{label}::Pipeline uniforms")),
+        layout,
         entries: &[
             wgpu::BindGroupEntry {
                 binding: 0,
@@ -440,12 +1762,103 @@ fn create_uniforms(
             },
             wgpu::BindGroupEntry {
                 binding: 2,
-                resource: wgpu::BindingResource::TextureView(cache),
+                resource: palette.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: transform_slots.as_entire_binding(),
             },
         ],
     })
 }
 
+fn create_instances(
+    device: &wgpu::Device,
+    label: &str,
+    instance_mode: InstanceMode,
+    capacity: usize,
+) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(&format!("{label}::Pipeline instances")),
+        size: instance_mode.instance_size() * capacity as u64,
+        usage: instance_mode.buffer_usage(),
+        mapped_at_creation: false,
+    })
+}
+
+fn create_cache_bind_group(
+    device: &wgpu::Device,
+    label: &str,
+    layout: &wgpu::BindGroupLayout,
+    cache_view: &wgpu::TextureView,
+    atlases: &[Option<wgpu::TextureView>],
+    instance_mode: InstanceMode,
+    instances: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    let mut entries = vec![wgpu::BindGroupEntry {
+        binding: 0,
+        resource: wgpu::BindingResource::TextureView(cache_view),
+    }];
+
+    if instance_mode == InstanceMode::Storage {
+        entries.push(wgpu::BindGroupEntry {
+            binding: 1,
+            resource: instances.as_entire_binding(),
+        });
+    }
+
+    entries.extend(atlases.iter().enumerate().map(|(i, atlas)| {
+        wgpu::BindGroupEntry {
+            binding: 2 + i as u32,
+            resource: wgpu::BindingResource::TextureView(
+                atlas.as_ref().unwrap_or(cache_view),
+            ),
+        }
+    }));
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(&format!("{label}::Pipeline cache")),
+        layout,
+        entries: &entries,
+    })
+}
+
+/// A glyph quad, as stored in the instance buffer of a
+/// [`GlyphBrush`](struct.GlyphBrush.html).
+///
+/// Every instance is drawn as a triangle strip of 4 vertices, indexed by
+/// `vertex_index` in the order: left-top, right-top, left-bottom and
+/// right-bottom. Its attributes are laid out as follows:
+///
+/// | Location | Format      | Description                                  |
+/// |----------|-------------|----------------------------------------------|
+/// | 0        | `Float32x3` | Left-top corner in pixels, and depth (`z`)   |
+/// | 1        | `Float32x2` | Right-bottom corner in pixels                |
+/// | 2        | `Float32x2` | Left-top texture coordinates in the cache    |
+/// | 3        | `Float32x2` | Right-bottom texture coordinates in the cache|
+/// | 4        | `Float32x4` | Linear RGBA color                            |
+/// | 5        | `Uint32`    | Atlas index and palette bits                 |
+///
+/// Positions are multiplied by the transform of the draw call in the vertex
+/// shader, or by the one of the transform slot stored in the bits 28 to 30,
+/// see [`GlyphStyle::transform_slot`](struct.GlyphStyle.html#structfield.transform_slot).
+/// The atlas `0` is the glyph cache, a single-channel `R8Unorm` texture
+/// holding the coverage of the glyphs, which is multiplied by the
+/// alpha of the color. The other atlases, set with
+/// [`GlyphBrush::set_atlas`](struct.GlyphBrush.html#method.set_atlas), are
+/// sampled as RGBA and multiplied by the color.
+///
+/// The atlas `255` is not sampled, drawing the color as is. See
+/// [`Instance::solid`]. The atlas `254` draws the color as a rounded
+/// rectangle spanning the texture coordinates from `0` to `1`, with the
+/// corner radius in pixels stored in the bits 17 to 27. See
+/// [`Instance::rounded`].
+///
+/// The atlas index is stored in the lowest 8 bits. When the highest bit is
+/// set, the color is multiplied by the palette color indexed by the bits 8
+/// to 15. When the bit 16 is set, the glyph cache is sampled with nearest
+/// filtering. See [`GlyphStyle`](struct.GlyphStyle.html) and
+/// [`Instance::with_style`].
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Zeroable, Pod)]
 pub struct Instance {
@@ -454,27 +1867,194 @@ pub struct Instance {
     tex_left_top: [f32; 2],
     tex_right_bottom: [f32; 2],
     color: [f32; 4],
+    atlas: u32,
 }
 
 impl Instance {
     const INITIAL_AMOUNT: usize = 50_000;
 
-    pub fn from_vertex(
+    /// The vertex attributes of an [`Instance`].
+    pub const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+        0 => Float32x3,
+        1 => Float32x2,
+        2 => Float32x2,
+        3 => Float32x2,
+        4 => Float32x4,
+        5 => Uint32,
+    ];
+
+    /// Creates a new [`Instance`] drawing the `tex_rect` region of the given
+    /// atlas, in normalized texture coordinates, into the `rect` region in
+    /// pixels.
+    pub fn new(
+        rect: Rect,
+        tex_rect: Rect,
+        color: [f32; 4],
+        z: f32,
+        atlas: u32,
+    ) -> Instance {
+        Instance {
+            left_top: [rect.min.x, rect.max.y, z],
+            right_bottom: [rect.max.x, rect.min.y],
+            tex_left_top: [tex_rect.min.x, tex_rect.max.y],
+            tex_right_bottom: [tex_rect.max.x, tex_rect.min.y],
+            color,
+            atlas,
+        }
+    }
+
+    /// Creates a new [`Instance`] filling the `rect` region in pixels with a
+    /// solid color, e.g. for backgrounds, selections or cursors.
+    pub fn solid(rect: Rect, color: [f32; 4], z: f32) -> Instance {
+        Instance::new(
+            rect,
+            Rect {
+                min: point(0.0, 0.0),
+                max: point(0.0, 0.0),
+            },
+            color,
+            z,
+            SOLID_ATLAS,
+        )
+    }
+
+    /// Creates a new [`Instance`] filling the `rect` region in pixels with a
+    /// solid color and rounded corners of the given radius.
+    pub fn rounded(
+        rect: Rect,
+        color: [f32; 4],
+        z: f32,
+        corner_radius: f32,
+    ) -> Instance {
+        let radius = (corner_radius.round().max(0.0) as u32).min(0x7ff);
+
+        Instance::new(
+            rect,
+            Rect {
+                min: point(0.0, 0.0),
+                max: point(1.0, 1.0),
+            },
+            color,
+            z,
+            ROUNDED_ATLAS | (radius << 17),
+        )
+    }
+
+    /// Returns the vertex buffer layout of the instance buffer.
+    pub const fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Instance>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+
+    /// Returns the region of the instance, in pixels.
+    pub fn rect(&self) -> Rect {
+        Rect {
+            min: point(self.left_top[0], self.right_bottom[1]),
+            max: point(self.right_bottom[0], self.left_top[1]),
+        }
+    }
+
+    /// Returns the normalized texture coordinates of the instance.
+    pub fn tex_rect(&self) -> Rect {
+        Rect {
+            min: point(self.tex_left_top[0], self.tex_right_bottom[1]),
+            max: point(self.tex_right_bottom[0], self.tex_left_top[1]),
+        }
+    }
+
+    /// Returns the color of the instance, before it is multiplied by its
+    /// palette color, if any.
+    pub fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    /// Returns the depth of the instance.
+    pub fn z(&self) -> f32 {
+        self.left_top[2]
+    }
+
+    /// Returns the atlas of the instance, with its palette and other bits.
+    pub fn atlas(&self) -> u32 {
+        self.atlas
+    }
+
+    /// Returns the position of the center of the instance.
+    pub(crate) fn center(&self) -> [f32; 3] {
+        [
+            (self.left_top[0] + self.right_bottom[0]) / 2.0,
+            (self.left_top[1] + self.right_bottom[1]) / 2.0,
+            self.left_top[2],
+        ]
+    }
+
+    /// Returns the bits of the attributes of the instance that affect the
+    /// pixels it covers, except for its texture coordinates: its corners,
+    /// color, depth and atlas.
+    pub(crate) fn appearance(&self) -> [u32; 10] {
+        let [left, top, z] = self.left_top.map(f32::to_bits);
+        let [right, bottom] = self.right_bottom.map(f32::to_bits);
+        let [r, g, b, a] = self.color.map(f32::to_bits);
+
+        [left, top, right, bottom, r, g, b, a, z, self.atlas]
+    }
+
+    /// Returns the transform slot of the instance, where `0` is the transform
+    /// of the draw call.
+    pub fn transform_slot(&self) -> u8 {
+        ((self.atlas >> SLOT_SHIFT) & 0x7) as u8
+    }
+
+    /// Returns the instance with the atlas bits of the given style added, e.g.
+    /// to sample the glyph cache with nearest filtering for a custom instance.
+    pub fn with_style(mut self, style: GlyphStyle) -> Instance {
+        self.atlas |= style.atlas_bits();
+        self
+    }
+
+    /// Returns the instance sampling the atlas with the given index instead,
+    /// keeping the rest of its atlas bits.
+    #[cfg(feature = "svg")]
+    pub(crate) fn with_atlas(mut self, atlas: u32) -> Instance {
+        self.atlas = self.atlas & !0xff | atlas;
+        self
+    }
+
+    /// Returns the instance sampling the given normalized texture coordinates
+    /// instead.
+    pub(crate) fn with_tex_rect(mut self, tex_rect: Rect) -> Instance {
+        self.tex_left_top = [tex_rect.min.x, tex_rect.max.y];
+        self.tex_right_bottom = [tex_rect.max.x, tex_rect.min.y];
+        self
+    }
+
+    /// Divides the pixel coordinates of the instance by the given factors.
+    pub(crate) fn unscale(&mut self, (x, y): (f32, f32)) {
+        self.left_top[0] /= x;
+        self.left_top[1] /= y;
+        self.right_bottom[0] /= x;
+        self.right_bottom[1] /= y;
+    }
+
+    pub(crate) fn from_vertex(
         glyph_brush::GlyphVertex {
             mut tex_coords,
             pixel_coords,
             bounds,
             extra,
-        }: glyph_brush::GlyphVertex,
+        }: glyph_brush::GlyphVertex<'_, Styled>,
     ) -> Instance {
         let gl_bounds = bounds;
 
         let mut gl_rect = Rect {
-            min: point(pixel_coords.min.x as f32, pixel_coords.min.y as f32),
-            max: point(pixel_coords.max.x as f32, pixel_coords.max.y as f32),
+            min: point(pixel_coords.min.x, pixel_coords.min.y),
+            max: point(pixel_coords.max.x, pixel_coords.max.y),
         };
 
-        // handle overlapping bounds, modify uv_rect to preserve texture aspect
+        // clip to the bounds, modifying uv_rect to preserve texture aspect.
+        // Sections with `Overflow::Visible` have infinite bounds.
         if gl_rect.max.x > gl_bounds.max.x {
             let old_width = gl_rect.width();
             gl_rect.max.x = gl_bounds.max.x;
@@ -504,11 +2084,150 @@ impl Instance {
         }
 
         Instance {
-            left_top: [gl_rect.min.x, gl_rect.max.y, extra.z],
+            left_top: [gl_rect.min.x, gl_rect.max.y, extra.extra.z],
             right_bottom: [gl_rect.max.x, gl_rect.min.y],
             tex_left_top: [tex_coords.min.x, tex_coords.max.y],
             tex_right_bottom: [tex_coords.max.x, tex_coords.min.y],
-            color: extra.color,
+            color: extra.extra.color,
+            atlas: 0,
+        }
+        .with_style(extra.style)
+    }
+}
+
+/// A glyph quad encoded compactly, as stored in the instance buffer of a
+/// [`GlyphBrush`](struct.GlyphBrush.html) when using
+/// [`InstanceMode::Compact`].
+///
+/// Its attributes are laid out as follows:
+///
+/// | Location | Format      | Description                                   |
+/// |----------|-------------|-----------------------------------------------|
+/// | 0        | `Sint16x4`  | Left, top, right and bottom in quarter pixels |
+/// | 1        | `Float32`   | Depth (`z`)                                   |
+/// | 2        | `Unorm16x4` | Left-top and right-bottom texture coordinates |
+/// | 3        | `Unorm8x4`  | Linear RGBA color                             |
+/// | 4        | `Uint32`    | Atlas index and palette bits                  |
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Zeroable, Pod)]
+pub struct CompactInstance {
+    rect: [i16; 4],
+    z: f32,
+    tex_rect: [u16; 4],
+    color: [u8; 4],
+    atlas: u32,
+}
+
+impl CompactInstance {
+    const SUBPIXELS: f32 = 4.0;
+
+    /// The vertex attributes of a [`CompactInstance`].
+    pub const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        0 => Sint16x4,
+        1 => Float32,
+        2 => Unorm16x4,
+        3 => Unorm8x4,
+        4 => Uint32,
+    ];
+
+    /// Returns the vertex buffer layout of the compact instance buffer.
+    pub const fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<CompactInstance>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+impl From<&Instance> for CompactInstance {
+    fn from(instance: &Instance) -> CompactInstance {
+        fn position(value: f32) -> i16 {
+            (value * CompactInstance::SUBPIXELS)
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        }
+
+        fn unorm16(value: f32) -> u16 {
+            (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+        }
+
+        fn unorm8(value: f32) -> u8 {
+            (value.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8
+        }
+
+        CompactInstance {
+            rect: [
+                position(instance.left_top[0]),
+                position(instance.left_top[1]),
+                position(instance.right_bottom[0]),
+                position(instance.right_bottom[1]),
+            ],
+            z: instance.left_top[2],
+            tex_rect: [
+                unorm16(instance.tex_left_top[0]),
+                unorm16(instance.tex_left_top[1]),
+                unorm16(instance.tex_right_bottom[0]),
+                unorm16(instance.tex_right_bottom[1]),
+            ],
+            color: instance.color.map(unorm8),
+            atlas: instance.atlas,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::PALETTE_FLAG;
+    use crate::style::NEAREST_FLAG;
+
+    fn rect() -> Rect {
+        Rect {
+            min: point(0.0, 0.0),
+            max: point(10.0, 10.0),
         }
     }
+
+    #[test]
+    fn style_bits_keep_the_atlas_index() {
+        let style = GlyphStyle::default()
+            .with_nearest_filtered(true)
+            .with_palette(0xab)
+            .with_transform_slot(7);
+
+        let instance =
+            Instance::new(rect(), rect(), [1.0; 4], 0.0, 2).with_style(style);
+
+        assert_eq!(instance.atlas() & 0xff, 2);
+        assert_eq!((instance.atlas() >> 8) & 0xff, 0xab);
+        assert_ne!(instance.atlas() & NEAREST_FLAG, 0);
+        assert_ne!(instance.atlas() & PALETTE_FLAG, 0);
+        assert_eq!(instance.transform_slot(), 7);
+    }
+
+    #[test]
+    fn default_style_has_no_bits() {
+        assert_eq!(GlyphStyle::default().atlas_bits(), 0);
+    }
+
+    #[test]
+    fn rounded_radius_does_not_overlap_the_style_bits() {
+        let style = GlyphStyle::default()
+            .with_palette(0xff)
+            .with_transform_slot(3);
+
+        let instance =
+            Instance::rounded(rect(), [1.0; 4], 0.0, 5_000.0).with_style(style);
+
+        assert_eq!(instance.atlas() & 0xff, ROUNDED_ATLAS);
+        assert_eq!((instance.atlas() >> 8) & 0xff, 0xff);
+        assert_eq!(instance.atlas() & NEAREST_FLAG, 0);
+        assert_eq!((instance.atlas() >> 17) & 0x7ff, 0x7ff);
+        assert_eq!(instance.transform_slot(), 3);
+
+        let instance = Instance::rounded(rect(), [1.0; 4], 0.0, 4.4);
+
+        assert_eq!(instance.atlas(), ROUNDED_ATLAS | (4 << 17));
+    }
 }
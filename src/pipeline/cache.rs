@@ -1,26 +1,74 @@
 use core::num::NonZeroU64;
 
 pub struct Cache {
+    label: String,
     texture: wgpu::Texture,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    texel_size: usize,
     pub(super) view: wgpu::TextureView,
     upload_buffer: wgpu::Buffer,
     upload_buffer_size: u64,
+    pending: Vec<Update>,
+    pending_data: Vec<u8>,
+}
+
+/// A queued update of a region of the cache, stored in the pending data at
+/// the given offset with padded rows.
+struct Update {
+    offset: [u16; 2],
+    size: [u16; 2],
+    data_offset: usize,
+    padded_width: usize,
 }
 
 impl Cache {
     const INITIAL_UPLOAD_BUFFER_SIZE: u64 =
         wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64 * 100;
 
-    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Cache {
+    // D3D12 requires buffer offsets of texture copies to be multiples of
+    // 512 bytes, which also satisfies every other backend.
+    const UPLOAD_OFFSET_ALIGNMENT: usize = 512;
+
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        width: u32,
+        height: u32,
+    ) -> Cache {
+        Cache::with_format(
+            device,
+            label,
+            width,
+            height,
+            wgpu::TextureFormat::R8Unorm,
+        )
+    }
+
+    /// Creates a cache whose texels have the given format, which must have a
+    /// fixed size and not be block-compressed, e.g. for color glyphs.
+    pub fn with_format(
+        device: &wgpu::Device,
+        label: &str,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Cache {
+        let texel_size = format
+            .block_copy_size(None)
+            .expect("Cache format with a fixed texel size")
+            as usize;
+
         let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("wgpu_glyph::Cache"),
+            label: Some(&format!("{label}::Cache")),
             size: wgpu::Extent3d {
                 width,
                 height,
                 depth_or_array_layers: 1,
             },
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::R8Unorm,
+            format,
             usage: wgpu::TextureUsages::COPY_DST
                 | wgpu::TextureUsages::TEXTURE_BINDING,
             mip_level_count: 1,
@@ -31,93 +79,261 @@ impl Cache {
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         let upload_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("wgpu_glyph::Cache upload buffer"),
+            label: Some(&format!("{label}::Cache upload buffer")),
             size: Self::INITIAL_UPLOAD_BUFFER_SIZE,
             usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
         Cache {
+            label: label.to_owned(),
             texture,
+            width,
+            height,
+            format,
+            texel_size,
             view,
             upload_buffer,
             upload_buffer_size: Self::INITIAL_UPLOAD_BUFFER_SIZE,
+            pending: Vec::new(),
+            pending_data: Vec::new(),
         }
     }
 
-    pub fn update(
-        &mut self,
-        device: &wgpu::Device,
-        staging_belt: &mut wgpu::util::StagingBelt,
-        encoder: &mut wgpu::CommandEncoder,
-        offset: [u16; 2],
-        size: [u16; 2],
-        data: &[u8],
-    ) {
-        let width = size[0] as usize;
-        let height = size[1] as usize;
+    /// Returns the amount of bytes of GPU memory used by the texture and the
+    /// upload buffer of the cache.
+    pub fn gpu_bytes(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height) * self.texel_size as u64
+            + self.upload_buffer_size
+    }
+
+    /// Returns the amount of bytes of CPU memory used by the pending updates
+    /// of the cache.
+    pub fn cpu_bytes(&self) -> u64 {
+        (self.pending_data.capacity()
+            + self.pending.capacity() * std::mem::size_of::<Update>())
+            as u64
+    }
+
+    /// Resizes the cache to the given dimensions, dropping its contents.
+    ///
+    /// The contents are not copied over: `glyph_brush` clears its packing
+    /// when its cache is resized, then rasterizes and uploads every glyph
+    /// again, so the old contents are never drawn.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        *self =
+            Cache::with_format(device, &self.label, width, height, self.format);
+    }
+
+    /// Queues an update of a region of the cache.
+    ///
+    /// Updates are batched and uploaded together on the next
+    /// [`flush`](Cache::flush), with their rows and offsets padded to the
+    /// copy alignments of every backend, so regions of any size are valid.
+    pub fn update(&mut self, offset: [u16; 2], size: [u16; 2], data: &[u8]) {
+        let x = (offset[0] as u32).min(self.width);
+        let y = (offset[1] as u32).min(self.height);
+        let width = (size[0] as usize).min((self.width - x) as usize);
+        let height = (size[1] as usize).min((self.height - y) as usize);
+
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let texel_size = self.texel_size;
 
         // It is a webgpu requirement that:
         //  BufferCopyView.layout.bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0
-        // So we calculate padded_width by rounding width
+        // So we calculate padded_width by rounding the width in bytes
         // up to the next multiple of wgpu::COPY_BYTES_PER_ROW_ALIGNMENT.
-        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
-        let padded_width_padding = (align - width % align) % align;
-        let padded_width = width + padded_width_padding;
+        let padded_width = align_to(
+            width * texel_size,
+            wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize,
+        );
+
+        let data_offset =
+            align_to(self.pending_data.len(), Self::UPLOAD_OFFSET_ALIGNMENT);
+
+        self.pending_data
+            .resize(data_offset + padded_width * height, 0);
+
+        for row in 0..height {
+            let source = row * size[0] as usize * texel_size;
+            let target = data_offset + row * padded_width;
+            let bytes = width * texel_size;
+
+            self.pending_data[target..target + bytes]
+                .copy_from_slice(&data[source..source + bytes]);
+        }
 
-        let padded_data_size = (padded_width * height) as u64;
+        self.pending.push(Update {
+            offset: [x as u16, y as u16],
+            size: [width as u16, height as u16],
+            data_offset,
+            padded_width,
+        });
+    }
+
+    /// Uploads all the queued updates using a single staging write.
+    pub fn flush(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let Some(size) = NonZeroU64::new(self.pending_data.len() as u64) else {
+            self.pending.clear();
+            return;
+        };
 
-        if self.upload_buffer_size < padded_data_size {
+        if self.upload_buffer_size < size.get() {
             self.upload_buffer =
                 device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("wgpu_glyph::Cache upload buffer"),
-                    size: padded_data_size,
+                    label: Some(&format!(
+                        "{}::Cache upload buffer",
+                        self.label
+                    )),
+                    size: size.get(),
                     usage: wgpu::BufferUsages::COPY_DST
                         | wgpu::BufferUsages::COPY_SRC,
                     mapped_at_creation: false,
                 });
 
-            self.upload_buffer_size = padded_data_size;
+            self.upload_buffer_size = size.get();
         }
 
-        let mut padded_data = staging_belt.write_buffer(
-            encoder,
-            &self.upload_buffer,
-            0,
-            NonZeroU64::new(padded_data_size).unwrap(),
-            device,
-        );
-
-        for row in 0..height {
-            padded_data[row * padded_width..row * padded_width + width]
-                .copy_from_slice(&data[row * width..(row + 1) * width])
-        }
+        staging_belt
+            .write_buffer(encoder, &self.upload_buffer, 0, size, device)
+            .copy_from_slice(&self.pending_data);
 
         // TODO: Move to use Queue for less buffer usage
-        encoder.copy_buffer_to_texture(
-            wgpu::TexelCopyBufferInfo {
-                buffer: &self.upload_buffer,
-                layout: wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(padded_width as u32),
-                    rows_per_image: Some(height as u32),
+        for update in self.pending.drain(..) {
+            encoder.copy_buffer_to_texture(
+                wgpu::TexelCopyBufferInfo {
+                    buffer: &self.upload_buffer,
+                    layout: wgpu::TexelCopyBufferLayout {
+                        offset: update.data_offset as u64,
+                        bytes_per_row: Some(update.padded_width as u32),
+                        rows_per_image: Some(u32::from(update.size[1])),
+                    },
                 },
-            },
-            wgpu::TexelCopyTextureInfo {
-                texture: &self.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d {
-                    x: u32::from(offset[0]),
-                    y: u32::from(offset[1]),
-                    z: 0,
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: u32::from(update.offset[0]),
+                        y: u32::from(update.offset[1]),
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
                 },
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::Extent3d {
-                width: size[0] as u32,
-                height: size[1] as u32,
-                depth_or_array_layers: 1,
-            },
+                wgpu::Extent3d {
+                    width: u32::from(update.size[0]),
+                    height: u32::from(update.size[1]),
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        self.pending_data.clear();
+    }
+}
+
+fn align_to(value: usize, alignment: usize) -> usize {
+    value.div_ceil(alignment) * alignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROW: usize = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+
+    /// Returns a device of any adapter, if there is one.
+    fn device() -> Option<wgpu::Device> {
+        let instance = wgpu::Instance::default();
+
+        let adapter = futures::executor::block_on(
+            instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+        )
+        .ok()?;
+
+        let (device, _) = futures::executor::block_on(
+            adapter.request_device(&wgpu::DeviceDescriptor::default()),
+        )
+        .ok()?;
+
+        Some(device)
+    }
+
+    #[test]
+    fn update_pads_rows_and_offsets() {
+        let Some(device) = device() else {
+            return;
+        };
+
+        let mut cache = Cache::new(&device, "Test", 300, 300);
+
+        cache.update([0, 0], [3, 2], &[1, 2, 3, 4, 5, 6]);
+        cache.update([10, 10], [1, 1], &[7]);
+
+        assert_eq!(cache.pending.len(), 2);
+        assert_eq!(cache.pending[0].padded_width, ROW);
+        assert_eq!(
+            cache.pending[1].data_offset,
+            Cache::UPLOAD_OFFSET_ALIGNMENT
+        );
+
+        assert_eq!(cache.pending_data[..4], [1, 2, 3, 0]);
+        assert_eq!(cache.pending_data[ROW..ROW + 4], [4, 5, 6, 0]);
+        assert_eq!(cache.pending_data[Cache::UPLOAD_OFFSET_ALIGNMENT], 7);
+        assert_eq!(
+            cache.pending_data.len(),
+            Cache::UPLOAD_OFFSET_ALIGNMENT + ROW
+        );
+    }
+
+    #[test]
+    fn update_clips_to_the_cache() {
+        let Some(device) = device() else {
+            return;
+        };
+
+        let mut cache = Cache::new(&device, "Test", 300, 300);
+
+        // Rows are read with the stride of the given size
+        cache.update([298, 299], [3, 2], &[1, 2, 3, 4, 5, 6]);
+        cache.update([300, 0], [1, 1], &[7]);
+
+        assert_eq!(cache.pending.len(), 1);
+        assert_eq!(cache.pending[0].offset, [298, 299]);
+        assert_eq!(cache.pending[0].size, [2, 1]);
+        assert_eq!(cache.pending_data, [&[1, 2][..], &[0; ROW - 2]].concat());
+    }
+
+    #[test]
+    fn update_pads_rows_of_wide_texels() {
+        let Some(device) = device() else {
+            return;
+        };
+
+        let mut cache = Cache::with_format(
+            &device,
+            "Test",
+            300,
+            300,
+            wgpu::TextureFormat::Rgba8Unorm,
+        );
+
+        let texels = ROW / 4 + 1;
+        cache.update([0, 0], [texels as u16, 2], &vec![1; texels * 4 * 2]);
+
+        assert_eq!(cache.pending[0].padded_width, 2 * ROW);
+        assert_eq!(cache.pending_data.len(), 4 * ROW);
+        assert_eq!(cache.pending_data[ROW + 4..2 * ROW], [0; ROW - 4]);
+        assert_eq!(
+            cache.pending_data[2 * ROW..2 * ROW + ROW + 4],
+            [1; ROW + 4]
         );
     }
 }
@@ -0,0 +1,313 @@
+use crate::Region;
+
+use core::num::NonZeroU64;
+use std::mem;
+
+/// Draws the contents of the glyph cache and outlines of its occupied
+/// regions, to diagnose cache thrashing and fragmentation.
+pub struct Debug {
+    label: String,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    pipeline_layout: wgpu::PipelineLayout,
+    shader: wgpu::ShaderModule,
+    multisample: wgpu::MultisampleState,
+    /// The atlas and outlines pipelines of every target drawn into, created
+    /// on first use.
+    pipelines: Vec<(Target, Pipelines)>,
+    rects: wgpu::Buffer,
+    supported_rects: usize,
+    /// The amount of rectangles uploaded last.
+    uploaded_rects: usize,
+}
+
+/// The attachment the pipelines of the overlay are compatible with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Target {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+struct Pipelines {
+    atlas: wgpu::RenderPipeline,
+    outlines: wgpu::RenderPipeline,
+}
+
+impl Debug {
+    const INITIAL_RECTS: usize = 1_000;
+
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        multisample: wgpu::MultisampleState,
+    ) -> Debug {
+        let layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some(&format!("{label}::Debug atlas")),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(
+                            wgpu::SamplerBindingType::NonFiltering,
+                        ),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float {
+                                filterable: false,
+                            },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some(&format!("{label}::Debug sampler")),
+            ..Default::default()
+        });
+
+        let pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some(&format!("{label}::Debug layout")),
+                push_constant_ranges: &[],
+                bind_group_layouts: &[&layout],
+            });
+
+        let shader =
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(&format!("{label}::Debug shader")),
+                source: wgpu::ShaderSource::Wgsl(crate::Cow::Borrowed(
+                    include_str!("../shader/debug.wgsl"),
+                )),
+            });
+
+        let rects = create_rects(device, label, Self::INITIAL_RECTS);
+
+        Debug {
+            label: label.to_owned(),
+            layout,
+            sampler,
+            pipeline_layout,
+            shader,
+            multisample,
+            pipelines: Vec::new(),
+            rects,
+            supported_rects: Self::INITIAL_RECTS,
+            uploaded_rects: 0,
+        }
+    }
+
+    /// Returns the amount of bytes of the buffer of the outlines.
+    pub fn buffer_bytes(&self) -> u64 {
+        (mem::size_of::<[f32; 4]>() * self.supported_rects) as u64
+    }
+
+    /// Returns the index of the pipelines compatible with the given target,
+    /// creating them if needed.
+    fn pipelines(&mut self, device: &wgpu::Device, target: Target) -> usize {
+        if let Some(index) = self
+            .pipelines
+            .iter()
+            .position(|(compatible, _)| *compatible == target)
+        {
+            return index;
+        }
+
+        let label = &self.label;
+
+        let targets = [Some(wgpu::ColorTargetState {
+            format: target.format,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+
+        let atlas =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("{label}::Debug atlas")),
+                cache: None,
+                layout: Some(&self.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &self.shader,
+                    entry_point: Some("vs_atlas"),
+                    buffers: &[],
+                    compilation_options:
+                        wgpu::PipelineCompilationOptions::default(),
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: target.sample_count,
+                    ..self.multisample
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.shader,
+                    entry_point: Some("fs_atlas"),
+                    targets: &targets,
+                    compilation_options:
+                        wgpu::PipelineCompilationOptions::default(),
+                }),
+                multiview: None,
+            });
+
+        let outlines =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("{label}::Debug outlines")),
+                cache: None,
+                layout: Some(&self.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &self.shader,
+                    entry_point: Some("vs_outline"),
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: mem::size_of::<[f32; 4]>() as u64,
+                        step_mode: wgpu::VertexStepMode::Instance,
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x4],
+                    }],
+                    compilation_options:
+                        wgpu::PipelineCompilationOptions::default(),
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::LineStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: target.sample_count,
+                    ..self.multisample
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.shader,
+                    entry_point: Some("fs_outline"),
+                    targets: &targets,
+                    compilation_options:
+                        wgpu::PipelineCompilationOptions::default(),
+                }),
+                multiview: None,
+            });
+
+        self.pipelines.push((target, Pipelines { atlas, outlines }));
+        self.pipelines.len() - 1
+    }
+
+    /// Uploads the rectangles to outline, in normalized texture coordinates.
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        rects: &[[f32; 4]],
+    ) {
+        if rects.len() > self.supported_rects {
+            self.rects = create_rects(device, &self.label, rects.len());
+            self.supported_rects = rects.len();
+        }
+
+        if let Some(size) = NonZeroU64::new(mem::size_of_val(rects) as u64) {
+            staging_belt
+                .write_buffer(encoder, &self.rects, 0, size, device)
+                .copy_from_slice(bytemuck::cast_slice(rects));
+        }
+
+        self.uploaded_rects = rects.len();
+    }
+
+    /// Draws the given atlas and the outlines of the uploaded rectangles
+    /// into the region of the target.
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        atlas: &wgpu::TextureView,
+        region: Region,
+    ) {
+        if region.is_empty() {
+            return;
+        }
+
+        let index = self.pipelines(
+            device,
+            Target {
+                format: target.texture().format(),
+                sample_count: target.texture().sample_count(),
+            },
+        );
+        let pipelines = &self.pipelines[index].1;
+
+        let label = &self.label;
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("{label}::Debug atlas")),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(atlas),
+                },
+            ],
+        });
+
+        let mut render_pass =
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(&format!("{label}::Debug render pass")),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+        render_pass.set_viewport(
+            region.x as f32,
+            region.y as f32,
+            region.width as f32,
+            region.height as f32,
+            0.0,
+            1.0,
+        );
+
+        render_pass.set_bind_group(0, &bind_group, &[]);
+
+        render_pass.set_pipeline(&pipelines.atlas);
+        render_pass.draw(0..4, 0..1);
+
+        if self.uploaded_rects > 0 {
+            render_pass.set_pipeline(&pipelines.outlines);
+            render_pass.set_vertex_buffer(0, self.rects.slice(..));
+            render_pass.draw(0..5, 0..self.uploaded_rects as u32);
+        }
+    }
+}
+
+fn create_rects(
+    device: &wgpu::Device,
+    label: &str,
+    amount: usize,
+) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(&format!("{label}::Debug rects")),
+        size: (mem::size_of::<[f32; 4]>() * amount) as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -0,0 +1,45 @@
+use crate::ab_glyph::{Font, GlyphId, Point, Rect};
+use crate::{FontId, SectionGlyph};
+
+/// A glyph of a section in its final position, e.g. to anchor particle
+/// effects, hit areas or per-character animations to it.
+///
+/// Get them with
+/// [`GlyphBrush::positioned_glyphs`](struct.GlyphBrush.html#method.positioned_glyphs).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedGlyph {
+    /// The index of the text of the section the glyph comes from.
+    pub section_index: usize,
+    /// The byte index of the character of the glyph in its text.
+    pub byte_index: usize,
+    /// The font of the glyph.
+    pub font_id: FontId,
+    /// The id of the glyph in its font.
+    pub glyph_id: GlyphId,
+    /// The position of the origin of the glyph on its baseline, in pixels
+    /// from top-left.
+    pub position: Point,
+    /// The bounds of the pixels covered by the glyph, or `None` if it has no
+    /// outline, like a space.
+    pub pixel_bounds: Option<Rect>,
+}
+
+/// Returns the given glyphs laid out from a section in their final position.
+pub(crate) fn positioned<F: Font>(
+    fonts: &[F],
+    glyphs: &[SectionGlyph],
+) -> Vec<PositionedGlyph> {
+    glyphs
+        .iter()
+        .map(|glyph| PositionedGlyph {
+            section_index: glyph.section_index,
+            byte_index: glyph.byte_index,
+            font_id: glyph.font_id,
+            glyph_id: glyph.glyph.id,
+            position: glyph.glyph.position,
+            pixel_bounds: fonts[glyph.font_id.0]
+                .outline_glyph(glyph.glyph.clone())
+                .map(|outlined| outlined.px_bounds()),
+        })
+        .collect()
+}
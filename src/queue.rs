@@ -0,0 +1,84 @@
+use crate::{Extra, OwnedSection, Section, SectionGlyph};
+
+use glyph_brush::ab_glyph::Rect;
+use std::borrow::Cow;
+use std::sync::{Mutex, PoisonError};
+
+/// A queue of sections that can be shared between threads.
+///
+/// It is `Send + Sync`, so multiple threads can queue sections at the same
+/// time, e.g. while encoding the commands of different views in parallel.
+/// The queued sections are then moved to a `GlyphBrush` with
+/// [`GlyphBrush::queue_from`](struct.GlyphBrush.html#method.queue_from)
+/// before drawing on a single thread.
+///
+/// Sections queued from different threads are drawn in the order they were
+/// queued in, which depends on the scheduling of the threads. Use a separate
+/// queue per view when the order matters.
+#[derive(Debug, Default)]
+pub struct GlyphQueue {
+    queued: Mutex<Vec<Queued>>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Queued {
+    Section(OwnedSection),
+    PrePositioned {
+        glyphs: Vec<SectionGlyph>,
+        extra: Vec<Extra>,
+        bounds: Rect,
+    },
+}
+
+impl GlyphQueue {
+    /// Creates an empty [`GlyphQueue`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a section to be drawn by the `GlyphBrush` the queue is moved
+    /// to.
+    ///
+    /// Like [`GlyphBrush::queue`](struct.GlyphBrush.html#method.queue), it
+    /// accepts a reference to an [`OwnedSection`](struct.OwnedSection.html).
+    pub fn queue<'a, S>(&self, section: S)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = Section::to_owned(&section.into());
+
+        self.push(Queued::Section(section));
+    }
+
+    /// Queues pre-positioned glyphs to be drawn by the `GlyphBrush` the queue
+    /// is moved to.
+    pub fn queue_pre_positioned(
+        &self,
+        glyphs: Vec<SectionGlyph>,
+        extra: Vec<Extra>,
+        bounds: Rect,
+    ) {
+        self.push(Queued::PrePositioned {
+            glyphs,
+            extra,
+            bounds,
+        });
+    }
+
+    /// Returns whether nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub(crate) fn take(&self) -> Vec<Queued> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn push(&self, queued: Queued) {
+        self.lock().push(queued);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Queued>> {
+        self.queued.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{Extra, SectionGlyph};
+
+use std::collections::HashMap;
+
+/// Recolors the glyphs laid out from a section with a callback given the
+/// index of every glyph in layout order, returning the colors and depths of
+/// the texts of the section followed by the overriding colors, which the
+/// recolored glyphs point to with their `section_index`.
+///
+/// Glyphs sharing a text and an overriding color share its entry, so a
+/// section only grows by the distinct colors of its texts.
+pub(crate) fn recolor(
+    glyphs: &mut [SectionGlyph],
+    mut extra: Vec<Extra>,
+    mut color: impl FnMut(usize, &SectionGlyph) -> Option<[f32; 4]>,
+) -> Vec<Extra> {
+    let mut overrides: HashMap<(usize, [u32; 4]), usize> = HashMap::new();
+
+    for (index, glyph) in glyphs.iter_mut().enumerate() {
+        let Some(color) = color(index, glyph) else {
+            continue;
+        };
+
+        let text = glyph.section_index;
+
+        glyph.section_index = *overrides
+            .entry((text, color.map(f32::to_bits)))
+            .or_insert_with(|| {
+                extra.push(Extra {
+                    color,
+                    z: extra[text].z,
+                });
+
+                extra.len() - 1
+            });
+    }
+
+    extra
+}
@@ -1,7 +1,130 @@
+use crate::ab_glyph::Rect;
+use crate::pipeline::multiply;
+
 /// A region of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Region {
     pub x: u32,
     pub y: u32,
     pub width: u32,
     pub height: u32,
 }
+
+impl Region {
+    /// Returns the region of the framebuffer covered by a rectangle in the
+    /// coordinates of the sections, e.g. to scissor the text drawn with a
+    /// custom transform to logical bounds.
+    ///
+    /// The `transform` is the one the sections are drawn with, mapping their
+    /// coordinates to a render target of the given logical `size`, which is
+    /// `scale_factor` times larger in framebuffer pixels.
+    ///
+    /// The region covers every pixel the transformed rectangle touches, so
+    /// its edges are rounded outwards, and is clamped to the framebuffer.
+    pub fn from_logical(
+        rect: Rect,
+        transform: [f32; 16],
+        (width, height): (f32, f32),
+        scale_factor: f32,
+    ) -> Region {
+        let framebuffer_width = (width * scale_factor).round().max(0.0);
+        let framebuffer_height = (height * scale_factor).round().max(0.0);
+
+        let (mut left, mut top) = (f32::INFINITY, f32::INFINITY);
+        let (mut right, mut bottom) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+
+        for (x, y) in [
+            (rect.min.x, rect.min.y),
+            (rect.max.x, rect.min.y),
+            (rect.min.x, rect.max.y),
+            (rect.max.x, rect.max.y),
+        ] {
+            let [x, y, _, w] = multiply(&transform, [x, y, 0.0, 1.0]);
+
+            let x = (x / w + 1.0) / 2.0 * framebuffer_width;
+            let y = (1.0 - y / w) / 2.0 * framebuffer_height;
+
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+
+        let left = snap(left).floor().clamp(0.0, framebuffer_width);
+        let top = snap(top).floor().clamp(0.0, framebuffer_height);
+        let right = snap(right).ceil().clamp(left, framebuffer_width);
+        let bottom = snap(bottom).ceil().clamp(top, framebuffer_height);
+
+        Region {
+            x: left as u32,
+            y: top as u32,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        }
+    }
+
+    /// Returns whether the region covers no pixels, e.g. the region of a
+    /// minimized window. Nothing is drawn into an empty region.
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns the part of the region inside another one, which is empty if
+    /// they do not overlap.
+    pub(crate) fn intersect(self, other: Region) -> Region {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        Region {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+
+    /// Returns the region mirrored across the rows from `top` to
+    /// `top + height`, e.g. the rows of a viewport, with its edges rounded
+    /// outwards.
+    pub(crate) fn flip_y(self, top: f32, height: f32) -> Region {
+        let mirror = 2.0 * top + height;
+        let flipped_top = (mirror - (self.y + self.height) as f32).floor();
+        let flipped_bottom = (mirror - self.y as f32).ceil();
+
+        let y = flipped_top.max(0.0);
+
+        Region {
+            y: y as u32,
+            height: (flipped_bottom - y).max(0.0) as u32,
+            ..self
+        }
+    }
+
+    /// Returns the part of the region inside a target of the given size.
+    pub(crate) fn clamp(self, width: u32, height: u32) -> Region {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+
+        Region {
+            x,
+            y,
+            width: self.width.min(width - x),
+            height: self.height.min(height - y),
+        }
+    }
+}
+
+/// Rounds a pixel coordinate that is a whole pixel but for the error of the
+/// transform, so it is not rounded outwards to the next pixel.
+fn snap(coordinate: f32) -> f32 {
+    let rounded = coordinate.round();
+
+    if (coordinate - rounded).abs() < 1e-3 {
+        rounded
+    } else {
+        coordinate
+    }
+}
@@ -0,0 +1,30 @@
+/// The GPU resources a `GlyphBrush` reads and writes when drawing, e.g. to
+/// declare the dependencies of its render pass in a render graph.
+///
+/// A draw writes the buffers and the textures owned by the brush with
+/// commands recorded before its render pass, and reads all of them during
+/// it. Get them with
+/// [`GlyphBrush::resources`](struct.GlyphBrush.html#method.resources).
+#[derive(Debug, Clone)]
+pub struct Resources<'a> {
+    /// The uniform buffer of the transform of the last draw, written by draws
+    /// with a new transform. With several
+    /// [frames in flight](struct.GlyphBrushBuilder.html#method.frames_in_flight),
+    /// the next one is written instead.
+    pub transform: &'a wgpu::Buffer,
+    /// The uniform buffer of the palette colors, written by draws after a
+    /// palette color changes.
+    pub palette: &'a wgpu::Buffer,
+    /// The instance buffers of every frame in flight, written by draws with
+    /// new instances.
+    pub instances: Vec<&'a wgpu::Buffer>,
+    /// The texture of the glyph cache, written by draws with glyphs that are
+    /// not cached yet. It is the texture of the
+    /// [`SharedCache`](struct.SharedCache.html) of the brush, if any.
+    pub glyph_cache: &'a wgpu::Texture,
+    /// The other textures sampled by draws, like the atlases set with
+    /// [`GlyphBrush::set_atlas`](struct.GlyphBrush.html#method.set_atlas)
+    /// and the textures of oversized glyphs. The brush writes the ones it
+    /// owns when drawing new glyphs into them.
+    pub textures: Vec<&'a wgpu::Texture>,
+}
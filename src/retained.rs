@@ -0,0 +1,53 @@
+use crate::{GlyphStyle, OwnedSection};
+
+use std::collections::BTreeMap;
+
+/// The identifier of a section retained by a `GlyphBrush`.
+///
+/// See [`GlyphBrush::insert`](struct.GlyphBrush.html#method.insert).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SectionId(pub u64);
+
+/// The sections retained by a `GlyphBrush` between frames, with the style
+/// they are drawn with.
+#[derive(Debug, Default)]
+pub(crate) struct Retained {
+    pub(crate) sections: BTreeMap<SectionId, (OwnedSection, GlyphStyle)>,
+    pub(crate) dirty: bool,
+}
+
+impl Retained {
+    pub(crate) fn insert(
+        &mut self,
+        id: SectionId,
+        section: OwnedSection,
+        style: GlyphStyle,
+    ) -> Option<OwnedSection> {
+        self.dirty = true;
+        self.sections
+            .insert(id, (section, style))
+            .map(|(section, _)| section)
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        id: SectionId,
+        f: impl FnOnce(&mut OwnedSection),
+    ) -> bool {
+        let Some((section, _)) = self.sections.get_mut(&id) else {
+            return false;
+        };
+
+        f(section);
+        self.dirty = true;
+
+        true
+    }
+
+    pub(crate) fn remove(&mut self, id: SectionId) -> Option<OwnedSection> {
+        let section = self.sections.remove(&id);
+        self.dirty |= section.is_some();
+
+        section.map(|(section, _)| section)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::ab_glyph::{point, Font, Rect, ScaleFont};
+use crate::{selection, Section, SectionGlyph};
+
+use std::ops::Range;
+
+/// A run of laid out text of a single text of a section on a single line,
+/// with the geometry needed by accessibility tools, like the text run nodes
+/// of AccessKit.
+///
+/// Get them with
+/// [`GlyphBrush::text_runs`](struct.GlyphBrush.html#method.text_runs).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    /// The index of the text of the section the run comes from.
+    pub section_index: usize,
+    /// The byte range of the run in its text.
+    pub byte_range: Range<usize>,
+    /// The text of the run, including the line break ending it, if any.
+    pub text: String,
+    /// The index of the line of the run, starting at 0.
+    pub line: usize,
+    /// Whether the run is the last one of its line.
+    pub ends_line: bool,
+    /// The bounds of the run, in pixels from top-left.
+    pub bounds: Rect,
+    /// The byte range in its text and the bounds of every glyph of the run,
+    /// in order.
+    pub clusters: Vec<(Range<usize>, Rect)>,
+}
+
+/// Returns the runs of the given glyphs laid out from a section.
+pub(crate) fn runs<F: Font>(
+    fonts: &[F],
+    section: &Section<'_>,
+    glyphs: &[SectionGlyph],
+) -> Vec<TextRun> {
+    let clusters = selection::clusters(section, glyphs);
+
+    let mut lines: Vec<f32> = Vec::new();
+    let mut runs: Vec<TextRun> = Vec::new();
+
+    for (glyph, bytes) in glyphs.iter().zip(clusters) {
+        let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+        let position = glyph.glyph.position;
+
+        let bounds = Rect {
+            min: point(position.x, position.y - font.ascent()),
+            max: point(
+                position.x + font.h_advance(glyph.glyph.id),
+                position.y - font.descent(),
+            ),
+        };
+
+        let line = match lines.iter().position(|y| *y == position.y) {
+            Some(line) => line,
+            None => {
+                lines.push(position.y);
+                lines.len() - 1
+            }
+        };
+
+        match runs.last_mut() {
+            Some(run)
+                if run.line == line
+                    && run.section_index == glyph.section_index
+                    && run.byte_range.end == bytes.start =>
+            {
+                run.byte_range.end = bytes.end;
+                run.bounds = Rect {
+                    min: point(
+                        run.bounds.min.x.min(bounds.min.x),
+                        run.bounds.min.y.min(bounds.min.y),
+                    ),
+                    max: point(
+                        run.bounds.max.x.max(bounds.max.x),
+                        run.bounds.max.y.max(bounds.max.y),
+                    ),
+                };
+                run.clusters.push((bytes, bounds));
+            }
+            _ => runs.push(TextRun {
+                section_index: glyph.section_index,
+                byte_range: bytes.clone(),
+                text: String::new(),
+                line,
+                ends_line: false,
+                bounds,
+                clusters: vec![(bytes, bounds)],
+            }),
+        }
+    }
+
+    for index in 0..runs.len() {
+        let next_line = runs.get(index + 1).map(|run| run.line);
+        let run = &mut runs[index];
+
+        run.text = section.text[run.section_index].text[run.byte_range.clone()]
+            .to_owned();
+        run.ends_line = next_line != Some(run.line);
+    }
+
+    runs
+}
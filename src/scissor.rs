@@ -0,0 +1,142 @@
+use crate::ab_glyph::{point, Rect};
+use crate::pipeline::{multiply, Transform};
+use crate::{Instance, Region, Viewport};
+
+/// The pixels covered by the instances of a draw, in the coordinates of the
+/// sections, to scissor its render pass to them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Bounds {
+    /// There are no instances.
+    Empty,
+    /// The union of the rectangles of the instances, with their lowest and
+    /// highest depths.
+    Rect { rect: Rect, z: (f32, f32) },
+    /// Some instances are drawn with a transform slot, so the pixels they
+    /// cover are not known until the slot is drawn.
+    Unknown,
+}
+
+impl Bounds {
+    /// Returns the bounds of the given instances.
+    pub(crate) fn of<'a>(
+        instances: impl IntoIterator<Item = &'a Instance>,
+    ) -> Bounds {
+        instances
+            .into_iter()
+            .map(|instance| {
+                if instance.transform_slot() != 0 {
+                    return Bounds::Unknown;
+                }
+
+                Bounds::Rect {
+                    rect: instance.rect(),
+                    z: (instance.z(), instance.z()),
+                }
+            })
+            .fold(Bounds::Empty, Bounds::union)
+    }
+
+    /// Returns the bounds covering both bounds.
+    pub(crate) fn union(self, other: Bounds) -> Bounds {
+        match (self, other) {
+            (Bounds::Unknown, _) | (_, Bounds::Unknown) => Bounds::Unknown,
+            (Bounds::Empty, bounds) | (bounds, Bounds::Empty) => bounds,
+            (
+                Bounds::Rect { rect: a, z: a_z },
+                Bounds::Rect { rect: b, z: b_z },
+            ) => Bounds::Rect {
+                rect: Rect {
+                    min: point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+                    max: point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+                },
+                z: (a_z.0.min(b_z.0), a_z.1.max(b_z.1)),
+            },
+        }
+    }
+
+    /// Returns the region of a target of the given size covered by the
+    /// bounds after the transform, in the viewport if any, or `None` if it
+    /// is not known, e.g. for bounds behind the camera.
+    ///
+    /// The region covers every pixel the bounds touch, so its edges are
+    /// rounded outwards. With `flip_y`, the y axis is flipped after the
+    /// transform, like the default shaders do.
+    pub(crate) fn region(
+        self,
+        transform: &Transform,
+        viewport: Option<Viewport>,
+        (width, height): (u32, u32),
+        flip_y: bool,
+    ) -> Option<Region> {
+        let (rect, (near, far)) = match self {
+            Bounds::Empty => {
+                return Some(Region {
+                    x: 0,
+                    y: 0,
+                    width: 0,
+                    height: 0,
+                })
+            }
+            Bounds::Rect { rect, z } => (rect, z),
+            Bounds::Unknown => return None,
+        };
+
+        let (x, y, viewport_width, viewport_height) = match viewport {
+            Some(viewport) => {
+                (viewport.x, viewport.y, viewport.width, viewport.height)
+            }
+            None => (0.0, 0.0, width as f32, height as f32),
+        };
+
+        let (mut left, mut top) = (f32::INFINITY, f32::INFINITY);
+        let (mut right, mut bottom) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+
+        for corner_x in [rect.min.x, rect.max.x] {
+            for corner_y in [rect.min.y, rect.max.y] {
+                for z in [near, far] {
+                    let [clip_x, mut clip_y, _, w] = multiply(
+                        &transform.projection,
+                        multiply(&transform.view, [corner_x, corner_y, z, 1.0]),
+                    );
+
+                    if w <= 0.0 {
+                        return None;
+                    }
+
+                    if flip_y {
+                        clip_y = -clip_y;
+                    }
+
+                    let pixel_x = x + (clip_x / w + 1.0) / 2.0 * viewport_width;
+                    let pixel_y =
+                        y + (1.0 - clip_y / w) / 2.0 * viewport_height;
+
+                    left = left.min(pixel_x);
+                    top = top.min(pixel_y);
+                    right = right.max(pixel_x);
+                    bottom = bottom.max(pixel_y);
+                }
+            }
+        }
+
+        if !(left.is_finite()
+            && top.is_finite()
+            && right.is_finite()
+            && bottom.is_finite())
+        {
+            return None;
+        }
+
+        let left = left.floor().clamp(0.0, width as f32);
+        let top = top.floor().clamp(0.0, height as f32);
+        let right = right.ceil().clamp(left, width as f32);
+        let bottom = bottom.ceil().clamp(top, height as f32);
+
+        Some(Region {
+            x: left as u32,
+            y: top as u32,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        })
+    }
+}
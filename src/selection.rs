@@ -0,0 +1,154 @@
+use crate::ab_glyph::{point, Font, Rect, ScaleFont};
+use crate::{Section, SectionGlyph};
+
+use std::ops::Range;
+
+/// Returns the rectangles covering the glyphs of the given byte range of a
+/// section, one per line, from the glyphs laid out from it.
+///
+/// Byte offsets refer to the texts of the section joined together.
+pub(crate) fn rects<F: Font>(
+    fonts: &[F],
+    section: &Section<'_>,
+    glyphs: &[SectionGlyph],
+    range: Range<usize>,
+) -> Vec<Rect> {
+    let text_offsets = text_offsets(section);
+
+    let mut lines: Vec<(f32, Rect)> = Vec::new();
+
+    for glyph in glyphs {
+        let byte = text_offsets[glyph.section_index] + glyph.byte_index;
+
+        if !range.contains(&byte) {
+            continue;
+        }
+
+        let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+        let position = glyph.glyph.position;
+
+        let rect = Rect {
+            min: point(position.x, position.y - font.ascent()),
+            max: point(
+                position.x + font.h_advance(glyph.glyph.id),
+                position.y - font.descent(),
+            ),
+        };
+
+        match lines.iter_mut().find(|(y, _)| *y == position.y) {
+            Some((_, line)) => {
+                *line = Rect {
+                    min: point(
+                        line.min.x.min(rect.min.x),
+                        line.min.y.min(rect.min.y),
+                    ),
+                    max: point(
+                        line.max.x.max(rect.max.x),
+                        line.max.y.max(rect.max.y),
+                    ),
+                };
+            }
+            None => lines.push((position.y, rect)),
+        }
+    }
+
+    lines.into_iter().map(|(_, rect)| rect).collect()
+}
+
+/// Returns the zero-width rectangle of the caret at the given byte offset of
+/// a section, spanning the height of its line, from the glyphs laid out from
+/// it.
+///
+/// The caret is at the left edge of the glyph starting at the offset, or at
+/// the right edge of the last glyph before it. A section without glyphs has
+/// its caret at its screen position, with the height of its first text.
+pub(crate) fn caret<F: Font>(
+    fonts: &[F],
+    section: &Section<'_>,
+    glyphs: &[SectionGlyph],
+    byte: usize,
+) -> Option<Rect> {
+    let text_offsets = text_offsets(section);
+
+    let before = glyphs
+        .iter()
+        .map(|glyph| {
+            (text_offsets[glyph.section_index] + glyph.byte_index, glyph)
+        })
+        .filter(|(start, _)| *start <= byte)
+        .max_by_key(|(start, _)| *start);
+
+    let Some((start, glyph)) = before else {
+        let text = section.text.first()?;
+        let font = fonts[text.font_id.0].as_scaled(text.scale);
+        let (x, y) = section.screen_position;
+
+        return Some(Rect {
+            min: point(x, y),
+            max: point(x, y + font.ascent() - font.descent()),
+        });
+    };
+
+    let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+    let position = glyph.glyph.position;
+
+    let x = if start == byte {
+        position.x
+    } else {
+        position.x + font.h_advance(glyph.glyph.id)
+    };
+
+    Some(Rect {
+        min: point(x, position.y - font.ascent()),
+        max: point(x, position.y - font.descent()),
+    })
+}
+
+/// Returns the byte offset of every text of a section in the texts joined
+/// together.
+fn text_offsets(section: &Section<'_>) -> Vec<usize> {
+    section
+        .text
+        .iter()
+        .scan(0, |offset, text| {
+            let text_offset = *offset;
+            *offset += text.text.len();
+
+            Some(text_offset)
+        })
+        .collect()
+}
+
+/// Returns the byte ranges of the text each glyph came from, in the text of
+/// the section it belongs to.
+///
+/// A glyph covers the bytes up to the next glyph of the same text, so
+/// characters without a glyph of their own, like line breaks, belong to the
+/// glyph before them.
+pub(crate) fn clusters(
+    section: &Section<'_>,
+    glyphs: &[SectionGlyph],
+) -> Vec<Range<usize>> {
+    let mut starts: Vec<(usize, usize)> = glyphs
+        .iter()
+        .map(|glyph| (glyph.section_index, glyph.byte_index))
+        .collect();
+
+    starts.sort_unstable();
+    starts.dedup();
+
+    glyphs
+        .iter()
+        .map(|glyph| {
+            let start = (glyph.section_index, glyph.byte_index);
+            let next = starts.partition_point(|other| *other <= start);
+
+            let end = match starts.get(next) {
+                Some((text, byte)) if *text == glyph.section_index => *byte,
+                _ => section.text[glyph.section_index].text.len(),
+            };
+
+            glyph.byte_index..end
+        })
+        .collect()
+}
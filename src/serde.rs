@@ -0,0 +1,158 @@
+//! Remote [`serde`] definitions for the section types of [`glyph_brush`].
+//!
+//! The section types are defined in [`glyph_brush`], so they cannot implement
+//! `Serialize` and `Deserialize` directly. Use the definitions of this module
+//! with the `with` attribute of `serde` instead:
+//!
+//! ```ignore
+//! #[derive(serde::Deserialize)]
+//! struct Dialogue {
+//!     #[serde(with = "wgpu_glyph::serde::OwnedSectionDef")]
+//!     section: wgpu_glyph::OwnedSection,
+//! }
+//! ```
+//!
+//! [`serde`]: https://serde.rs
+//! [`glyph_brush`]: https://github.com/alexheretic/glyph-brush/tree/master/glyph-brush
+use crate::ab_glyph::PxScale;
+use crate::{
+    BuiltInLineBreaker, Extra, FontId, HorizontalAlign, Layout, OwnedSection,
+    OwnedText, VerticalAlign,
+};
+
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Remote definition of [`OwnedSection`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "OwnedSection")]
+pub struct OwnedSectionDef {
+    #[serde(default)]
+    pub screen_position: (f32, f32),
+    #[serde(default = "unbounded")]
+    pub bounds: (f32, f32),
+    #[serde(with = "LayoutDef", default)]
+    pub layout: Layout<BuiltInLineBreaker>,
+    #[serde(with = "texts", default)]
+    pub text: Vec<OwnedText>,
+}
+
+/// Remote definition of [`OwnedText`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "OwnedText")]
+pub struct OwnedTextDef {
+    pub text: String,
+    #[serde(with = "PxScaleDef", default = "default_scale")]
+    pub scale: PxScale,
+    #[serde(with = "FontIdDef", default)]
+    pub font_id: FontId,
+    #[serde(with = "ExtraDef", default)]
+    pub extra: Extra,
+}
+
+/// Remote definition of [`Extra`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Extra")]
+pub struct ExtraDef {
+    pub color: [f32; 4],
+    #[serde(default)]
+    pub z: f32,
+}
+
+/// Remote definition of [`PxScale`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "PxScale")]
+pub struct PxScaleDef {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Remote definition of [`FontId`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "FontId")]
+pub struct FontIdDef(pub usize);
+
+/// Remote definition of [`Layout`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Layout<BuiltInLineBreaker>")]
+pub enum LayoutDef {
+    SingleLine {
+        #[serde(with = "BuiltInLineBreakerDef", default)]
+        line_breaker: BuiltInLineBreaker,
+        #[serde(with = "HorizontalAlignDef", default = "left")]
+        h_align: HorizontalAlign,
+        #[serde(with = "VerticalAlignDef", default = "top")]
+        v_align: VerticalAlign,
+    },
+    Wrap {
+        #[serde(with = "BuiltInLineBreakerDef", default)]
+        line_breaker: BuiltInLineBreaker,
+        #[serde(with = "HorizontalAlignDef", default = "left")]
+        h_align: HorizontalAlign,
+        #[serde(with = "VerticalAlignDef", default = "top")]
+        v_align: VerticalAlign,
+    },
+}
+
+/// Remote definition of [`BuiltInLineBreaker`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "BuiltInLineBreaker")]
+pub enum BuiltInLineBreakerDef {
+    UnicodeLineBreaker,
+    AnyCharLineBreaker,
+}
+
+/// Remote definition of [`HorizontalAlign`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "HorizontalAlign")]
+pub enum HorizontalAlignDef {
+    Left,
+    Center,
+    Right,
+}
+
+/// Remote definition of [`VerticalAlign`].
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "VerticalAlign")]
+pub enum VerticalAlignDef {
+    Top,
+    Center,
+    Bottom,
+}
+
+fn unbounded() -> (f32, f32) {
+    OwnedSection::<Extra>::default().bounds
+}
+
+fn default_scale() -> PxScale {
+    OwnedText::<Extra>::default().scale
+}
+
+fn left() -> HorizontalAlign {
+    HorizontalAlign::Left
+}
+
+fn top() -> VerticalAlign {
+    VerticalAlign::Top
+}
+
+mod texts {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Text(#[serde(with = "OwnedTextDef")] OwnedText);
+
+    pub fn serialize<S: Serializer>(
+        texts: &[OwnedText],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(texts.iter().cloned().map(Text))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<OwnedText>, D::Error> {
+        let texts = Vec::<Text>::deserialize(deserializer)?;
+
+        Ok(texts.into_iter().map(|Text(text)| text).collect())
+    }
+}
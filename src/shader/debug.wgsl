@@ -0,0 +1,56 @@
+@group(0) @binding(0) var atlas_sampler: sampler;
+@group(0) @binding(1) var atlas: texture_2d<f32>;
+
+const BACKGROUND: vec3f = vec3f(0.1, 0.1, 0.1);
+const OUTLINE: vec4f = vec4f(0.0, 1.0, 0.0, 1.0);
+
+struct AtlasOutput {
+    @builtin(position) position: vec4f,
+    @location(0) tex_pos: vec2f,
+}
+
+@vertex
+fn vs_atlas(@builtin(vertex_index) vertex_index: u32) -> AtlasOutput {
+    var out: AtlasOutput;
+
+    out.tex_pos = vec2f(f32(vertex_index & 1u), f32(vertex_index >> 1u));
+    out.position = vec4f(
+        out.tex_pos.x * 2.0 - 1.0,
+        1.0 - out.tex_pos.y * 2.0,
+        0.0,
+        1.0,
+    );
+
+    return out;
+}
+
+@fragment
+fn fs_atlas(input: AtlasOutput) -> @location(0) vec4f {
+    let coverage = textureSample(atlas, atlas_sampler, input.tex_pos).r;
+
+    return vec4f(mix(BACKGROUND, vec3f(1.0, 1.0, 1.0), coverage), 1.0);
+}
+
+@vertex
+fn vs_outline(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) rect: vec4f,
+) -> @builtin(position) vec4f {
+    // Outlines are drawn as line strips of 5 vertices going around the
+    // rectangle clockwise, starting and ending at the left-top corner.
+    let corner = vertex_index % 4u;
+    let right = corner == 1u || corner == 2u;
+    let bottom = corner == 2u || corner == 3u;
+
+    let pos = vec2f(
+        select(rect.x, rect.z, right),
+        select(rect.y, rect.w, bottom),
+    );
+
+    return vec4f(pos.x * 2.0 - 1.0, 1.0 - pos.y * 2.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_outline() -> @location(0) vec4f {
+    return OUTLINE;
+}
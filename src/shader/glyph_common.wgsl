@@ -0,0 +1,206 @@
+struct Globals {
+    transform: mat4x4<f32>,
+    view: mat4x4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+@group(0) @binding(1) var font_sampler: sampler;
+@group(0) @binding(2) var<uniform> palette: array<vec4f, 256>;
+@group(0) @binding(3) var<uniform> transform_slots: array<mat4x4<f32>, 8>;
+@group(1) @binding(0) var font_tex: texture_2d<f32>;
+@group(1) @binding(2) var atlas_1_tex: texture_2d<f32>;
+@group(1) @binding(3) var atlas_2_tex: texture_2d<f32>;
+@group(1) @binding(4) var atlas_3_tex: texture_2d<f32>;
+
+// Exponent applied to the coverage of glyphs. Values above `1.0` thin the
+// text, and values below thicken it.
+override alpha_gamma: f32 = 1.0;
+
+// The bit of the atlas of an instance that multiplies its color by the palette
+// color indexed by the bits 8 to 15.
+const PALETTE_FLAG: u32 = 0x80000000u;
+
+// The bit of the atlas of an instance that samples the glyph cache with
+// nearest filtering.
+const NEAREST_FLAG: u32 = 0x10000u;
+
+// The lowest bit of the transform slot of an instance, whose slot `0` is the
+// transform of the draw call.
+const SLOT_SHIFT: u32 = 28u;
+
+// Whether the colors of glyphs are sRGB-encoded, instead of linear.
+override srgb_colors: bool = false;
+
+// Whether the target is read bottom-up, like the frames of video pipelines,
+// so the text is drawn upside down.
+override flip_y: bool = false;
+
+// The sharpness of the edges of glyphs, in pixels per unit of coverage around
+// `0.5`, keeping edges equally sharp at any scale or rotation of the text.
+// `0.0` keeps the coverage as rasterized.
+override edge_sharpness: f32 = 0.0;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) f_tex_pos: vec2f,
+    @location(1) f_color: vec4f,
+    @location(2) @interpolate(flat) f_atlas: u32,
+    @location(3) @interpolate(flat) f_radius: f32,
+    @location(4) @interpolate(flat) f_nearest: u32,
+}
+
+fn srgb_to_linear(color: vec4f) -> vec4f {
+    let lower = color.rgb / 12.92;
+    let higher = pow((color.rgb + 0.055) / 1.055, vec3(2.4));
+
+    return vec4(select(higher, lower, color.rgb <= vec3(0.04045)), color.a);
+}
+
+// Returns the vertex of the given corner of the triangle strip of a glyph
+// quad, shared by the vertex entry points of every instance mode.
+fn glyph_vertex(
+    vertex_index: u32,
+    left_top: vec3f,
+    right_bottom: vec2f,
+    tex_left_top: vec2f,
+    tex_right_bottom: vec2f,
+    color: vec4f,
+    atlas: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    var pos = vec2f(0, 0);
+    let left = left_top.x;
+    let right = right_bottom.x;
+    let top = left_top.y;
+    let bottom = right_bottom.y;
+
+    // Flipping swaps the two middle corners of the strip, so the winding of
+    // the quad is kept.
+    var corner = vertex_index;
+
+    if (flip_y && (corner == 1u || corner == 2u)) {
+        corner = 3u - corner;
+    }
+
+    switch corner {
+        case 0u: {
+            pos = vec2(left, top);
+            out.f_tex_pos = tex_left_top;
+        }
+        case 1u: {
+            pos = vec2(right, top);
+            out.f_tex_pos = vec2(tex_right_bottom.x, tex_left_top.y);
+        }
+        case 2u: {
+            pos = vec2(left, bottom);
+            out.f_tex_pos = vec2(tex_left_top.x, tex_right_bottom.y);
+        }
+        case 3u: {
+            pos = vec2(right, bottom);
+            out.f_tex_pos = tex_right_bottom;
+        }
+        default: {}
+    }
+
+    out.f_atlas = atlas & 0xffu;
+    out.f_radius = f32((atlas >> 17u) & 0x7ffu);
+    out.f_nearest = atlas & NEAREST_FLAG;
+    out.f_color = color;
+
+    // The color is multiplied by the palette color of the instance, if any.
+    if ((atlas & PALETTE_FLAG) != 0u) {
+        out.f_color *= palette[(atlas >> 8u) & 0xffu];
+    }
+
+    if (srgb_colors) {
+        out.f_color = srgb_to_linear(out.f_color);
+    }
+
+    var transform = globals.transform * globals.view;
+    let slot = (atlas >> SLOT_SHIFT) & 0x7u;
+
+    if (slot != 0u) {
+        transform = transform_slots[slot];
+    }
+
+    out.position = transform * vec4(pos, left_top.z, 1.0);
+
+    if (flip_y) {
+        out.position.y = -out.position.y;
+    }
+
+    return out;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4f {
+    return fragment(input);
+}
+
+// Single-channel targets, like the `R8Unorm` targets of coverage masks, store
+// the coverage of the text instead of its color, accumulated by blending its
+// alpha.
+@fragment
+fn fs_mask(input: VertexOutput) -> @location(0) vec4f {
+    return vec4f(1.0, 1.0, 1.0, fragment(input).a);
+}
+
+// Remaps the coverage of a glyph so its edge, where the coverage is `0.5`,
+// fades over `1.0 / edge_sharpness` pixels on screen.
+fn sharpen(coverage: f32, width: f32) -> f32 {
+    if (edge_sharpness <= 0.0 || width <= 0.0) {
+        return coverage;
+    }
+
+    return clamp((coverage - 0.5) / width * edge_sharpness + 0.5, 0.0, 1.0);
+}
+
+fn fragment(input: VertexOutput) -> vec4f {
+    // Every atlas is sampled so sampling happens in uniform control flow.
+    let filtered = textureSample(font_tex, font_sampler, input.f_tex_pos).r;
+    // Nearest filtering loads the texel directly, as some backends cannot
+    // sample a texture with multiple samplers.
+    let dimensions = textureDimensions(font_tex);
+    let texel = min(
+        vec2u(input.f_tex_pos * vec2f(dimensions)),
+        dimensions - vec2(1u),
+    );
+    let nearest = textureLoad(font_tex, texel, 0).r;
+    let coverage = select(filtered, nearest, input.f_nearest != 0u);
+    // The change of coverage between neighboring pixels.
+    let coverage_width = fwidth(coverage);
+    let atlas_1 = textureSample(atlas_1_tex, font_sampler, input.f_tex_pos);
+    let atlas_2 = textureSample(atlas_2_tex, font_sampler, input.f_tex_pos);
+    let atlas_3 = textureSample(atlas_3_tex, font_sampler, input.f_tex_pos);
+
+    // The size in pixels of rounded rectangles, which span the texture
+    // coordinates from 0 to 1.
+    let size = 1.0 / fwidth(input.f_tex_pos);
+
+    var sampled = vec4f(1.0, 1.0, 1.0, pow(sharpen(coverage, coverage_width), alpha_gamma));
+
+    switch input.f_atlas {
+        case 1u: { sampled = atlas_1; }
+        case 2u: { sampled = atlas_2; }
+        case 3u: { sampled = atlas_3; }
+        case 254u: {
+            let half = size / 2.0;
+            let radius = min(input.f_radius, min(half.x, half.y));
+            let corner = abs(input.f_tex_pos * size - half) - (half - radius);
+            let distance = length(max(corner, vec2(0.0)))
+                + min(max(corner.x, corner.y), 0.0)
+                - radius;
+
+            sampled = vec4f(1.0, 1.0, 1.0, clamp(0.5 - distance, 0.0, 1.0));
+        }
+        case 255u: { sampled = vec4f(1.0); }
+        default: {}
+    }
+
+    if (sampled.a <= 0.0) {
+        discard;
+    }
+
+    return input.f_color * sampled;
+}
@@ -0,0 +1,26 @@
+// Positions are encoded in fixed point with this many subpixels per pixel.
+const SUBPIXELS: f32 = 4.0;
+
+struct VertexInput {
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) rect: vec4<i32>,
+    @location(1) z: f32,
+    @location(2) tex_rect: vec4f,
+    @location(3) color: vec4f,
+    @location(4) atlas: u32,
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    let rect = vec4f(input.rect) / SUBPIXELS;
+
+    return glyph_vertex(
+        input.vertex_index,
+        vec3(rect.xy, input.z),
+        rect.zw,
+        input.tex_rect.xy,
+        input.tex_rect.zw,
+        input.color,
+        input.atlas,
+    );
+}
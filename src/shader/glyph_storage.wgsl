@@ -0,0 +1,29 @@
+@group(1) @binding(1) var<storage, read> instances: array<f32>;
+
+// Instances are tightly packed, so they are read as a flat array of floats.
+const INSTANCE_SIZE: u32 = 14u;
+
+struct VertexInput {
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    let base = input.instance_index * INSTANCE_SIZE;
+
+    return glyph_vertex(
+        input.vertex_index,
+        vec3f(instances[base], instances[base + 1u], instances[base + 2u]),
+        vec2f(instances[base + 3u], instances[base + 4u]),
+        vec2f(instances[base + 5u], instances[base + 6u]),
+        vec2f(instances[base + 7u], instances[base + 8u]),
+        vec4f(
+            instances[base + 9u],
+            instances[base + 10u],
+            instances[base + 11u],
+            instances[base + 12u],
+        ),
+        bitcast<u32>(instances[base + 13u]),
+    );
+}
@@ -0,0 +1,167 @@
+use crate::pipeline::Cache;
+use crate::style::Styled;
+use crate::{Extra, GlyphStyle, Instance, SectionGlyph};
+
+use glyph_brush::ab_glyph::Rect;
+use glyph_brush::{DefaultSectionHasher, GlyphVertex};
+use glyph_brush_draw_cache::DrawCache;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A glyph cache that can be shared by multiple `GlyphBrush`, e.g. one per
+/// render target format or per window, so glyphs are rasterized and stored
+/// only once.
+///
+/// Use it with
+/// [`GlyphBrushBuilder::shared_cache`](struct.GlyphBrushBuilder.html#method.shared_cache).
+///
+/// Glyphs are identified in the cache by the index of their font, so every
+/// brush sharing a cache must have the same fonts, in the same order. Building
+/// the brushes with clones of the same `FontArc` shares the font data too.
+#[derive(Clone)]
+pub struct SharedCache {
+    state: Arc<Mutex<State>>,
+}
+
+pub(crate) struct State {
+    pub(crate) draw_cache: DrawCache,
+    pub(crate) texture: Cache,
+    pub(crate) generation: u64,
+}
+
+impl SharedCache {
+    /// Creates a new [`SharedCache`] with the given initial dimensions.
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        SharedCache {
+            state: Arc::new(Mutex::new(State {
+                draw_cache: DrawCache::builder()
+                    .dimensions(width, height)
+                    .build(),
+                texture: Cache::new(device, "wgpu_glyph", width, height),
+                generation: 0,
+            })),
+        }
+    }
+
+    /// Returns the current dimensions of the cache.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.lock().draw_cache.dimensions()
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for SharedCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedCache")
+    }
+}
+
+/// The sections queued by a `GlyphBrush` using a [`SharedCache`].
+///
+/// Layouts are cached between frames by the hash of their section, like
+/// `glyph_brush` does for brushes with their own cache.
+#[derive(Default)]
+pub(crate) struct Queue {
+    layouts: HashMap<u64, Glyphed>,
+    used: HashSet<u64>,
+    sections: Vec<Queued>,
+    hasher: DefaultSectionHasher,
+}
+
+#[derive(Debug)]
+enum Queued {
+    Cached(u64),
+    PrePositioned(Glyphed),
+}
+
+/// Laid out glyphs of a section.
+#[derive(Debug)]
+pub(crate) struct Glyphed {
+    pub(crate) glyphs: Vec<SectionGlyph>,
+    pub(crate) extra: Vec<Extra>,
+    pub(crate) bounds: Rect,
+    pub(crate) style: GlyphStyle,
+}
+
+impl Queue {
+    /// Queues a section with the given hash, laying it out if it is not
+    /// cached.
+    pub(crate) fn queue(
+        &mut self,
+        hash: u64,
+        layout: impl FnOnce() -> Glyphed,
+    ) {
+        let _ = self.layouts.entry(hash).or_insert_with(layout);
+        let _ = self.used.insert(hash);
+
+        self.sections.push(Queued::Cached(hash));
+    }
+
+    /// Hashes a section and its layout with the default section hasher.
+    pub(crate) fn hash(&self, section: impl Hash, layout: impl Hash) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+
+        section.hash(&mut hasher);
+        layout.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    pub(crate) fn queue_pre_positioned(&mut self, glyphed: Glyphed) {
+        self.sections.push(Queued::PrePositioned(glyphed));
+    }
+
+    /// Retains the layout of a section with the given hash for the next
+    /// frame.
+    pub(crate) fn keep_cached(&mut self, hash: u64) {
+        if self.layouts.contains_key(&hash) {
+            let _ = self.used.insert(hash);
+        }
+    }
+
+    fn sections(&self) -> impl Iterator<Item = &Glyphed> {
+        self.sections.iter().map(|queued| match queued {
+            Queued::Cached(hash) => &self.layouts[hash],
+            Queued::PrePositioned(glyphed) => glyphed,
+        })
+    }
+
+    /// Returns the glyphs of the queued sections.
+    pub(crate) fn glyphs(&self) -> impl Iterator<Item = &SectionGlyph> {
+        self.sections().flat_map(|glyphed| &glyphed.glyphs)
+    }
+
+    /// Returns the instances of the queued sections in the given cache.
+    pub(crate) fn instances(&self, draw_cache: &DrawCache) -> Vec<Instance> {
+        self.sections()
+            .flat_map(|glyphed| {
+                glyphed.glyphs.iter().filter_map(|glyph| {
+                    let (tex_coords, pixel_coords) =
+                        draw_cache.rect_for(glyph.font_id.0, &glyph.glyph)?;
+
+                    Some(Instance::from_vertex(GlyphVertex {
+                        tex_coords,
+                        pixel_coords,
+                        bounds: glyphed.bounds,
+                        extra: &Styled {
+                            extra: glyphed.extra[glyph.section_index],
+                            style: glyphed.style,
+                        },
+                    }))
+                })
+            })
+            .collect()
+    }
+
+    /// Clears the queued sections, dropping the layouts that were not used.
+    pub(crate) fn finish(&mut self) {
+        self.sections.clear();
+        self.layouts.retain(|hash, _| self.used.contains(hash));
+        self.used.clear();
+    }
+}
@@ -0,0 +1,228 @@
+use glyph_brush::ab_glyph::{Font, PxScale, Rect};
+use glyph_brush::{
+    BuiltInLineBreaker, FontId, GlyphPositioner, Layout, SectionGeometry,
+    SectionGlyph, SectionText, ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that synthesizes small
+/// caps for some texts of a section.
+///
+/// Glyphs are not shaped with OpenType features, so the `smcp` feature of a
+/// font is never used. Instead, lowercase letters are replaced with their
+/// uppercase form, drawn at a reduced scale and spaced out slightly.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+///
+/// Line breaking is performed by the inner layout before spacing out the
+/// small caps, so wrapped lines may slightly exceed their bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmallCaps<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    all: bool,
+    texts: Vec<usize>,
+    scale: f32,
+    tracking: f32,
+}
+
+impl<L> SmallCaps<L> {
+    /// Wraps the given layout, without synthesizing small caps for any text.
+    pub fn new(layout: L) -> Self {
+        SmallCaps {
+            layout,
+            all: false,
+            texts: Vec::new(),
+            scale: 0.7,
+            tracking: 0.05,
+        }
+    }
+
+    /// Synthesizes small caps for all the texts of the section.
+    pub fn all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+
+    /// Synthesizes small caps for the text with the given index in the
+    /// section.
+    pub fn text(mut self, index: usize) -> Self {
+        if !self.texts.contains(&index) {
+            self.texts.push(index);
+        }
+
+        self
+    }
+
+    /// Sets the scale of the small caps, relative to the scale of their text.
+    ///
+    /// Defaults to `0.7`.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the spacing added after every small cap, relative to the
+    /// horizontal scale of their text.
+    ///
+    /// Defaults to `0.05`.
+    pub fn tracking(mut self, tracking: f32) -> Self {
+        self.tracking = tracking;
+        self
+    }
+
+    /// Returns whether small caps are synthesized for the text with the given
+    /// index.
+    pub fn applies_to(&self, index: usize) -> bool {
+        self.all || self.texts.contains(&index)
+    }
+}
+
+/// A part of a text laid out with the same scale.
+struct Run {
+    index: usize,
+    text: String,
+    scale: PxScale,
+    font_id: FontId,
+    small: bool,
+    /// The byte index of every character, in the run and in the original
+    /// text.
+    bytes: Vec<(usize, usize)>,
+}
+
+impl Run {
+    fn new(index: usize, text: &SectionText<'_>, scale: PxScale) -> Self {
+        Run {
+            index,
+            text: String::new(),
+            scale,
+            font_id: text.font_id,
+            small: scale != text.scale,
+            bytes: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, byte_index: usize, character: char) {
+        self.bytes.push((self.text.len(), byte_index));
+        self.text.push(character);
+    }
+
+    fn original_byte_index(&self, byte_index: usize) -> usize {
+        self.bytes
+            .binary_search_by_key(&byte_index, |(run, _)| *run)
+            .map_or(byte_index, |i| self.bytes[i].1)
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for SmallCaps<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        if !self.all && self.texts.is_empty() {
+            return self.layout.calculate_glyphs(fonts, geometry, sections);
+        }
+
+        let mut runs: Vec<Run> = Vec::new();
+
+        for (index, section) in sections.iter().enumerate() {
+            let text = section.to_section_text();
+
+            if !self.applies_to(index) || text.text.is_empty() {
+                let mut run = Run::new(index, &text, text.scale);
+
+                for (byte_index, character) in text.text.char_indices() {
+                    run.push(byte_index, character);
+                }
+
+                runs.push(run);
+                continue;
+            }
+
+            let small_scale = PxScale {
+                x: text.scale.x * self.scale,
+                y: text.scale.y * self.scale,
+            };
+
+            for (byte_index, character) in text.text.char_indices() {
+                let mut uppercase = character.to_uppercase();
+                let small = character.is_lowercase() && uppercase.len() == 1;
+
+                let character = if small {
+                    uppercase.next().unwrap_or(character)
+                } else {
+                    character
+                };
+
+                let continues = runs.last().is_some_and(|run| {
+                    run.index == index && run.small == small
+                });
+
+                if !continues {
+                    runs.push(Run::new(
+                        index,
+                        &text,
+                        if small { small_scale } else { text.scale },
+                    ));
+                }
+
+                if let Some(run) = runs.last_mut() {
+                    run.push(byte_index, character);
+                }
+            }
+        }
+
+        let texts: Vec<SectionText<'_>> = runs
+            .iter()
+            .map(|run| SectionText {
+                text: &run.text,
+                scale: run.scale,
+                font_id: run.font_id,
+            })
+            .collect();
+
+        let mut glyphs = self.layout.calculate_glyphs(fonts, geometry, &texts);
+
+        let mut line_y = f32::NAN;
+        let mut shift = 0.0;
+
+        for glyph in &mut glyphs {
+            let run = &runs[glyph.section_index];
+
+            if glyph.glyph.position.y != line_y {
+                line_y = glyph.glyph.position.y;
+                shift = 0.0;
+            }
+
+            glyph.glyph.position.x += shift;
+            glyph.section_index = run.index;
+            glyph.byte_index = run.original_byte_index(glyph.byte_index);
+
+            if run.small {
+                shift += self.tracking * glyph.glyph.scale.x / self.scale;
+            }
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for SmallCaps<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.all.hash(state);
+        self.texts.hash(state);
+        self.scale.to_bits().hash(state);
+        self.tracking.to_bits().hash(state);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::mpsc;
+
+/// The format of the target of a snapshot, matching the sRGB surfaces text
+/// is usually drawn onto.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// An offscreen target that queued text is drawn onto and read back from.
+pub(crate) struct Snapshot {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+}
+
+impl Snapshot {
+    /// Creates a transparent target of the given size.
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+    ) -> Result<Snapshot, String> {
+        let max = device.limits().max_texture_dimension_2d;
+
+        if width == 0 || height == 0 || width > max || height > max {
+            return Err(format!(
+                "Invalid snapshot size {width}x{height}, must be within \
+                 1x1 and {max}x{max}"
+            ));
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("wgpu_glyph::snapshot"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Ok(Snapshot {
+            texture,
+            view,
+            width,
+            height,
+        })
+    }
+
+    pub(crate) fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Submits the given encoder, reads the target back and writes it to the
+    /// given path as a PNG image with straight alpha.
+    pub(crate) fn save(
+        self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mut encoder: wgpu::CommandEncoder,
+        path: &Path,
+    ) -> Result<(), String> {
+        let row_size = self.width * 4;
+        let padded_row_size = row_size
+            .div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("wgpu_glyph::snapshot"),
+            size: u64::from(padded_row_size) * u64::from(self.height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_size),
+                    rows_per_image: None,
+                },
+            },
+            self.texture.size(),
+        );
+
+        let _ = queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+
+        let _ = device
+            .poll(wgpu::PollType::Wait)
+            .map_err(|error| error.to_string())?;
+
+        receiver
+            .recv()
+            .map_err(|error| error.to_string())?
+            .map_err(|error| error.to_string())?;
+
+        let pixels: Vec<u8> = slice
+            .get_mapped_range()
+            .chunks(padded_row_size as usize)
+            .flat_map(|row| row[..row_size as usize].chunks(4))
+            .flat_map(demultiply)
+            .collect();
+
+        buffer.unmap();
+
+        let file = File::create(path).map_err(|error| error.to_string())?;
+
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// Converts a pixel blended onto a transparent sRGB target, which is
+/// premultiplied in linear space, to straight alpha.
+fn demultiply(pixel: &[u8]) -> [u8; 4] {
+    let alpha = pixel[3];
+
+    if alpha == 0 {
+        return [0; 4];
+    }
+
+    let alpha_linear = f32::from(alpha) / 255.0;
+    let channel = |value: u8| {
+        let linear = to_linear(f32::from(value) / 255.0) / alpha_linear;
+
+        (to_srgb(linear.min(1.0)) * 255.0).round() as u8
+    };
+
+    [
+        channel(pixel[0]),
+        channel(pixel[1]),
+        channel(pixel[2]),
+        alpha,
+    ]
+}
+
+fn to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,109 @@
+use crate::Instance;
+
+use std::collections::HashSet;
+
+/// Statistics about the last frame drawn by a `GlyphBrush`.
+///
+/// Obtained with
+/// [`GlyphBrush::frame_stats`](struct.GlyphBrush.html#method.frame_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameStats {
+    /// The amount of glyphs drawn.
+    pub glyphs: usize,
+    /// The amount of custom instances drawn, queued with
+    /// [`GlyphBrush::queue_instances`](struct.GlyphBrush.html#method.queue_instances).
+    pub custom_instances: usize,
+    /// The amount of bytes of the instance buffer in use.
+    pub instance_bytes: u64,
+    /// The amount of instances the instance buffer can hold before growing.
+    pub instance_capacity: usize,
+    /// The amount of glyphs drawn that were already in the glyph cache.
+    pub cache_hits: usize,
+    /// The amount of glyphs rasterized and uploaded to the glyph cache.
+    pub cache_misses: usize,
+    /// The percentage of the glyph cache occupied by glyphs, from `0.0` to
+    /// `100.0`.
+    pub atlas_occupancy: f32,
+    /// The amount of times the glyph cache has grown since the brush was
+    /// built.
+    pub cache_resizes: usize,
+}
+
+/// Tracks the glyphs placed in the glyph cache to compute [`FrameStats`].
+///
+/// The draw cache only evicts glyphs by clearing the whole cache and placing
+/// the glyphs in use again, so an upload overlapping a known glyph means
+/// every known glyph has been evicted.
+#[derive(Debug, Default)]
+pub(crate) struct Tracker {
+    glyphs: HashSet<[u32; 4]>,
+    uploads: Vec<[u32; 4]>,
+}
+
+impl Tracker {
+    /// Records an upload of the given region of the glyph cache.
+    pub(crate) fn upload(&mut self, region: [u32; 4]) {
+        self.uploads.push(region);
+    }
+
+    /// Forgets every known glyph, e.g. after the glyph cache is resized.
+    pub(crate) fn reset(&mut self) {
+        self.glyphs.clear();
+    }
+
+    /// Records the glyphs of a frame, returning the amount of cache hits and
+    /// misses.
+    pub(crate) fn draw(
+        &mut self,
+        glyphs: &[Instance],
+        (width, height): (u32, u32),
+    ) -> (usize, usize) {
+        let regions = glyphs.iter().map(|glyph| {
+            let rect = glyph.tex_rect();
+
+            [
+                (rect.min.x * width as f32).round() as u32,
+                (rect.min.y * height as f32).round() as u32,
+                (rect.max.x * width as f32).round() as u32,
+                (rect.max.y * height as f32).round() as u32,
+            ]
+        });
+
+        let evicted = self.uploads.drain(..).any(|upload| {
+            self.glyphs.iter().any(|glyph| overlaps(&upload, glyph))
+        });
+
+        if evicted {
+            self.glyphs.clear();
+        }
+
+        let misses =
+            regions.filter(|region| self.glyphs.insert(*region)).count();
+
+        (glyphs.len() - misses, misses)
+    }
+
+    /// Returns the regions of the glyph cache occupied by the known glyphs,
+    /// in pixels.
+    pub(crate) fn glyphs(&self) -> impl Iterator<Item = &[u32; 4]> {
+        self.glyphs.iter()
+    }
+
+    /// Returns the fraction of a glyph cache of the given dimensions occupied
+    /// by the known glyphs.
+    pub(crate) fn occupancy(&self, (width, height): (u32, u32)) -> f32 {
+        let area: u64 = self
+            .glyphs
+            .iter()
+            .map(|[left, top, right, bottom]| {
+                u64::from(right - left) * u64::from(bottom - top)
+            })
+            .sum();
+
+        area as f32 / (width as f32 * height as f32)
+    }
+}
+
+fn overlaps(a: &[u32; 4], b: &[u32; 4]) -> bool {
+    a[0] < b[2] && b[0] < a[2] && a[1] < b[3] && b[1] < a[3]
+}
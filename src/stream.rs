@@ -0,0 +1,224 @@
+use crate::ab_glyph::{Font, ScaleFont};
+use crate::{
+    BuiltInLineBreaker, Layout, OwnedText, Section, SectionGlyph, Text,
+    VerticalAlign,
+};
+
+/// A section of text that only grows at its end, like a log or a chat.
+///
+/// The text is split into paragraphs at hard line breaks, and every paragraph
+/// is queued as a separate section. Complete paragraphs are laid out once and
+/// reuse their cached vertices, while only the last paragraph is laid out
+/// again when text is appended to it. Paragraphs below the bounds are not
+/// queued at all.
+///
+/// Queue it with
+/// [`GlyphBrush::queue_stream`](struct.GlyphBrush.html#method.queue_stream).
+/// The vertical alignment of the layout is ignored, the text is always
+/// aligned to the top.
+#[derive(Debug, Clone)]
+pub struct TextStream {
+    screen_position: (f32, f32),
+    bounds: (f32, f32),
+    layout: Layout<BuiltInLineBreaker>,
+    paragraphs: Vec<Paragraph>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Paragraph {
+    text: Vec<OwnedText>,
+    height: Option<f32>,
+}
+
+impl Default for TextStream {
+    fn default() -> Self {
+        TextStream {
+            screen_position: (0.0, 0.0),
+            bounds: (f32::INFINITY, f32::INFINITY),
+            layout: Layout::default(),
+            paragraphs: Vec::new(),
+        }
+    }
+}
+
+impl TextStream {
+    /// Creates an empty [`TextStream`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the position on screen of the text, in pixels from top-left.
+    pub fn with_screen_position<P: Into<(f32, f32)>>(
+        mut self,
+        position: P,
+    ) -> Self {
+        self.screen_position = position.into();
+        self
+    }
+
+    /// Sets the maximum (width, height) bounds of the text, in pixels.
+    pub fn with_bounds<P: Into<(f32, f32)>>(mut self, bounds: P) -> Self {
+        self.bounds = bounds.into();
+        self.invalidate();
+        self
+    }
+
+    /// Sets the layout of the text.
+    pub fn with_layout<L: Into<Layout<BuiltInLineBreaker>>>(
+        mut self,
+        layout: L,
+    ) -> Self {
+        self.layout = layout.into();
+        self.invalidate();
+        self
+    }
+
+    /// Appends the given text at the end of the stream.
+    pub fn append(&mut self, text: OwnedText) {
+        let OwnedText {
+            text: content,
+            scale,
+            font_id,
+            extra,
+        } = text;
+
+        let style = |line: &str| OwnedText {
+            text: line.to_owned(),
+            scale,
+            font_id,
+            extra,
+        };
+
+        let mut lines = content.split('\n');
+
+        if let Some(line) = lines.next() {
+            if self.paragraphs.is_empty() {
+                self.paragraphs.push(Paragraph::default());
+            }
+
+            let last = self.paragraphs.last_mut().unwrap();
+
+            // Empty texts are only kept to size empty paragraphs
+            if !line.is_empty() || last.text.is_empty() {
+                last.text.push(style(line));
+            }
+        }
+
+        self.paragraphs.extend(lines.map(|line| Paragraph {
+            text: vec![style(line)],
+            height: None,
+        }));
+    }
+
+    /// Removes all the text of the stream.
+    pub fn clear(&mut self) {
+        self.paragraphs.clear();
+    }
+
+    /// Returns whether the stream contains no text.
+    pub fn is_empty(&self) -> bool {
+        self.paragraphs.is_empty()
+    }
+
+    fn invalidate(&mut self) {
+        for paragraph in &mut self.paragraphs {
+            paragraph.height = None;
+        }
+    }
+
+    /// Measures the complete paragraphs that have not been measured yet with
+    /// the given function, stopping at the bounds.
+    pub(crate) fn measure(
+        &mut self,
+        mut height: impl FnMut(&Section<'_>) -> f32,
+    ) {
+        let complete = self.paragraphs.len().saturating_sub(1);
+        let mut top = self.screen_position.1;
+
+        for i in 0..complete {
+            if !self.contains(top) {
+                break;
+            }
+
+            let paragraph_height = match self.paragraphs[i].height {
+                Some(paragraph_height) => paragraph_height,
+                None => {
+                    let section = self.section(&self.paragraphs[i], top);
+                    let paragraph_height = height(&section);
+
+                    self.paragraphs[i].height = Some(paragraph_height);
+                    paragraph_height
+                }
+            };
+
+            top += paragraph_height;
+        }
+    }
+
+    /// Returns the sections of the paragraphs within the bounds.
+    ///
+    /// The complete paragraphs must have been measured before.
+    pub(crate) fn sections(&self) -> impl Iterator<Item = Section<'_>> {
+        self.paragraphs
+            .iter()
+            .scan(self.screen_position.1, |top, paragraph| {
+                let paragraph_top = *top;
+                *top += paragraph.height.unwrap_or(0.0);
+
+                Some((paragraph_top, paragraph))
+            })
+            .take_while(|(top, _)| self.contains(*top))
+            .map(|(top, paragraph)| self.section(paragraph, top))
+    }
+
+    fn contains(&self, top: f32) -> bool {
+        top < self.screen_position.1 + self.bounds.1
+    }
+
+    fn section<'a>(&self, paragraph: &'a Paragraph, top: f32) -> Section<'a> {
+        Section {
+            screen_position: (self.screen_position.0, top),
+            bounds: (
+                self.bounds.0,
+                self.bounds.1 - (top - self.screen_position.1),
+            ),
+            layout: self.layout.v_align(VerticalAlign::Top),
+            text: paragraph.text.iter().map(Text::from).collect(),
+        }
+    }
+}
+
+/// Returns the height of the lines of the given glyphs, laid out from the
+/// given section.
+pub(crate) fn height<F: Font>(
+    fonts: &[F],
+    section: &Section<'_>,
+    glyphs: &[SectionGlyph],
+) -> f32 {
+    let line_height = |font_id: crate::FontId, scale| {
+        let font = fonts[font_id.0].as_scaled(scale);
+
+        font.ascent() - font.descent() + font.line_gap()
+    };
+
+    if glyphs.is_empty() {
+        return section
+            .text
+            .iter()
+            .map(|text| line_height(text.font_id, text.scale))
+            .fold(0.0, f32::max);
+    }
+
+    let mut lines: Vec<(f32, f32)> = Vec::new();
+
+    for glyph in glyphs {
+        let height = line_height(glyph.font_id, glyph.glyph.scale);
+
+        match lines.iter_mut().find(|(y, _)| *y == glyph.glyph.position.y) {
+            Some((_, line)) => *line = line.max(height),
+            None => lines.push((glyph.glyph.position.y, height)),
+        }
+    }
+
+    lines.iter().map(|(_, height)| height).sum()
+}
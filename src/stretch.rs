@@ -0,0 +1,103 @@
+use glyph_brush::ab_glyph::{Font, PxScale, Rect};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, Layout, SectionGeometry, SectionGlyph,
+    SectionText, ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+
+/// A [`GlyphPositioner`](trait.GlyphPositioner.html) that stretches the
+/// text of a section horizontally.
+///
+/// The horizontal scale of the texts is multiplied by their stretch factor,
+/// synthesizing condensed (below `1.0`) or expanded (above `1.0`) widths
+/// without changing the font. Both the advances used by the inner layout and
+/// the rasterized glyphs are stretched, so stretched text wraps and fits
+/// fixed-width columns as expected.
+///
+/// Use it with
+/// [`queue_custom_layout`](struct.GlyphBrush.html#method.queue_custom_layout).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stretch<L = Layout<BuiltInLineBreaker>> {
+    layout: L,
+    factor: f32,
+    text_factors: Vec<(usize, f32)>,
+}
+
+impl<L> Stretch<L> {
+    /// Wraps the given layout, without stretching the text.
+    pub fn new(layout: L) -> Self {
+        Stretch {
+            layout,
+            factor: 1.0,
+            text_factors: Vec::new(),
+        }
+    }
+
+    /// Sets the stretch factor of all the texts of the section.
+    pub fn factor(mut self, factor: f32) -> Self {
+        self.factor = factor;
+        self
+    }
+
+    /// Sets the stretch factor of the text with the given index in the
+    /// section, overriding the factor of the section.
+    pub fn text_factor(mut self, index: usize, factor: f32) -> Self {
+        self.text_factors.retain(|(i, _)| *i != index);
+        self.text_factors.push((index, factor));
+        self
+    }
+
+    /// Returns the stretch factor of the text with the given index.
+    pub fn factor_of(&self, index: usize) -> f32 {
+        self.text_factors
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map_or(self.factor, |(_, factor)| *factor)
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Stretch<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let sections: Vec<SectionText<'_>> = sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                let text = section.to_section_text();
+
+                SectionText {
+                    scale: PxScale {
+                        x: text.scale.x * self.factor_of(index),
+                        y: text.scale.y,
+                    },
+                    ..text
+                }
+            })
+            .collect();
+
+        self.layout.calculate_glyphs(fonts, geometry, &sections)
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for Stretch<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.factor.to_bits().hash(state);
+
+        for (index, factor) in &self.text_factors {
+            (index, factor.to_bits()).hash(state);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::ab_glyph::{Font, Glyph, GlyphImageFormat, PxScale, ScaleFont};
+
+/// How the strike a bitmap glyph is drawn from is chosen, for fonts with
+/// bitmaps of multiple sizes, like the PNG images of some emoji fonts.
+///
+/// The bitmap of the strike is uploaded as is and scaled to the size of the
+/// glyph when drawn, so glyphs of any size drawn from the same strike share
+/// the same region of the atlas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StrikePolicy {
+    /// The strike closest to the size of the glyph, which is scaled up or
+    /// down the least.
+    Nearest,
+    /// The smallest strike at least as large as the glyph, which is only
+    /// scaled up if the font has no larger strike.
+    #[default]
+    NearestLarger,
+    /// The largest strike, which is always scaled down unless the glyph is
+    /// larger, for the sharpest result at the cost of larger uploads.
+    ScaleDown,
+}
+
+impl StrikePolicy {
+    /// Returns the pixels per em of the strike the bitmap of a glyph is drawn
+    /// from, if it has a PNG bitmap, e.g. to draw bitmap glyphs from a
+    /// [custom atlas](struct.GlyphBrush.html#method.set_atlas).
+    pub fn strike<F: Font>(self, font: &F, glyph: &Glyph) -> Option<u16> {
+        let size = pixels_per_em(font, glyph.scale);
+        let requested = size.ceil().clamp(1.0, f32::from(u16::MAX)) as u16;
+
+        // Fonts return the smallest strike at least as large as the given
+        // size, or the largest one
+        let strike = |size: u16| {
+            font.glyph_raster_image2(glyph.id, size)
+                .filter(|image| matches!(image.format, GlyphImageFormat::Png))
+                .map(|image| image.pixels_per_em)
+        };
+
+        match self {
+            StrikePolicy::NearestLarger => strike(requested),
+            StrikePolicy::ScaleDown => strike(u16::MAX),
+            StrikePolicy::Nearest => {
+                let larger = strike(requested)?;
+
+                if larger < requested || strike(1)? >= requested {
+                    return Some(larger);
+                }
+
+                // Searches the largest size whose strike is smaller than the
+                // requested size, which is the largest smaller strike
+                let (mut low, mut high) = (1, requested - 1);
+
+                while low < high {
+                    let middle = low + (high - low).div_ceil(2);
+
+                    if strike(middle)? < requested {
+                        low = middle;
+                    } else {
+                        high = middle - 1;
+                    }
+                }
+
+                let smaller = strike(low)?;
+
+                if size - f32::from(smaller) < f32::from(larger) - size {
+                    Some(smaller)
+                } else {
+                    Some(larger)
+                }
+            }
+        }
+    }
+}
+
+/// Returns the pixels per em of a font at the given scale.
+pub(crate) fn pixels_per_em<F: Font>(font: &F, scale: PxScale) -> f32 {
+    font.as_scaled(scale).v_scale_factor() * font.units_per_em().unwrap_or(1.0)
+}
@@ -0,0 +1,151 @@
+use crate::ab_glyph::{point, Rect};
+use crate::{Extra, SectionGlyph};
+
+/// An outline drawn around the glyphs of a section, like the lettering of
+/// comics or subtitles over video.
+///
+/// The outline is drawn with copies of the glyphs moved around them by
+/// whole pixels, so it is meant for outlines of a few pixels.
+///
+/// Queue it with
+/// [`GlyphBrush::queue_with_stroke`](struct.GlyphBrush.html#method.queue_with_stroke).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    /// The color of the outline.
+    pub color: [f32; 4],
+    /// The width of the outline, in pixels, rounded up to whole pixels.
+    pub width: f32,
+    /// The order in which the outlines and the glyphs are drawn.
+    pub order: StrokeOrder,
+}
+
+/// The order in which the outlines of a [`Stroke`] and the glyphs they
+/// surround are drawn, which only matters when glyphs overlap, e.g. in
+/// tightly set italic text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StrokeOrder {
+    /// The outlines of all the glyphs are drawn before any glyph, so an
+    /// outline never covers a glyph and overlapping outlines merge without
+    /// seams, like in comic lettering.
+    #[default]
+    StrokesFirst,
+    /// The outline of every glyph is drawn right before the glyph, so every
+    /// glyph stands out from the ones before it.
+    PerGlyph,
+}
+
+impl Stroke {
+    /// Creates a [`Stroke`] with the given color and width, drawing the
+    /// outlines of all the glyphs first.
+    pub fn new(color: [f32; 4], width: f32) -> Self {
+        Stroke {
+            color,
+            width,
+            order: StrokeOrder::default(),
+        }
+    }
+
+    /// Sets the order in which the outlines and the glyphs are drawn.
+    pub fn with_order(mut self, order: StrokeOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Returns the glyphs laid out from a section with the copies drawing
+    /// their outline, in drawing order, with the colors and depths of the
+    /// texts of the section followed by the ones of their outlines, and
+    /// their bounds grown to fit the outlines.
+    pub(crate) fn glyphs(
+        &self,
+        glyphs: Vec<SectionGlyph>,
+        mut extra: Vec<Extra>,
+        bounds: Rect,
+    ) -> (Vec<SectionGlyph>, Vec<Extra>, Rect) {
+        let offsets = self.offsets();
+        let texts = extra.len();
+
+        let outlines: Vec<Extra> = extra
+            .iter()
+            .map(|text| Extra {
+                color: self.color,
+                z: text.z,
+            })
+            .collect();
+
+        extra.extend(outlines);
+
+        let outline = |glyph: &SectionGlyph,
+                       stroked: &mut Vec<SectionGlyph>| {
+            stroked.extend(offsets.iter().map(|&(x, y)| {
+                let mut copy = glyph.clone();
+
+                copy.section_index += texts;
+                copy.glyph.position.x += x;
+                copy.glyph.position.y += y;
+                copy
+            }));
+        };
+
+        let mut stroked =
+            Vec::with_capacity(glyphs.len() * (offsets.len() + 1));
+
+        match self.order {
+            StrokeOrder::StrokesFirst => {
+                for glyph in &glyphs {
+                    outline(glyph, &mut stroked);
+                }
+
+                stroked.extend(glyphs);
+            }
+            StrokeOrder::PerGlyph => {
+                for glyph in glyphs {
+                    outline(&glyph, &mut stroked);
+                    stroked.push(glyph);
+                }
+            }
+        }
+
+        let radius = self.radius() as f32;
+
+        let bounds = Rect {
+            min: point(bounds.min.x - radius, bounds.min.y - radius),
+            max: point(bounds.max.x + radius, bounds.max.y + radius),
+        };
+
+        (stroked, extra, bounds)
+    }
+
+    fn radius(&self) -> u32 {
+        self.width.max(0.0).ceil() as u32
+    }
+
+    /// Returns the offsets of the copies of a glyph drawing its outline, in
+    /// eight directions at every whole pixel up to the width of the outline,
+    /// so the glyph keeps its subpixel position and cached rasterization.
+    fn offsets(&self) -> Vec<(f32, f32)> {
+        let mut offsets: Vec<(f32, f32)> = (1..=self.radius())
+            .flat_map(|radius| {
+                let radius = radius as f32;
+                let diagonal =
+                    (radius * std::f32::consts::FRAC_1_SQRT_2).round();
+
+                [
+                    (radius, 0.0),
+                    (-radius, 0.0),
+                    (0.0, radius),
+                    (0.0, -radius),
+                    (diagonal, diagonal),
+                    (diagonal, -diagonal),
+                    (-diagonal, diagonal),
+                    (-diagonal, -diagonal),
+                ]
+            })
+            .collect();
+
+        // Small diagonals round to the same offsets
+        offsets.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        offsets.dedup();
+
+        offsets
+    }
+}
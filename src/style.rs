@@ -0,0 +1,145 @@
+use crate::palette::PALETTE_FLAG;
+use crate::transform_slot::{SLOT_SHIFT, TRANSFORM_SLOTS};
+use crate::{Extra, Section, Text};
+
+/// The bit of the atlas of an [`Instance`](struct.Instance.html) that
+/// samples the glyph cache with nearest filtering.
+pub(crate) const NEAREST_FLAG: u32 = 1 << 16;
+
+/// How the glyphs of a section are drawn, besides the color and depth of
+/// their text.
+///
+/// Set it for the sections queued afterwards with
+/// [`GlyphBrush::set_glyph_style`](struct.GlyphBrush.html#method.set_glyph_style),
+/// or apply it to custom instances with
+/// [`Instance::with_style`](struct.Instance.html#method.with_style).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GlyphStyle {
+    /// Whether the glyph cache is sampled with nearest filtering, drawing
+    /// the glyphs crisp and unfiltered like the ones of pixel-art bitmap
+    /// fonts, regardless of the filter mode of the brush.
+    pub nearest_filtered: bool,
+    /// The index of the palette entry the color of the glyphs is multiplied
+    /// by, if any, so white text is drawn with the current color of the
+    /// entry.
+    ///
+    /// Palette colors are set with
+    /// [`GlyphBrush::set_palette_color`](struct.GlyphBrush.html#method.set_palette_color),
+    /// so themes can be switched without queuing the text again.
+    pub palette: Option<u8>,
+    /// The transform slot the glyphs are drawn with, lower than
+    /// [`TRANSFORM_SLOTS`](constant.TRANSFORM_SLOTS.html).
+    ///
+    /// The glyphs are transformed by the matrix set for the slot with
+    /// [`GlyphBrush::set_transform_slot`](struct.GlyphBrush.html#method.set_transform_slot)
+    /// instead of the transform of the draw call, so texts in a few
+    /// coordinate spaces, like a world HUD and a screen HUD, are drawn
+    /// together. The slot `0` is the transform of the draw call.
+    pub transform_slot: u8,
+}
+
+impl GlyphStyle {
+    /// Sets whether the glyph cache is sampled with nearest filtering.
+    pub fn with_nearest_filtered(mut self, nearest_filtered: bool) -> Self {
+        self.nearest_filtered = nearest_filtered;
+        self
+    }
+
+    /// Sets the index of the palette entry the color of the glyphs is
+    /// multiplied by.
+    pub fn with_palette(mut self, index: u8) -> Self {
+        self.palette = Some(index);
+        self
+    }
+
+    /// Sets the transform slot the glyphs are drawn with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slot is not lower than
+    /// [`TRANSFORM_SLOTS`](constant.TRANSFORM_SLOTS.html).
+    pub fn with_transform_slot(mut self, slot: u8) -> Self {
+        assert!(
+            usize::from(slot) < TRANSFORM_SLOTS,
+            "Invalid transform slot: {slot}"
+        );
+
+        self.transform_slot = slot;
+        self
+    }
+
+    /// Returns the bits of the atlas of an instance drawn with the style.
+    pub(crate) fn atlas_bits(self) -> u32 {
+        let nearest = if self.nearest_filtered {
+            NEAREST_FLAG
+        } else {
+            0
+        };
+        let palette = self
+            .palette
+            .map_or(0, |index| PALETTE_FLAG | u32::from(index) << 8);
+        let slot = u32::from(self.transform_slot)
+            .min(TRANSFORM_SLOTS as u32 - 1)
+            << SLOT_SHIFT;
+
+        nearest | palette | slot
+    }
+}
+
+/// The extra data of the texts queued to the `glyph_brush` of a
+/// `GlyphBrush`: their color and depth, and the style of their section.
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub(crate) struct Styled {
+    pub(crate) extra: Extra,
+    pub(crate) style: GlyphStyle,
+}
+
+/// A buffer the texts of sections are styled in before being queued to the
+/// `glyph_brush` of a `GlyphBrush`, reused so styling them does not
+/// allocate.
+#[derive(Debug, Default)]
+pub(crate) struct Scratch(Vec<Text<'static, Styled>>);
+
+impl Scratch {
+    /// Calls `f` with the section with its texts drawn with the given style.
+    pub(crate) fn styled<R>(
+        &mut self,
+        section: &Section<'_>,
+        style: GlyphStyle,
+        f: impl FnOnce(&Section<'_, Styled>) -> R,
+    ) -> R {
+        let mut text = recycle(std::mem::take(&mut self.0));
+
+        text.extend(section.text.iter().map(|text| Text {
+            text: text.text,
+            scale: text.scale,
+            font_id: text.font_id,
+            extra: Styled {
+                extra: text.extra,
+                style,
+            },
+        }));
+
+        let styled = Section {
+            screen_position: section.screen_position,
+            bounds: section.bounds,
+            layout: section.layout,
+            text,
+        };
+
+        let result = f(&styled);
+
+        self.0 = recycle(styled.text);
+
+        result
+    }
+}
+
+/// Empties a buffer of texts, keeping its allocation for texts borrowing
+/// another lifetime.
+fn recycle<'b>(mut texts: Vec<Text<'_, Styled>>) -> Vec<Text<'b, Styled>> {
+    texts.clear();
+
+    // Collecting an empty iterator of the same layout reuses the buffer
+    texts.into_iter().map(|_| unreachable!()).collect()
+}
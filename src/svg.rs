@@ -0,0 +1,529 @@
+use crate::ab_glyph::{
+    point, Font, Glyph, GlyphId, GlyphImageFormat, Rect, ScaleFont,
+};
+use crate::pipeline::{Cache, Pipeline};
+use crate::strike::{pixels_per_em, StrikePolicy};
+use crate::style::Styled;
+use crate::{Extra, GlyphStyle, Instance, SectionGlyph};
+
+use glyph_brush::{FontId, GlyphPositioner, SectionGeometry, ToSectionText};
+use log::warn;
+use resvg::{tiny_skia, usvg};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// The size of the atlas page of the SVG glyphs, in pixels.
+const PAGE_SIZE: u32 = 1024;
+
+/// The color glyphs of a `GlyphBrush`, OT-SVG documents rasterized and PNG
+/// bitmaps decoded into an RGBA atlas page.
+///
+/// Glyphs are packed in rows and kept until the page is full. Then, the
+/// glyphs that do not fit are skipped for a frame and the page is cleared,
+/// so the next frame packs only the glyphs in use.
+pub(crate) struct SvgGlyphs {
+    atlas: usize,
+    /// The transparent space left around every glyph in the page, in
+    /// pixels, so neighbors do not bleed into each other when filtered.
+    padding: u32,
+    strike_policy: StrikePolicy,
+    page: Option<Cache>,
+    glyphs: HashMap<Key, Option<Entry>>,
+    /// The regions of the glyphs rasterized since the last flush, with their
+    /// padding, and their pixels.
+    pending: Vec<([u16; 2], [u16; 2], Vec<u8>)>,
+    row: Row,
+    full: bool,
+}
+
+/// A glyph rasterized at a specific size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    font_id: FontId,
+    glyph_id: GlyphId,
+    size: Size,
+}
+
+/// The size a glyph is rasterized at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Size {
+    /// The scale of an SVG glyph.
+    Scale(u32, u32),
+    /// The pixels per em of the strike of a bitmap glyph.
+    Strike(u16),
+}
+
+/// The region of a glyph in the page.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    offset: [u16; 2],
+    size: [u16; 2],
+    /// The position of the top-left corner of the glyph relative to its
+    /// origin, in pixels.
+    origin: (f32, f32),
+    /// The pixels per em of the strike of a bitmap glyph, whose size and
+    /// origin are in pixels of the strike.
+    strike: Option<u16>,
+}
+
+/// The row of the page glyphs are currently packed into.
+#[derive(Debug, Clone, Copy, Default)]
+struct Row {
+    x: u32,
+    y: u32,
+    height: u32,
+}
+
+impl SvgGlyphs {
+    pub(crate) fn new(
+        atlas: usize,
+        pad_glyphs: bool,
+        strike_policy: StrikePolicy,
+    ) -> SvgGlyphs {
+        SvgGlyphs {
+            atlas,
+            padding: u32::from(pad_glyphs),
+            strike_policy,
+            page: None,
+            glyphs: HashMap::new(),
+            pending: Vec::new(),
+            row: Row::default(),
+            full: false,
+        }
+    }
+
+    /// Returns the instances of the color glyphs among the given glyphs laid
+    /// out from texts with the given colors and depths, drawn with the given
+    /// style, rasterizing the ones missing from the page.
+    pub(crate) fn instances<F: Font>(
+        &mut self,
+        fonts: &[F],
+        extra: &[Extra],
+        glyphs: &[SectionGlyph],
+        bounds: Rect,
+        style: GlyphStyle,
+    ) -> Vec<Instance> {
+        let mut instances = Vec::new();
+
+        for glyph in glyphs {
+            let font = &fonts[glyph.font_id.0];
+
+            // SVG documents take precedence over bitmaps
+            let strike = match font.glyph_svg_image(glyph.glyph.id) {
+                Some(_) => None,
+                None => self.strike_policy.strike(font, &glyph.glyph),
+            };
+
+            let key = Key {
+                font_id: glyph.font_id,
+                glyph_id: glyph.glyph.id,
+                size: match strike {
+                    Some(strike) => Size::Strike(strike),
+                    None => Size::Scale(
+                        glyph.glyph.scale.x.to_bits(),
+                        glyph.glyph.scale.y.to_bits(),
+                    ),
+                },
+            };
+
+            let entry = match self.glyphs.get(&key) {
+                Some(entry) => *entry,
+                None => {
+                    let entry = match strike {
+                        Some(strike) => {
+                            self.decode(font, glyph.glyph.id, strike)
+                        }
+                        None => self.rasterize(font, &glyph.glyph),
+                    };
+
+                    // The page is full, so the glyph is tried again once it
+                    // is cleared
+                    if entry.is_none() && self.full {
+                        continue;
+                    }
+
+                    let _ = self.glyphs.insert(key, entry);
+                    entry
+                }
+            };
+
+            let Some(entry) = entry else {
+                continue;
+            };
+
+            let scale = match entry.strike {
+                Some(strike) => {
+                    pixels_per_em(font, glyph.glyph.scale) / f32::from(strike)
+                }
+                None => 1.0,
+            };
+
+            let position = glyph.glyph.position;
+            let left = position.x + entry.origin.0 * scale;
+            let top = position.y + entry.origin.1 * scale;
+
+            let extra = &extra[glyph.section_index];
+
+            // Color glyphs have their own colors, so only the alpha of the
+            // text is kept, without its palette color
+            let vertex = glyph_brush::GlyphVertex {
+                tex_coords: Rect {
+                    min: point(
+                        f32::from(entry.offset[0]) / PAGE_SIZE as f32,
+                        f32::from(entry.offset[1]) / PAGE_SIZE as f32,
+                    ),
+                    max: point(
+                        f32::from(entry.offset[0] + entry.size[0])
+                            / PAGE_SIZE as f32,
+                        f32::from(entry.offset[1] + entry.size[1])
+                            / PAGE_SIZE as f32,
+                    ),
+                },
+                pixel_coords: Rect {
+                    min: point(left, top),
+                    max: point(
+                        left + f32::from(entry.size[0]) * scale,
+                        top + f32::from(entry.size[1]) * scale,
+                    ),
+                },
+                bounds,
+                extra: &Styled {
+                    extra: Extra {
+                        color: [1.0, 1.0, 1.0, extra.color[3]],
+                        z: extra.z,
+                    },
+                    style: GlyphStyle {
+                        palette: None,
+                        ..style
+                    },
+                },
+            };
+
+            instances.push(
+                Instance::from_vertex(vertex).with_atlas(self.atlas as u32),
+            );
+        }
+
+        instances
+    }
+
+    /// Uploads the glyphs rasterized since the last call, creating the page
+    /// on first use, and clears the page if it got full.
+    pub(crate) fn flush<D>(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &mut Pipeline<D>,
+    ) {
+        if !self.pending.is_empty() {
+            let page = self.page.get_or_insert_with(|| {
+                let page = Cache::with_format(
+                    device,
+                    "wgpu_glyph::svg",
+                    PAGE_SIZE,
+                    PAGE_SIZE,
+                    wgpu::TextureFormat::Rgba8UnormSrgb,
+                );
+
+                pipeline.bind_atlas_cache(device, self.atlas, &page);
+
+                page
+            });
+
+            for (offset, size, data) in self.pending.drain(..) {
+                page.update(offset, size, &data);
+            }
+
+            page.flush(device, staging_belt, encoder);
+        }
+
+        if self.full {
+            self.glyphs.clear();
+            self.row = Row::default();
+            self.full = false;
+        }
+    }
+
+    /// Returns the amount of bytes of GPU memory used by the page.
+    pub(crate) fn gpu_bytes(&self) -> u64 {
+        self.page.as_ref().map_or(0, Cache::gpu_bytes)
+    }
+
+    /// Returns the amount of bytes of CPU memory used by the copy of the
+    /// page, the entries of the glyphs and the glyphs not uploaded yet.
+    pub(crate) fn cpu_bytes(&self) -> u64 {
+        let pending: usize = self
+            .pending
+            .iter()
+            .map(|(_, _, data)| data.capacity())
+            .sum();
+
+        self.page.as_ref().map_or(0, Cache::cpu_bytes)
+            + (self.glyphs.capacity()
+                * std::mem::size_of::<(Key, Option<Entry>)>()
+                + pending) as u64
+    }
+
+    /// Rasterizes the SVG document of a glyph into the page, returning its
+    /// entry, or `None` if it has nothing to draw or the page is full.
+    fn rasterize<F: Font>(&mut self, font: &F, glyph: &Glyph) -> Option<Entry> {
+        let svg = font.glyph_svg_image(glyph.id)?;
+        let tree =
+            usvg::Tree::from_data(svg.data, &usvg::Options::default()).ok()?;
+
+        // A document may contain the glyphs of a whole range, identified by
+        // the `glyph<id>` element.
+        let node = tree.node_by_id(&format!("glyph{}", glyph.id.0));
+
+        let bounds = match node {
+            Some(node) => node.abs_layer_bounding_box()?,
+            None if svg.start_glyph_id == svg.end_glyph_id => {
+                tree.root().abs_layer_bounding_box()
+            }
+            None => return None,
+        };
+
+        // The document is in font units, with its origin at the origin of
+        // the glyph and the y axis pointing down.
+        let font = font.as_scaled(glyph.scale);
+        let (scale_x, scale_y) = (font.h_scale_factor(), font.v_scale_factor());
+
+        let left = (bounds.left() * scale_x).floor();
+        let top = (bounds.top() * scale_y).floor();
+        let width = (bounds.right() * scale_x).ceil() - left;
+        let height = (bounds.bottom() * scale_y).ceil() - top;
+
+        if width < 1.0 || height < 1.0 {
+            return None;
+        }
+
+        let (width, height) = (width as u32, height as u32);
+        let padding = self.padding;
+
+        let offset = self.allocate_padded(width, height)?;
+
+        let mut pixmap =
+            tiny_skia::Pixmap::new(width + 2 * padding, height + 2 * padding)?;
+
+        let left_padded = left - padding as f32;
+        let top_padded = top - padding as f32;
+
+        match node {
+            Some(node) => {
+                let transform = tiny_skia::Transform::from_row(
+                    scale_x,
+                    0.0,
+                    0.0,
+                    scale_y,
+                    bounds.x() * scale_x - left_padded,
+                    bounds.y() * scale_y - top_padded,
+                );
+
+                resvg::render_node(node, transform, &mut pixmap.as_mut())?;
+            }
+            None => {
+                let transform = tiny_skia::Transform::from_row(
+                    scale_x,
+                    0.0,
+                    0.0,
+                    scale_y,
+                    -left_padded,
+                    -top_padded,
+                );
+
+                resvg::render(&tree, transform, &mut pixmap.as_mut());
+            }
+        }
+
+        Some(self.store(offset, &pixmap, (left, top), None))
+    }
+
+    /// Decodes the PNG bitmap of a glyph from the given strike into the
+    /// page, returning its entry, or `None` if it is invalid or the page is
+    /// full.
+    fn decode<F: Font>(
+        &mut self,
+        font: &F,
+        id: GlyphId,
+        strike: u16,
+    ) -> Option<Entry> {
+        let image = font.glyph_raster_image2(id, strike)?;
+        let bitmap = tiny_skia::Pixmap::decode_png(image.data).ok()?;
+
+        let (width, height) = (bitmap.width(), bitmap.height());
+        let padding = self.padding;
+
+        let offset = self.allocate_padded(width, height)?;
+
+        let mut pixmap =
+            tiny_skia::Pixmap::new(width + 2 * padding, height + 2 * padding)?;
+
+        pixmap.draw_pixmap(
+            padding as i32,
+            padding as i32,
+            bitmap.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            tiny_skia::Transform::identity(),
+            None,
+        );
+
+        // The origin of the bitmap is its bottom-left corner, with the y axis
+        // pointing up
+        let origin = (image.origin.x, -(image.origin.y + height as f32));
+
+        Some(self.store(offset, &pixmap, origin, Some(image.pixels_per_em)))
+    }
+
+    /// Allocates a region of the page for a glyph of the given size and its
+    /// padding, marking the page as full if it does not fit.
+    fn allocate_padded(&mut self, width: u32, height: u32) -> Option<[u32; 2]> {
+        let padding = self.padding;
+
+        let offset = self.allocate(width + 2 * padding, height + 2 * padding);
+
+        if offset.is_none() {
+            if !self.full && log::log_enabled!(log::Level::Warn) {
+                warn!(
+                    "The color glyph page is full, clearing it on the next \
+                     draw"
+                );
+            }
+
+            self.full = true;
+        }
+
+        offset
+    }
+
+    /// Queues the padded pixels of a glyph for upload into its region of the
+    /// page, returning its entry.
+    fn store(
+        &mut self,
+        offset: [u32; 2],
+        pixmap: &tiny_skia::Pixmap,
+        origin: (f32, f32),
+        strike: Option<u16>,
+    ) -> Entry {
+        let padding = self.padding;
+
+        let data = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+
+        self.pending.push((
+            [offset[0] as u16, offset[1] as u16],
+            [pixmap.width() as u16, pixmap.height() as u16],
+            data,
+        ));
+
+        Entry {
+            offset: [
+                (offset[0] + padding) as u16,
+                (offset[1] + padding) as u16,
+            ],
+            size: [
+                (pixmap.width() - 2 * padding) as u16,
+                (pixmap.height() - 2 * padding) as u16,
+            ],
+            origin,
+            strike,
+        }
+    }
+
+    /// Allocates a region of the given size in the page.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<[u32; 2]> {
+        if width > PAGE_SIZE || height > PAGE_SIZE {
+            return None;
+        }
+
+        if self.row.x + width > PAGE_SIZE {
+            self.row = Row {
+                x: 0,
+                y: self.row.y + self.row.height,
+                height: 0,
+            };
+        }
+
+        if self.row.y + height > PAGE_SIZE {
+            return None;
+        }
+
+        let offset = [self.row.x, self.row.y];
+
+        self.row.x += width;
+        self.row.height = self.row.height.max(height);
+
+        Some(offset)
+    }
+}
+
+/// A [`GlyphPositioner`] that leaves out the glyphs with an SVG document or
+/// a PNG bitmap, so their outlines are not drawn under them.
+#[derive(Debug)]
+pub(crate) struct Layout<L> {
+    layout: L,
+    hide: bool,
+}
+
+impl<L> Layout<L> {
+    pub(crate) fn new(layout: L, hide: bool) -> Self {
+        Layout { layout, hide }
+    }
+}
+
+impl<L: GlyphPositioner> GlyphPositioner for Layout<L> {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs =
+            self.layout.calculate_glyphs(fonts, geometry, sections);
+
+        if self.hide {
+            remove_color_glyphs(fonts, &mut glyphs);
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl<L: Hash> Hash for Layout<L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.hide.hash(state);
+    }
+}
+
+/// Removes the color glyphs drawn by the `SvgGlyphs` of a `GlyphBrush` from
+/// glyphs to be drawn from the glyph cache.
+pub(crate) fn remove_color_glyphs<F: Font>(
+    fonts: &[F],
+    glyphs: &mut Vec<SectionGlyph>,
+) {
+    glyphs.retain(|glyph| {
+        let font = &fonts[glyph.font_id.0];
+        let id = glyph.glyph.id;
+
+        font.glyph_svg_image(id).is_none()
+            && !font.glyph_raster_image2(id, u16::MAX).is_some_and(|image| {
+                matches!(image.format, GlyphImageFormat::Png)
+            })
+    });
+}
@@ -0,0 +1,7 @@
+/// The amount of transform slots of a `GlyphBrush`, including the slot `0`
+/// of the transform of the draw call.
+pub const TRANSFORM_SLOTS: usize = 8;
+
+/// The lowest bit of the transform slot in the atlas of an
+/// [`Instance`](struct.Instance.html).
+pub(crate) const SLOT_SHIFT: u32 = 28;
@@ -0,0 +1,54 @@
+/// A viewport of a render target, mapping the coordinates of the sections
+/// drawn into it.
+///
+/// Used with
+/// [`GlyphBrush::draw_queued_with_viewport`](struct.GlyphBrush.html#method.draw_queued_with_viewport).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Viewport {
+    /// The left side of the viewport, in pixels of the render target.
+    pub x: f32,
+    /// The top side of the viewport, in pixels of the render target.
+    pub y: f32,
+    /// The width of the viewport, in pixels.
+    pub width: f32,
+    /// The height of the viewport, in pixels.
+    pub height: f32,
+    /// The depth the near plane is mapped to, usually `0.0`.
+    pub min_depth: f32,
+    /// The depth the far plane is mapped to, usually `1.0`.
+    pub max_depth: f32,
+}
+
+impl Viewport {
+    /// Creates a viewport with the given position and size, in pixels, and
+    /// the full depth range.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Viewport {
+        Viewport {
+            x,
+            y,
+            width,
+            height,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        }
+    }
+
+    /// Returns whether the viewport covers no pixels, e.g. the viewport of a
+    /// minimized window. Nothing is drawn into an empty viewport.
+    pub fn is_empty(&self) -> bool {
+        !(self.width > 0.0 && self.height > 0.0)
+    }
+
+    /// Returns the orthographic projection of the viewport, with coordinates
+    /// relative to its top-left corner.
+    #[rustfmt::skip]
+    pub(crate) fn projection(&self) -> [f32; 16] {
+        [
+            2.0 / self.width, 0.0, 0.0, 0.0,
+            0.0, -2.0 / self.height, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            -1.0, 1.0, 0.0, 1.0,
+        ]
+    }
+}